@@ -20,6 +20,13 @@
 //! - [`Connection`]: Represents a link from an affordance to places on the breadboard.
 //! - [`Sketch`]: A graphical representation associated with a place, including clickable areas.
 //! - [`Area`]: A specific clickable area within a `Sketch`.
+//! - [`Flow`]: A named user journey through the breadboard, made up of [`Step`]s.
+//!
+//! With the `schemars` feature enabled, all types implement `schemars::JsonSchema`, which is used
+//! to generate the JSON Schema of the serialized breadboard format.
+//!
+//! Beyond the data model, the [`validate`] module checks a [`Breadboard`] for semantic problems
+//! that the parser cannot detect on its own, such as connections to places that do not exist.
 //!
 //! ## Usage
 //!
@@ -35,33 +42,204 @@
 //!
 //! // Create a new breadboard instance
 //! let breadboard = Breadboard {
+//!     imports: vec![],
 //!     places: vec![],
 //!     components: vec![],
+//!     flows: vec![],
 //! };
 //!
 //! // Add places and components as needed
 //! // ...
 //! ```
 
-use std::ops::Deref;
+pub mod validate;
+
+use std::{
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+};
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+pub use validate::Violation;
+
 /// The root node of the [Breadboard], containing [`Place`]s and [`Component`]s.
 ///
 /// [Breadboard]: https://basecamp.com/shapeup/1.3-chapter-04
 #[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Breadboard {
+    /// The files imported by this breadboard, relative to the file that imports them.
+    ///
+    /// Imports are resolved when loading a breadboard from the file system, which merges the
+    /// places and components of all imported files into a single breadboard.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<PathBuf>,
+
     /// A vector of `Place` instances, representing different locations on the breadboard.
     pub places: Vec<Place>,
 
     /// A vector of `Component` instances, defining the grouped affordances shared across the
     /// breadboard.
     pub components: Vec<Component>,
+
+    /// Named user journeys through the breadboard, such as "new user signs up".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flows: Vec<Flow>,
+}
+
+impl Breadboard {
+    /// Returns the [`Place`] with the given name, if any.
+    #[must_use]
+    pub fn place(&self, name: &str) -> Option<&Place> {
+        self.places.iter().find(|place| place.name == name)
+    }
+
+    /// Returns the [`Component`] with the given name, if any.
+    #[must_use]
+    pub fn component(&self, name: &str) -> Option<&Component> {
+        self.components
+            .iter()
+            .find(|component| component.name == name)
+    }
+
+    /// Returns the [`Flow`] with the given name, if any.
+    #[must_use]
+    pub fn flow(&self, name: &str) -> Option<&Flow> {
+        self.flows.iter().find(|flow| flow.name == name)
+    }
+
+    /// Returns the affordances of the given place (or component), with any [`Reference`]s to
+    /// components expanded in-place.
+    ///
+    /// The nesting level of expanded affordances is increased by the level of the reference that
+    /// included them. References to unknown components, and references that would recursively
+    /// include a component into itself, are skipped.
+    #[must_use]
+    pub fn affordances(&self, place: &Place) -> Vec<Affordance> {
+        let mut affordances = vec![];
+        let mut stack = vec![place.name.as_str()];
+        self.collect_affordances(place, 0, &mut stack, &mut affordances);
+
+        affordances
+    }
+
+    /// Returns the affordances of the given place (or component) like
+    /// [`affordances`](Self::affordances), with affordances that share a name combined into one.
+    ///
+    /// Nested or included affordances can share a name, but are the same affordance. Each combined
+    /// affordance is the first one with its name, with the connections of all of them.
+    #[must_use]
+    pub fn unique_affordances(&self, place: &Place) -> Vec<Affordance> {
+        let mut affordances: Vec<Affordance> = vec![];
+
+        for affordance in self.affordances(place) {
+            let Some(first) = affordances.iter_mut().find(|a| a.name == affordance.name) else {
+                affordances.push(affordance);
+                continue;
+            };
+
+            for connection in affordance.connections {
+                if !first.connections.contains(&connection) {
+                    first.connections.push(connection);
+                }
+            }
+        }
+
+        affordances
+    }
+
+    /// Returns `true` if the given place (or component) includes the component named `component`,
+    /// directly or through the components it includes.
+    #[must_use]
+    pub fn includes(&self, place: &Place, component: &str) -> bool {
+        self.includes_from(place, component, &mut vec![])
+    }
+
+    fn includes_from<'a>(
+        &'a self,
+        place: &Place,
+        component: &str,
+        seen: &mut Vec<&'a str>,
+    ) -> bool {
+        place.items.iter().any(|item| {
+            let Item::Reference(reference) = item else {
+                return false;
+            };
+
+            if reference.name == component {
+                return true;
+            }
+
+            let Some(included) = self.component(&reference.name) else {
+                return false;
+            };
+            if seen.contains(&included.name.as_str()) {
+                return false;
+            }
+            seen.push(&included.name);
+
+            self.includes_from(included, component, seen)
+        })
+    }
+
+    fn collect_affordances<'a>(
+        &'a self,
+        place: &'a Place,
+        root_level: usize,
+        stack: &mut Vec<&'a str>,
+        affordances: &mut Vec<Affordance>,
+    ) {
+        for item in &place.items {
+            match item {
+                Item::Affordance(affordance) => {
+                    let mut affordance = affordance.clone();
+                    affordance.level += root_level;
+                    affordances.push(affordance);
+                }
+                Item::Reference(reference) => {
+                    if stack.contains(&reference.name.as_str()) {
+                        continue;
+                    }
+
+                    let Some(component) = self.component(&reference.name) else {
+                        continue;
+                    };
+
+                    stack.push(&component.name);
+                    self.collect_affordances(
+                        component,
+                        root_level + reference.level,
+                        stack,
+                        affordances,
+                    );
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    /// Validates the breadboard, returning all [`Violation`]s found.
+    ///
+    /// An empty list means the breadboard is valid. See the [`validate`] module for details on
+    /// the checks performed.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Violation> {
+        self.validate_with(&validate::Config::default())
+    }
+
+    /// Validates the breadboard with the given [`validate::Config`], returning all [`Violation`]s
+    /// found.
+    #[must_use]
+    pub fn validate_with(&self, config: &validate::Config) -> Vec<Violation> {
+        validate::validate(self, config)
+    }
 }
 
 /// Represents a specific place or location on the breadboard.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Place {
     /// A unique identifier for the place.
     pub name: String,
@@ -69,6 +247,10 @@ pub struct Place {
     /// An optional description added to the place.
     pub description: Vec<String>,
 
+    /// Key/value metadata of the place, such as its `owner`, `status` or `ticket`.
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+
     /// A list of [`Item]` elements contained in the place.
     pub items: Vec<Item>,
 
@@ -81,10 +263,20 @@ pub struct Place {
 
     /// An optional `Sketch` representing a visual layout or design for this place.
     pub sketch: Option<Sketch>,
+
+    /// The file in which the place is defined, if it was loaded from the file system.
+    ///
+    /// This is not part of the serialized breadboard.
+    #[serde(skip)]
+    pub origin: Option<PathBuf>,
 }
 
+/// The key/value attributes of a [`Place`] or an [`Affordance`], in the order they are defined.
+pub type Attributes = IndexMap<String, String>;
+
 /// Represents the desired position for a given place.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Position {
     pub x: Coordinate,
     pub y: Coordinate,
@@ -92,6 +284,7 @@ pub struct Position {
 
 /// Represents one coordinate of a desired position for a given place.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Coordinate {
     /// An absolute position within the [`Breadboard`] canvas.
@@ -123,6 +316,7 @@ pub enum Coordinate {
 
 /// The relative position from which an offset is calculated.
 #[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Pivot {
     #[default]
     Center,
@@ -136,6 +330,7 @@ pub enum Pivot {
 ///
 /// Internally, a component is the same as a [`Place`].
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Component(Place);
 
 impl Component {
@@ -154,8 +349,15 @@ impl Deref for Component {
     }
 }
 
+impl DerefMut for Component {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 /// Describes an item within a [`Place`].
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Item {
     Affordance(Affordance),
@@ -164,6 +366,7 @@ pub enum Item {
 
 /// Describes an affordance, detailing an action or capability of a [`Place`].
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Affordance {
     /// A unique identifier for the affordance.
     pub name: String,
@@ -171,6 +374,17 @@ pub struct Affordance {
     /// An optional description added to the affordance.
     pub description: Vec<String>,
 
+    /// The optional kind of the affordance, such as `button` or `input`.
+    ///
+    /// Kinds are free-form, but the set of allowed kinds can be restricted during validation, see
+    /// [`validate::Config`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    /// Free-form key/value attributes of the affordance, e.g. `placeholder` or `required`.
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+
     /// A list of [`Connection`] items, specifying how this affordance interacts with other parts
     /// of the breadboard.
     pub connections: Vec<Connection>,
@@ -183,6 +397,7 @@ pub struct Affordance {
 
 /// Describes a reference to a [`Component`] embedded in a [`Place`].
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Reference {
     /// A unique identifier for the referenced [`Component`].
     pub name: String,
@@ -195,16 +410,56 @@ pub struct Reference {
 
 /// Represents a connection from an [`Affordance`] to [`Place`]s on the breadboard.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Connection {
     /// The name of the target [`Place`] for this connection.
     pub target_place: String,
 
+    /// The name of an optional [`Affordance`] within the target [`Place`], that the connection
+    /// lands on, e.g. a specific field or tab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_affordance: Option<String>,
+
+    /// What following the connection means, e.g. plain navigation or opening a modal.
+    #[serde(default, skip_serializing_if = "ConnectionKind::is_navigate")]
+    pub kind: ConnectionKind,
+
     /// An optional description of the connection.
     pub description: Option<String>,
 }
 
+/// The kind of a [`Connection`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ConnectionKind {
+    /// Navigates to the target place.
+    #[default]
+    Navigate,
+
+    /// Returns to a previously visited place, e.g. "go back" or "close".
+    Return,
+
+    /// Opens the target place as an overlay on top of the current place, which the overlay is
+    /// expected to return to.
+    Modal,
+
+    /// Submits data to the target place.
+    Data,
+}
+
+impl ConnectionKind {
+    /// Returns `true` for the default [`ConnectionKind::Navigate`] kind.
+    ///
+    /// Takes `self` by reference, as required by `skip_serializing_if`.
+    #[must_use]
+    pub fn is_navigate(&self) -> bool {
+        *self == Self::Navigate
+    }
+}
+
 /// Represents a graphical sketch or design associated with a [`Place`].
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Sketch {
     /// The file path to the sketch image or file.
     pub path: std::path::PathBuf,
@@ -215,6 +470,7 @@ pub struct Sketch {
 
 /// Defines a specific clickable area within a `Sketch`.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Area {
     /// The top-left coordinates of the area (x, y).
     pub top_left: (u32, u32),
@@ -228,3 +484,34 @@ pub struct Area {
     /// The name of the [`Affordance`] within the [`Place`] of the sketch, this area belongs to.
     pub affordance: String,
 }
+
+/// A named user journey through the breadboard, such as "new user signs up and hits failure".
+///
+/// Where the connections of a breadboard describe every possible path, a flow walks one specific
+/// path, to be reviewed or played back step by step.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Flow {
+    /// A unique identifier for the flow.
+    pub name: String,
+
+    /// An optional description added to the flow.
+    pub description: Vec<String>,
+
+    /// The steps of the flow, in order.
+    ///
+    /// Each step is expected to use an affordance that connects to the place of the next step.
+    pub steps: Vec<Step>,
+}
+
+/// A single step of a [`Flow`]: using an affordance of a place.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Step {
+    /// The name of the [`Place`] the step takes place in.
+    pub place: String,
+
+    /// The name of the [`Affordance`] used within the place, including affordances of included
+    /// [`Component`]s.
+    pub affordance: String,
+}
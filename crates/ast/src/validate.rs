@@ -0,0 +1,771 @@
+//! Semantic validation of a [`Breadboard`].
+//!
+//! The parser only guarantees that a breadboard is *syntactically* correct. This module checks
+//! the relations between nodes, for example that every connection targets an existing place, that
+//! every `include` references an existing component, or that every step of a flow follows a
+//! connection of the previous step.
+//!
+//! Use [`Breadboard::validate`] to run all checks, or [`Breadboard::validate_with`] to configure
+//! them with a [`Config`].
+
+use std::{collections::HashSet, fmt};
+
+use crate::{Affordance, Breadboard, ConnectionKind, Coordinate, Flow, Item, Place};
+
+/// Configuration of the validation checks.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Config {
+    /// The allowed affordance kinds, e.g. `button` and `input`.
+    ///
+    /// If `None`, any kind is allowed. Affordances without a kind are always allowed.
+    pub affordance_kinds: Option<Vec<String>>,
+}
+
+/// A semantic problem found in a [`Breadboard`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Violation {
+    /// Two or more places share the same name.
+    DuplicatePlace { place: String },
+
+    /// Two or more components share the same name.
+    DuplicateComponent { component: String },
+
+    /// A connection targets a place that does not exist.
+    UnknownConnectionTarget {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+
+    /// A connection targets an affordance that does not exist in the target place.
+    UnknownConnectionAffordance {
+        place: String,
+        affordance: String,
+        target: String,
+        target_affordance: String,
+    },
+
+    /// An affordance has a kind that is not in the list of allowed kinds.
+    UnknownAffordanceKind {
+        place: String,
+        affordance: String,
+        kind: String,
+    },
+
+    /// A connection opens a modal place that has no connection returning to the calling place.
+    ModalWithoutReturn {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+
+    /// An `include` references a component that does not exist.
+    UnknownComponent { place: String, component: String },
+
+    /// A component (indirectly) includes itself.
+    RecursiveInclude { component: String },
+
+    /// A relative position references a place that does not exist.
+    UnknownPositionPlace { place: String, target: String },
+
+    /// A sketch area references an affordance that does not exist in the place.
+    UnknownSketchAffordance { place: String, affordance: String },
+
+    /// A sketch area references an affordance without any connections.
+    SketchAffordanceWithoutConnections { place: String, affordance: String },
+
+    /// Two or more flows share the same name.
+    DuplicateFlow { flow: String },
+
+    /// A flow step visits a place that does not exist.
+    UnknownFlowPlace { flow: String, place: String },
+
+    /// A flow step uses an affordance that does not exist in its place.
+    UnknownFlowAffordance {
+        flow: String,
+        place: String,
+        affordance: String,
+    },
+
+    /// The affordance of a flow step does not connect to the place of the next step.
+    DisconnectedFlowStep {
+        flow: String,
+        place: String,
+        affordance: String,
+        next: String,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicatePlace { place } => write!(f, "duplicate place `{place}`"),
+            Self::DuplicateComponent { component } => {
+                write!(f, "duplicate component `{component}`")
+            }
+            Self::UnknownConnectionTarget {
+                place,
+                affordance,
+                target,
+            } => write!(
+                f,
+                "affordance `{affordance}` in `{place}` connects to unknown place `{target}`"
+            ),
+            Self::UnknownConnectionAffordance {
+                place,
+                affordance,
+                target,
+                target_affordance,
+            } => write!(
+                f,
+                "affordance `{affordance}` in `{place}` connects to unknown affordance \
+                 `{target_affordance}` in `{target}`"
+            ),
+            Self::UnknownAffordanceKind {
+                place,
+                affordance,
+                kind,
+            } => write!(
+                f,
+                "affordance `{affordance}` in `{place}` has unknown kind `{kind}`"
+            ),
+            Self::ModalWithoutReturn {
+                place,
+                affordance,
+                target,
+            } => write!(
+                f,
+                "affordance `{affordance}` in `{place}` opens modal `{target}`, which does not \
+                 return to `{place}`"
+            ),
+            Self::UnknownComponent { place, component } => {
+                write!(f, "`{place}` includes unknown component `{component}`")
+            }
+            Self::RecursiveInclude { component } => {
+                write!(f, "component `{component}` recursively includes itself")
+            }
+            Self::UnknownPositionPlace { place, target } => {
+                write!(
+                    f,
+                    "position of `{place}` is relative to unknown place `{target}`"
+                )
+            }
+            Self::UnknownSketchAffordance { place, affordance } => write!(
+                f,
+                "sketch area in `{place}` references unknown affordance `{affordance}`"
+            ),
+            Self::SketchAffordanceWithoutConnections { place, affordance } => write!(
+                f,
+                "sketch area in `{place}` references affordance `{affordance}` without connections"
+            ),
+            Self::DuplicateFlow { flow } => write!(f, "duplicate flow `{flow}`"),
+            Self::UnknownFlowPlace { flow, place } => {
+                write!(f, "flow `{flow}` visits unknown place `{place}`")
+            }
+            Self::UnknownFlowAffordance {
+                flow,
+                place,
+                affordance,
+            } => write!(
+                f,
+                "flow `{flow}` uses unknown affordance `{affordance}` in `{place}`"
+            ),
+            Self::DisconnectedFlowStep {
+                flow,
+                place,
+                affordance,
+                next,
+            } => write!(
+                f,
+                "flow `{flow}` continues to `{next}`, but affordance `{affordance}` in `{place}` \
+                 does not connect to it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Runs all validation checks on the given breadboard.
+pub(crate) fn validate(breadboard: &Breadboard, config: &Config) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    let mut names = HashSet::new();
+    for place in &breadboard.places {
+        if !names.insert(place.name.as_str()) {
+            violations.push(Violation::DuplicatePlace {
+                place: place.name.clone(),
+            });
+        }
+    }
+
+    let mut names = HashSet::new();
+    for component in &breadboard.components {
+        if !names.insert(component.name.as_str()) {
+            violations.push(Violation::DuplicateComponent {
+                component: component.name.clone(),
+            });
+        }
+
+        if breadboard.includes(component, &component.name) {
+            violations.push(Violation::RecursiveInclude {
+                component: component.name.clone(),
+            });
+        }
+    }
+
+    let places = breadboard.places.iter();
+    let components = breadboard.components.iter().map(|c| &**c);
+    for place in places.chain(components) {
+        validate_place(breadboard, config, place, &mut violations);
+    }
+
+    for place in &breadboard.places {
+        validate_modals(breadboard, place, &mut violations);
+    }
+
+    let mut names = HashSet::new();
+    for flow in &breadboard.flows {
+        if !names.insert(flow.name.as_str()) {
+            violations.push(Violation::DuplicateFlow {
+                flow: flow.name.clone(),
+            });
+        }
+
+        validate_flow(breadboard, flow, &mut violations);
+    }
+
+    violations
+}
+
+fn validate_place(
+    breadboard: &Breadboard,
+    config: &Config,
+    place: &Place,
+    violations: &mut Vec<Violation>,
+) {
+    for item in &place.items {
+        match item {
+            Item::Affordance(affordance) => {
+                if let (Some(kind), Some(kinds)) = (&affordance.kind, &config.affordance_kinds) {
+                    if !kinds.contains(kind) {
+                        violations.push(Violation::UnknownAffordanceKind {
+                            place: place.name.clone(),
+                            affordance: affordance.name.clone(),
+                            kind: kind.clone(),
+                        });
+                    }
+                }
+
+                for connection in &affordance.connections {
+                    let Some(target) = breadboard.place(&connection.target_place) else {
+                        violations.push(Violation::UnknownConnectionTarget {
+                            place: place.name.clone(),
+                            affordance: affordance.name.clone(),
+                            target: connection.target_place.clone(),
+                        });
+                        continue;
+                    };
+
+                    let Some(target_affordance) = &connection.target_affordance else {
+                        continue;
+                    };
+
+                    if !breadboard
+                        .affordances(target)
+                        .iter()
+                        .any(|affordance| &affordance.name == target_affordance)
+                    {
+                        violations.push(Violation::UnknownConnectionAffordance {
+                            place: place.name.clone(),
+                            affordance: affordance.name.clone(),
+                            target: connection.target_place.clone(),
+                            target_affordance: target_affordance.clone(),
+                        });
+                    }
+                }
+            }
+            Item::Reference(reference) => {
+                if breadboard.component(&reference.name).is_none() {
+                    violations.push(Violation::UnknownComponent {
+                        place: place.name.clone(),
+                        component: reference.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some(position) = &place.position {
+        let mut targets = vec![];
+        for coordinate in [&position.x, &position.y] {
+            if let Coordinate::Relative { place: target, .. } = coordinate {
+                if !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+
+        for target in targets {
+            if breadboard.place(target).is_none() {
+                violations.push(Violation::UnknownPositionPlace {
+                    place: place.name.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+    }
+
+    if let Some(sketch) = &place.sketch {
+        let affordances = breadboard.affordances(place);
+
+        for area in &sketch.areas {
+            match affordances.iter().find(|a| a.name == area.affordance) {
+                None => violations.push(Violation::UnknownSketchAffordance {
+                    place: place.name.clone(),
+                    affordance: area.affordance.clone(),
+                }),
+                Some(affordance) if affordance.connections.is_empty() => {
+                    violations.push(Violation::SketchAffordanceWithoutConnections {
+                        place: place.name.clone(),
+                        affordance: area.affordance.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Checks that every modal opened from the place, including from the affordances of included
+/// components, has a [`ConnectionKind::Return`] connection back to the place.
+fn validate_modals(breadboard: &Breadboard, place: &Place, violations: &mut Vec<Violation>) {
+    for affordance in breadboard.affordances(place) {
+        let modals = affordance
+            .connections
+            .iter()
+            .filter(|connection| connection.kind == ConnectionKind::Modal);
+
+        for connection in modals {
+            // Unknown targets are already reported by `validate_place`.
+            let Some(modal) = breadboard.place(&connection.target_place) else {
+                continue;
+            };
+
+            let returns = breadboard.affordances(modal).iter().any(|affordance| {
+                affordance.connections.iter().any(|connection| {
+                    connection.kind == ConnectionKind::Return
+                        && connection.target_place == place.name
+                })
+            });
+
+            if !returns {
+                violations.push(Violation::ModalWithoutReturn {
+                    place: place.name.clone(),
+                    affordance: affordance.name.clone(),
+                    target: modal.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Checks that every step of the flow uses an existing affordance, which connects to the place of
+/// the next step.
+fn validate_flow(breadboard: &Breadboard, flow: &Flow, violations: &mut Vec<Violation>) {
+    // The place and affordance of the previous step, if both exist. Steps following an unknown
+    // place or affordance are not checked for a connection.
+    let mut previous: Option<(&Place, Affordance)> = None;
+
+    for step in &flow.steps {
+        let Some(place) = breadboard.place(&step.place) else {
+            violations.push(Violation::UnknownFlowPlace {
+                flow: flow.name.clone(),
+                place: step.place.clone(),
+            });
+            previous = None;
+            continue;
+        };
+
+        if let Some((previous, affordance)) = previous.take() {
+            let connected = affordance
+                .connections
+                .iter()
+                .any(|connection| connection.target_place == step.place);
+
+            if !connected {
+                violations.push(Violation::DisconnectedFlowStep {
+                    flow: flow.name.clone(),
+                    place: previous.name.clone(),
+                    affordance: affordance.name,
+                    next: step.place.clone(),
+                });
+            }
+        }
+
+        let affordance = breadboard
+            .affordances(place)
+            .into_iter()
+            .find(|affordance| affordance.name == step.affordance);
+
+        match affordance {
+            Some(affordance) => previous = Some((place, affordance)),
+            None => violations.push(Violation::UnknownFlowAffordance {
+                flow: flow.name.clone(),
+                place: step.place.clone(),
+                affordance: step.affordance.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        Area, Attributes, Component, Connection, Pivot, Position, Reference, Sketch, Step,
+    };
+
+    use super::*;
+
+    fn place(name: &str, items: Vec<Item>) -> Place {
+        Place {
+            name: name.to_owned(),
+            attributes: Attributes::new(),
+            description: vec![],
+            items,
+            position: None,
+            sketch: None,
+            origin: None,
+        }
+    }
+
+    /// Returns an affordance connecting to each target, written as `Place` or `Place.Affordance`.
+    fn affordance(name: &str, targets: &[&str]) -> Item {
+        Item::Affordance(Affordance {
+            name: name.to_owned(),
+            description: vec![],
+            kind: None,
+            attributes: Attributes::new(),
+            connections: targets
+                .iter()
+                .map(|target| {
+                    let (place, affordance) = target
+                        .split_once('.')
+                        .map_or((*target, None), |(place, affordance)| {
+                            (place, Some(affordance.to_owned()))
+                        });
+
+                    Connection {
+                        target_place: place.to_owned(),
+                        target_affordance: affordance,
+                        kind: ConnectionKind::Navigate,
+                        description: None,
+                    }
+                })
+                .collect(),
+            level: 0,
+        })
+    }
+
+    /// Returns an affordance with a single connection of the given kind.
+    fn connection(name: &str, kind: ConnectionKind, target: &str) -> Item {
+        let mut item = affordance(name, &[target]);
+        if let Item::Affordance(affordance) = &mut item {
+            affordance.connections[0].kind = kind;
+        }
+
+        item
+    }
+
+    /// Returns an affordance of the given kind, without connections.
+    fn typed(name: &str, kind: &str) -> Item {
+        let mut item = affordance(name, &[]);
+        if let Item::Affordance(affordance) = &mut item {
+            affordance.kind = Some(kind.to_owned());
+        }
+
+        item
+    }
+
+    fn reference(name: &str) -> Item {
+        Item::Reference(Reference {
+            name: name.to_owned(),
+            level: 0,
+        })
+    }
+
+    #[test]
+    fn test_valid() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![
+                place(
+                    "Home",
+                    vec![reference("Header"), affordance("Dashboard", &[])],
+                ),
+                place(
+                    "Support",
+                    vec![affordance("Back", &["Home", "Home.Contact"])],
+                ),
+            ],
+            components: vec![Component::new(place(
+                "Header",
+                vec![affordance("Contact", &["Support.Back"])],
+            ))],
+            flows: vec![],
+        };
+
+        assert_eq!(breadboard.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_modals() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![
+                place(
+                    "Home",
+                    vec![
+                        reference("Header"),
+                        connection("Settings", ConnectionKind::Modal, "Settings"),
+                    ],
+                ),
+                place(
+                    "Settings",
+                    vec![connection("Close", ConnectionKind::Return, "Home")],
+                ),
+                place("Help", vec![affordance("Back", &["Home"])]),
+                place("Profile", vec![reference("Header")]),
+            ],
+            components: vec![Component::new(place(
+                "Header",
+                vec![connection("Help", ConnectionKind::Modal, "Help")],
+            ))],
+            flows: vec![],
+        };
+
+        let modal = |place: &str| Violation::ModalWithoutReturn {
+            place: place.to_owned(),
+            affordance: "Help".to_owned(),
+            target: "Help".to_owned(),
+        };
+
+        assert_eq!(breadboard.validate(), vec![modal("Home"), modal("Profile")]);
+    }
+
+    #[test]
+    fn test_affordance_kinds() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![place(
+                "Home",
+                vec![
+                    typed("Username", "input"),
+                    typed("Sign Up", "button"),
+                    typed("Logo", "image"),
+                    affordance("Untyped", &[]),
+                ],
+            )],
+            components: vec![],
+            flows: vec![],
+        };
+
+        assert_eq!(breadboard.validate(), vec![]);
+
+        let config = Config {
+            affordance_kinds: Some(vec!["input".to_owned(), "button".to_owned()]),
+        };
+
+        assert_eq!(
+            breadboard.validate_with(&config),
+            vec![Violation::UnknownAffordanceKind {
+                place: "Home".to_owned(),
+                affordance: "Logo".to_owned(),
+                kind: "image".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_flows() {
+        let flow = |name: &str, steps: &[(&str, &str)]| Flow {
+            name: name.to_owned(),
+            description: vec![],
+            steps: steps
+                .iter()
+                .map(|(place, affordance)| Step {
+                    place: (*place).to_owned(),
+                    affordance: (*affordance).to_owned(),
+                })
+                .collect(),
+        };
+
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![
+                place(
+                    "Registration",
+                    vec![
+                        reference("Header"),
+                        affordance("Sign Up", &["Home", "Support"]),
+                    ],
+                ),
+                place("Support", vec![affordance("Try Again", &["Registration"])]),
+                place("Home", vec![affordance("Dashboard", &[])]),
+            ],
+            components: vec![Component::new(place(
+                "Header",
+                vec![affordance("Contact", &["Support"])],
+            ))],
+            flows: vec![
+                flow(
+                    "Sign Up Fails",
+                    &[
+                        ("Registration", "Sign Up"),
+                        ("Support", "Try Again"),
+                        ("Registration", "Contact"),
+                        ("Support", "Try Again"),
+                    ],
+                ),
+                flow(
+                    "Broken",
+                    &[
+                        ("Home", "Dashboard"),
+                        ("Registration", "Nothing"),
+                        ("Support", "Try Again"),
+                        ("Nowhere", "Back"),
+                        ("Home", "Dashboard"),
+                    ],
+                ),
+                flow("Sign Up Fails", &[]),
+            ],
+        };
+
+        assert_eq!(
+            breadboard.validate(),
+            vec![
+                Violation::DisconnectedFlowStep {
+                    flow: "Broken".to_owned(),
+                    place: "Home".to_owned(),
+                    affordance: "Dashboard".to_owned(),
+                    next: "Registration".to_owned(),
+                },
+                Violation::UnknownFlowAffordance {
+                    flow: "Broken".to_owned(),
+                    place: "Registration".to_owned(),
+                    affordance: "Nothing".to_owned(),
+                },
+                Violation::UnknownFlowPlace {
+                    flow: "Broken".to_owned(),
+                    place: "Nowhere".to_owned(),
+                },
+                Violation::DuplicateFlow {
+                    flow: "Sign Up Fails".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![place("Home", vec![]), place("Home", vec![])],
+            components: vec![
+                Component::new(place("Header", vec![])),
+                Component::new(place("Header", vec![])),
+                Component::new(place("Loop", vec![reference("Loop")])),
+            ],
+            flows: vec![],
+        };
+
+        assert_eq!(
+            breadboard.validate(),
+            vec![
+                Violation::DuplicatePlace {
+                    place: "Home".to_owned()
+                },
+                Violation::DuplicateComponent {
+                    component: "Header".to_owned()
+                },
+                Violation::RecursiveInclude {
+                    component: "Loop".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let mut home = place(
+            "Home",
+            vec![
+                reference("Footer"),
+                affordance("Sign Up", &["Nowhere", "Home.Nothing"]),
+                affordance("Logo", &[]),
+            ],
+        );
+        home.position = Some(Position {
+            x: Coordinate::Relative {
+                place: "Elsewhere".to_owned(),
+                offset: 0,
+                pivot: Pivot::Center,
+            },
+            y: Coordinate::Absolute(0),
+        });
+        home.sketch = Some(Sketch {
+            path: PathBuf::from("home.png"),
+            areas: ["Logo", "Missing"]
+                .into_iter()
+                .map(|affordance| Area {
+                    top_left: (0, 0),
+                    width: 10,
+                    height: 10,
+                    affordance: affordance.to_owned(),
+                })
+                .collect(),
+        });
+
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![home],
+            components: vec![],
+            flows: vec![],
+        };
+
+        assert_eq!(
+            breadboard.validate(),
+            vec![
+                Violation::UnknownComponent {
+                    place: "Home".to_owned(),
+                    component: "Footer".to_owned(),
+                },
+                Violation::UnknownConnectionTarget {
+                    place: "Home".to_owned(),
+                    affordance: "Sign Up".to_owned(),
+                    target: "Nowhere".to_owned(),
+                },
+                Violation::UnknownConnectionAffordance {
+                    place: "Home".to_owned(),
+                    affordance: "Sign Up".to_owned(),
+                    target: "Home".to_owned(),
+                    target_affordance: "Nothing".to_owned(),
+                },
+                Violation::UnknownPositionPlace {
+                    place: "Home".to_owned(),
+                    target: "Elsewhere".to_owned(),
+                },
+                Violation::SketchAffordanceWithoutConnections {
+                    place: "Home".to_owned(),
+                    affordance: "Logo".to_owned(),
+                },
+                Violation::UnknownSketchAffordance {
+                    place: "Home".to_owned(),
+                    affordance: "Missing".to_owned(),
+                },
+            ]
+        );
+    }
+}
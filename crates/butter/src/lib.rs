@@ -1,22 +1,38 @@
 #![allow(elided_lifetimes_in_paths, clippy::needless_pass_by_value)]
 
+use std::path::PathBuf;
+
 mod plugins;
 pub(crate) mod prelude;
 pub(crate) mod widget;
 
 use plugins::{
     AssetManagementPlugin, BevyPlugin, CanvasPlugin, ComputedSizePlugin, DebugPlugin,
-    DesignTokensPlugin, ErrorHandlerPlugin, FileWatcherPlugin, InputPlugin, InspectorPlugin,
-    RngPlugin, SchedulePlugin, StartupPlugin, UiPlugin, WindowPlugin,
+    DesignTokensPlugin, ErrorHandlerPlugin, FileWatcherPlugin, HistoryPlugin, InputPlugin,
+    InspectorPlugin, RngPlugin, SchedulePlugin, StartupPlugin, UiPlugin, WindowPlugin,
 };
 use prelude::*;
 
 pub struct Config {
     pub debug: bool,
+
+    /// The breadboard file to load on startup.
+    pub file: Option<PathBuf>,
+
+    /// The earlier version of the breadboard file to compare it to.
+    pub compare: Option<PathBuf>,
+
+    /// The coverage overlay to tint the affordances of the breadboard with.
+    pub coverage: Option<PathBuf>,
 }
 
 pub fn run(config: Config) {
-    let Config { debug } = config;
+    let Config {
+        debug,
+        file,
+        compare,
+        coverage,
+    } = config;
 
     App::new()
         .add_plugins((
@@ -38,7 +54,14 @@ pub fn run(config: Config) {
             SchedulePlugin,
             StartupPlugin,
             WindowPlugin,
-            FileWatcherPlugin,
+            (
+                FileWatcherPlugin {
+                    file,
+                    compare,
+                    coverage,
+                },
+                HistoryPlugin,
+            ),
             CanvasPlugin,
             RngPlugin,
             ComputedSizePlugin,
@@ -2,8 +2,31 @@
 //!
 //! **A buttery smooth visual Breadboarding experience.**
 
+use std::path::PathBuf;
+
 fn main() {
-    let config = bnb_butter::Config { debug: true };
+    let mut file = None;
+    let mut compare = None;
+    let mut coverage = None;
+
+    // Usage: `butter [FILE] [--compare OLD] [--coverage OVERLAY]`
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--compare" {
+            compare = args.next().map(PathBuf::from);
+        } else if arg == "--coverage" {
+            coverage = args.next().map(PathBuf::from);
+        } else {
+            file = Some(PathBuf::from(arg));
+        }
+    }
+
+    let config = bnb_butter::Config {
+        debug: true,
+        file,
+        compare,
+        coverage,
+    };
 
     bnb_butter::run(config);
 }
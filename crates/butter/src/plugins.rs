@@ -9,6 +9,7 @@ pub(super) mod debug;
 pub(super) mod design_tokens;
 pub(super) mod error_handler;
 pub(super) mod file_watcher;
+pub(super) mod history;
 pub(super) mod input;
 pub(super) mod inspector;
 pub(super) mod rng;
@@ -25,6 +26,7 @@ pub(super) use debug::DebugPlugin;
 pub(super) use design_tokens::DesignTokensPlugin;
 pub(super) use error_handler::ErrorHandlerPlugin;
 pub(super) use file_watcher::FileWatcherPlugin;
+pub(super) use history::HistoryPlugin;
 pub(super) use input::InputPlugin;
 pub(super) use inspector::InspectorPlugin;
 pub(super) use rng::RngPlugin;
@@ -7,6 +7,7 @@
 //! - [`PlacePlugin`]
 //! - [`AffordancePlugin`]
 //! - [`ConnectionPlugin`]
+//! - [`FlowPlugin`]
 //!
 //! It orchestrates the visualization of the breadboard's components, enabling an intuitive and
 //! interactive layout for users to explore and understand their designs. This plugin plays a
@@ -19,6 +20,8 @@
 mod affordance;
 mod breadboard;
 mod connection;
+mod flow;
+mod highlight;
 mod place;
 mod shared;
 
@@ -27,11 +30,13 @@ use crate::prelude::*;
 pub(crate) use affordance::AffordanceCreatedEvent;
 pub(crate) use breadboard::{BreadboardCreatedEvent, ShowNumbersCheckbox};
 pub(crate) use connection::ConnectionCreated;
-pub(crate) use place::PlaceCreatedEvent;
+pub(crate) use flow::FlowPlayer;
+pub(crate) use highlight::{CoverageOverlay, Highlights, Legend};
+pub(crate) use place::{PlaceCreatedEvent, PlaceFilterInput};
 
 use self::{
     affordance::AffordancePlugin, breadboard::BreadboardPlugin, connection::ConnectionPlugin,
-    place::PlacePlugin,
+    flow::FlowPlugin, place::PlacePlugin,
 };
 
 /// Marker component for the root entity of the canvas.
@@ -83,6 +88,7 @@ impl Plugin for CanvasPlugin {
             PlacePlugin,
             AffordancePlugin,
             ConnectionPlugin,
+            FlowPlugin,
         ))
         .add_systems(
             Update,
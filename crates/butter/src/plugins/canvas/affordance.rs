@@ -14,6 +14,7 @@ use crate::prelude::*;
 
 use super::{
     breadboard::ShowNumbers,
+    highlight::{self, CoverageOverlay, Highlights},
     place::{Place, PlaceCreatedEvent},
     shared::{
         Body, Description, Header, Index, Title, TitleBundle, TitleNumberSpan,
@@ -47,7 +48,7 @@ impl Plugin for AffordancePlugin {
 /// This component is utilized to identify entities that function as affordances in the context of
 /// a place. Affordances represent actionable or informational elements within a place.
 #[derive(Component, Default)]
-struct Affordance;
+pub(super) struct Affordance;
 
 /// Bundle of required components for affordance entities.
 #[derive(Bundle)]
@@ -72,14 +73,14 @@ impl Default for AffordanceBundle {
 /// Signals the creation of an affordance entity within a place.
 ///
 /// Dispatched following the successful creation of an affordance, this event carries the new
-/// entity's identifier, its name, and a list of connections as defined in the DSL. It facilitates
-/// further interactions or behaviors associated with the affordance, enabling systems to respond
-/// to its addition and integrate it appropriately within the broader context of the breadboard and
-/// its places.
+/// entity's identifier, its name and the name of its place, and a list of connections as defined
+/// in the DSL. It facilitates further interactions or behaviors associated with the affordance,
+/// enabling systems to respond to its addition and integrate it appropriately within the broader
+/// context of the breadboard and its places.
 #[derive(Event)]
 pub(crate) struct AffordanceCreatedEvent {
     pub entity: Entity,
-    #[expect(dead_code)]
+    pub place: String,
     pub name: String,
     pub connections: Vec<ast::Connection>,
 }
@@ -108,9 +109,12 @@ fn create(
     mut created: EventWriter<AffordanceCreatedEvent>,
     asset_server: Res<AssetServer>,
     tokens: Res<DesignTokens>,
+    highlights: Res<Highlights>,
+    overlay: Res<CoverageOverlay>,
 ) {
     for &PlaceCreatedEvent {
         entity: place,
+        name: ref place_name,
         ref affordances,
         ..
     } in places.read()
@@ -139,8 +143,10 @@ fn create(
             ast::Affordance {
                 name,
                 description,
+                kind,
                 connections,
                 level,
+                ..
             },
         ) in affordances.clone().into_iter().enumerate()
         {
@@ -170,11 +176,33 @@ fn create(
                 "embedded://bnb_butter/plugins/../../assets/fonts/{font_family}.ttf"
             ));
 
-            let title = create_title(&mut cmd, place_index, &indices, level, &name, font, &tokens);
+            // Changes take precedence over coverage. The coverage of the affordance summarizes the
+            // coverage of its connections, which are colored one by one.
+            let color = highlights
+                .affordance(place_name, &name)
+                .map(highlight::color)
+                .or_else(|| {
+                    overlay
+                        .affordance(place_name, &name)
+                        .map(highlight::coverage_color)
+                });
+
+            let title = create_title(
+                &mut cmd,
+                place_index,
+                &indices,
+                level,
+                &name,
+                kind.as_deref(),
+                color,
+                font,
+                &tokens,
+            );
             cmd.entity(affordance).add_child(title);
 
             created.write(AffordanceCreatedEvent {
                 entity: affordance,
+                place: place_name.clone(),
                 name,
                 connections,
             });
@@ -187,14 +215,19 @@ fn create(
 /// Generates titles for affordance entities based on their creation events.
 ///
 /// For each [`AffordanceCreatedEvent`], this function creates a title entity with specified
-/// styling, including font size, color, and alignment.
+/// styling, including font size, color, and alignment. Typed affordances are colored by their
+/// kind, which is shown after the name, unless they are highlighted as changed or by their
+/// coverage.
 #[instrument(skip_all)]
+#[expect(clippy::too_many_arguments)]
 fn create_title(
     cmd: &mut Commands,
     place_index: usize,
     indices: &HashMap<usize, usize>,
     level: usize,
     name: &str,
+    kind: Option<&str>,
+    highlight: Option<Color>,
     font: Handle<Font>,
     tokens: &DesignTokens,
 ) -> Entity {
@@ -205,7 +238,7 @@ fn create_title(
         font_size: 16.,
         ..default()
     };
-    let name_color = TextColor(css::BLACK.into());
+    let name_color = TextColor(highlight.unwrap_or_else(|| kind_color(kind).into()));
 
     let numbers_font = TextFont {
         font: font.clone(),
@@ -256,7 +289,7 @@ fn create_title(
         .with_children(|parent| {
             parent.spawn((
                 TitleNumberSpanBundle::new(numbers),
-                numbers_font,
+                numbers_font.clone(),
                 numbers_color,
             ));
             parent.spawn((
@@ -266,6 +299,14 @@ fn create_title(
                 Transform::default(),
                 ComputedSize::Pending,
             ));
+
+            if let Some(kind) = kind {
+                parent.spawn((
+                    TextSpan::new(format!(" [{kind}]")),
+                    numbers_font,
+                    TextColor(css::DARK_GRAY.into()),
+                ));
+            }
         })
         .id();
 
@@ -274,6 +315,19 @@ fn create_title(
     title
 }
 
+/// Returns the color of an affordance title, based on the kind of the affordance.
+///
+/// Interactive kinds stand out from static content, unknown kinds are rendered like untyped
+/// affordances.
+fn kind_color(kind: Option<&str>) -> Srgba {
+    match kind {
+        Some("button" | "link") => css::ROYAL_BLUE,
+        Some("input" | "select" | "checkbox" | "toggle") => css::SEA_GREEN,
+        Some("text" | "image" | "badge") => css::DIM_GRAY,
+        _ => css::BLACK,
+    }
+}
+
 /// Positions affordances within their respective places based on their computed sizes.
 ///
 /// This function aligns affordances vertically within each place, starting directly below the
@@ -89,10 +89,10 @@ pub(crate) struct BreadboardCreatedEvent {
 
 /// Spawns a new breadboard entity based on the loaded file.
 ///
-/// Processes each [`FileLoadedEvent`], attempting to parse the file contents into a breadboard DSL
-/// structure. If parsing succeeds, any existing breadboard with the same name is removed from the
-/// canvas to make room for the new one. The new breadboard entity is then created, with visual
-/// variations seeded by its name to ensure a unique, yet consistent, hand-drawn appearance.
+/// Processes each [`FileLoadedEvent`], carrying the breadboard imported from the file. Any existing
+/// breadboard with the same name is removed from the canvas to make room for the new one. The new
+/// breadboard entity is then created, with visual variations seeded by its name to ensure a
+/// unique, yet consistent, hand-drawn appearance.
 ///
 /// Finally, a [`BreadboardCreatedEvent`] is emitted to signal the successful creation of the
 /// breadboard.
@@ -104,19 +104,11 @@ fn spawn(
     mut loaded: EventReader<FileLoadedEvent>,
     mut created: EventWriter<BreadboardCreatedEvent>,
 ) {
-    for FileLoadedEvent { name, contents } in loaded.read() {
+    for FileLoadedEvent { name, breadboard } in loaded.read() {
         let span = info_span!("spawn", %name, breadboard = field::Empty).entered();
 
-        let (mut places, components) = match parser::parse(contents) {
-            Ok(ast::Breadboard { places, components }) => (places, components),
-            Err(error) => {
-                // TODO: Trigger `alert` widget.
-                error!(?error, "Unable to parse breadboard DSL.");
-                continue;
-            }
-        };
-
-        for component in components {
+        let mut places = breadboard.places.clone();
+        for component in &breadboard.components {
             places.push(component.deref().clone());
         }
 
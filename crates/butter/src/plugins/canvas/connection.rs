@@ -1,64 +1,524 @@
-use crate::prelude::*;
+//! Connection Plugin: Drawing the Connections Between Affordances and Places
+//!
+//! The [`ConnectionPlugin`] draws an arrow for each connection of an affordance, from the
+//! affordance to its target place. Connections to an affordance within the target place end at
+//! the row of that affordance, instead of at the header of the place.
+//!
+//! Arrows are drawn as gizmos, rather than spawned as entities in the canvas, so that they do not
+//! take part in the [`ComputedSize`] of the places they connect. Each [`ast::ConnectionKind`] is
+//! drawn with its own color, dash pattern and arrowhead, see [`stroke`]. When a
+//! [`CoverageOverlay`] is loaded, connections are colored by whether the trace followed them
+//! instead, keeping the dash pattern and arrowhead of their kind.
+//!
+//! Clicking an affordance follows its connections, one per click, by moving the camera [`Target`]
+//! to the row of the target affordance, or to the target place, using the same tweening as the
+//! keyboard navigation.
 
-use super::{affordance::AffordanceCreatedEvent, CanvasSet};
+use bevy::{
+    gizmos::gizmos::Gizmos,
+    picking::{
+        events::{Click, Pointer},
+        Pickable,
+    },
+    platform::collections::HashMap,
+    sprite::Sprite,
+};
 
-/// Manage *affordances* in a place.
+use crate::{plugins::input::Target, prelude::*};
+
+use super::{
+    affordance::{Affordance, AffordanceCreatedEvent},
+    highlight::{self, CoverageOverlay},
+    place::{Place, PlaceHeader},
+    shared::Title,
+    CanvasSet,
+};
+
+/// The length of an arrowhead, along the connection.
+const HEAD_LENGTH: f32 = 10.;
+
+/// The distance between an arrow and the affordance or place it connects.
+const MARGIN: f32 = 6.;
+
+/// Draws the connections of affordances.
 pub(super) struct ConnectionPlugin;
 
 impl Plugin for ConnectionPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ConnectionCreated>().add_systems(
             Update,
-            create
-                .run_if(on_event::<AffordanceCreatedEvent>)
-                .in_set(CanvasSet::Connection),
+            (
+                create
+                    .run_if(on_event::<AffordanceCreatedEvent>)
+                    .in_set(CanvasSet::Connection),
+                resize_hit_area
+                    .run_if(any_with_component::<HitArea>)
+                    .in_set(CanvasSet::Connection),
+                draw.map(err)
+                    .run_if(any_with_component::<Connections>)
+                    .after(CanvasSet::Connection),
+            ),
         );
     }
 }
 
-// /// Marker component for connection entities.
-// #[derive(Component, Default)]
-// struct Connection;
-//
-// /// Bundle of required components for place entities.
-// #[derive(Bundle, Default)]
-// struct ConnectionBundle {
-//     marker: Connection,
-//     visibility: Visibility,
-//     transform: Transform,
-//     size: ComputedSize,
-// }
+/// The connections of an affordance entity.
+#[derive(Component, Deref)]
+struct Connections {
+    /// The name of the place of the affordance.
+    place: String,
+
+    /// The name of the affordance.
+    affordance: String,
+
+    #[deref]
+    list: Vec<ast::Connection>,
+
+    /// The connection that is followed by the next click on the affordance.
+    next: usize,
+}
+
+/// Marks the invisible sprite over the title of an affordance with connections, which makes the
+/// affordance clickable.
+#[derive(Component)]
+struct HitArea;
 
 #[derive(Event)]
 #[allow(dead_code)]
 pub(crate) struct ConnectionCreated {
+    /// The affordance the connection starts from.
     pub entity: Entity,
     pub target_place: Name,
+
+    /// The affordance within the target place that the connection lands on, if any.
+    pub target_affordance: Option<Name>,
+
+    /// How the connection moves the user to its target.
+    pub kind: ast::ConnectionKind,
 }
 
+/// Attaches the connections of newly created affordances, to be drawn by [`draw`], and followed
+/// by [`follow`] when the title of the affordance is clicked.
 #[instrument(skip_all)]
-fn create(mut affordances: EventReader<AffordanceCreatedEvent>) {
+fn create(
+    mut cmd: Commands,
+    mut affordances: EventReader<AffordanceCreatedEvent>,
+    titles: Query<(Entity, &ChildOf), With<Title>>,
+    mut created: EventWriter<ConnectionCreated>,
+) {
     for &AffordanceCreatedEvent {
         entity,
+        ref place,
+        ref name,
         ref connections,
-        ..
     } in affordances.read()
     {
-        for ast::Connection { target_place, .. } in connections.clone() {
-            let _span = info_span!("spawn", affordance = ?entity, target = %target_place).entered();
-
-            // TODO: Disabled for now, as it results in `ComputedSize::Pending`, which prevents
-            // the board from becoming visible.
-            //
-            // let entity = cmd
-            //     .spawn(ConnectionBundle::default())
-            //     .set_parent(entity)
-            //     .id();
-            //
-            // created.send(ConnectionCreated {
-            //     entity,
-            //     target_place: target_place.into(),
-            // });
+        if connections.is_empty() {
+            continue;
+        }
+
+        for connection in connections {
+            let _span = info_span!(
+                "spawn",
+                affordance = ?entity,
+                target = %connection.target_place,
+                target_affordance = ?connection.target_affordance,
+                kind = ?connection.kind
+            )
+            .entered();
+
+            created.write(ConnectionCreated {
+                entity,
+                target_place: connection.target_place.clone().into(),
+                target_affordance: connection.target_affordance.clone().map(Into::into),
+                kind: connection.kind,
+            });
+        }
+
+        cmd.entity(entity)
+            .insert(Connections {
+                place: place.clone(),
+                affordance: name.clone(),
+                list: connections.clone(),
+                next: 0,
+            })
+            .observe(follow);
+
+        // Text cannot be picked, so an invisible sprite over the title catches the clicks, which
+        // bubble up to the affordance. It is sized by `resize_hit_area`, once the size of the
+        // title is known.
+        let Some(title) = titles
+            .iter()
+            .find_map(|(title, parent)| (parent.parent() == entity).then_some(title))
+        else {
+            warn!(affordance = ?entity, "Affordance title not found.");
+            continue;
+        };
+
+        let hit_area = cmd
+            .spawn((
+                HitArea,
+                Sprite {
+                    color: Color::NONE,
+                    custom_size: Some(Vec2::ZERO),
+                    anchor: Anchor::TopLeft,
+                    ..default()
+                },
+                Pickable::default(),
+                Transform::default(),
+                ComputedSize::Static(Vec2::ZERO),
+            ))
+            .id();
+        cmd.entity(title).add_child(hit_area);
+    }
+}
+
+/// Sizes the hit area of an affordance to its title.
+#[instrument(skip_all)]
+fn resize_hit_area(
+    titles: Query<&ComputedSize, (With<Title>, Changed<ComputedSize>, Without<HitArea>)>,
+    mut hit_areas: Query<(&ChildOf, &mut Sprite, &mut ComputedSize), With<HitArea>>,
+) {
+    for (parent, mut sprite, mut size) in &mut hit_areas {
+        let Some(title_size) = titles.get(parent.parent()).ok().and_then(|s| s.size()) else {
+            continue;
+        };
+
+        sprite.custom_size = Some(title_size);
+        size.set_if_neq(ComputedSize::Static(title_size));
+    }
+}
+
+/// Follows the next connection of a clicked affordance, by moving the camera [`Target`] to the row
+/// of the target affordance, or to the target place.
+///
+/// Connections to places that are filtered out are skipped.
+#[instrument(skip_all)]
+fn follow(
+    trigger: Trigger<Pointer<Click>>,
+    mut connections: Query<&mut Connections>,
+    targets: Targets,
+    places: Query<&InheritedVisibility, With<Place>>,
+    mut target: ResMut<Target>,
+) {
+    let Ok(mut connections) = connections.get_mut(trigger.target()) else {
+        return;
+    };
+
+    let landmarks = targets.landmarks();
+    for _ in 0..connections.len() {
+        let index = connections.next % connections.len();
+        connections.next = index + 1;
+
+        let connection = &connections[index];
+        let Some((place, _)) = landmarks.place(&connection.target_place) else {
+            continue;
+        };
+
+        if !places.get(place).is_ok_and(|visibility| visibility.get()) {
+            continue;
+        }
+
+        let entity = connection
+            .target_affordance
+            .as_deref()
+            .and_then(|name| landmarks.row(place, name))
+            .unwrap_or(place);
+
+        target.set(entity);
+        return;
+    }
+}
+
+/// The places, and the affordance rows within them, that connections end at.
+#[derive(SystemParam)]
+struct Targets<'w, 's> {
+    titles: Query<'w, 's, (&'static Name, &'static ChildOf), With<Title>>,
+    headers: Query<'w, 's, &'static ChildOf, With<PlaceHeader>>,
+    affordances: Query<'w, 's, &'static ChildOf, With<Affordance>>,
+    parents: Query<'w, 's, &'static ChildOf>,
+}
+
+impl Targets<'_, '_> {
+    /// Returns the header of each place, and the row of each affordance within a place, by name.
+    fn landmarks(&self) -> Landmarks<'_> {
+        let mut landmarks = Landmarks::default();
+        for (name, parent) in &self.titles {
+            let entity = parent.parent();
+
+            if let Ok(place) = self.headers.get(entity) {
+                landmarks
+                    .places
+                    .insert(name.as_str(), (place.parent(), entity));
+            } else if let Ok(body) = self.affordances.get(entity) {
+                let Ok(place) = self.parents.get(body.parent()) else {
+                    continue;
+                };
+
+                landmarks
+                    .rows
+                    .insert((place.parent(), name.as_str()), entity);
+            }
+        }
+
+        landmarks
+    }
+}
+
+/// The places and affordance rows of the canvas, by name, see [`Targets::landmarks`].
+#[derive(Default)]
+struct Landmarks<'a> {
+    places: HashMap<&'a str, (Entity, Entity)>,
+    rows: HashMap<(Entity, &'a str), Entity>,
+}
+
+impl Landmarks<'_> {
+    /// Returns the place with the given name, and its header.
+    fn place(&self, name: &str) -> Option<(Entity, Entity)> {
+        self.places.get(name).copied()
+    }
+
+    /// Returns the row of the affordance with the given name within the place.
+    fn row(&self, place: Entity, name: &str) -> Option<Entity> {
+        self.rows.get(&(place, name)).copied()
+    }
+}
+
+/// Draws an arrow for each connection, between visible places.
+///
+/// Arrows start at the right edge of their affordance, and end at the left edge of the header of
+/// the target place, or of the row of the target affordance.
+#[instrument(skip_all)]
+fn draw(
+    mut gizmos: Gizmos,
+    connections: Query<(Entity, &Connections)>,
+    targets: Targets,
+    places: Query<&InheritedVisibility, With<Place>>,
+    parents: Query<&ChildOf>,
+    sizes: ComputedSizeParam<()>,
+    overlay: Res<CoverageOverlay>,
+) -> Result<(), Error> {
+    let landmarks = targets.landmarks();
+
+    let is_visible = |entity| {
+        parents
+            .iter_ancestors(entity)
+            .find_map(|ancestor| places.get(ancestor).ok())
+            .is_some_and(|visibility| visibility.get())
+    };
+
+    // The center of an entity and half its width, if its size is known.
+    let bounds = |entity| -> Result<Option<(Vec2, f32)>, Error> {
+        let (Some(center), Some(size)) =
+            (sizes.global_translation_of(entity)?, sizes.size_of(entity)?)
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some((center.xy(), size.x / 2.)))
+    };
+
+    for (affordance, connections) in &connections {
+        if !is_visible(affordance) {
+            continue;
+        }
+
+        let Some((center, half_width)) = bounds(affordance)? else {
+            continue;
+        };
+        let start = center + Vec2::new(half_width + MARGIN, 0.);
+
+        for connection in connections.iter() {
+            let Some((place, header)) = landmarks.place(&connection.target_place) else {
+                continue;
+            };
+
+            if !places.get(place).is_ok_and(|visibility| visibility.get()) {
+                continue;
+            }
+
+            // Connections to an unknown affordance end at the header of the place.
+            let target = connection
+                .target_affordance
+                .as_deref()
+                .and_then(|name| landmarks.row(place, name))
+                .unwrap_or(header);
+
+            let Some((center, half_width)) = bounds(target)? else {
+                continue;
+            };
+            let end = center - Vec2::new(half_width + MARGIN, 0.);
+
+            let stroke = stroke(connection.kind);
+            let color = overlay
+                .connection(
+                    &connections.place,
+                    &connections.affordance,
+                    &connection.target_place,
+                )
+                .map_or(stroke.color.into(), highlight::coverage_color);
+
+            for [from, to] in lines(start, end, &stroke) {
+                gizmos.line_2d(from, to, color);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// How a connection is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stroke {
+    color: Srgba,
+
+    /// The length of the dashes and of the gaps between them, or `None` for a solid line.
+    dash: Option<(f32, f32)>,
+
+    head: Head,
+}
+
+/// The arrowhead at the end of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Head {
+    /// A closed triangle.
+    Triangle,
+
+    /// Two lines, open at the back.
+    Open,
+
+    /// A closed diamond.
+    Diamond,
+
+    /// An open arrowhead, with a bar across the line behind it.
+    Bar,
+}
+
+/// Returns how connections of the given kind are drawn.
+///
+/// Navigation is the most common kind, and is drawn as a plain arrow. Returning to a previous
+/// place is dashed, opening a modal is drawn with a diamond, and submitting data is dotted.
+fn stroke(kind: ast::ConnectionKind) -> Stroke {
+    match kind {
+        ast::ConnectionKind::Navigate => Stroke {
+            color: css::DIM_GRAY,
+            dash: None,
+            head: Head::Triangle,
+        },
+        ast::ConnectionKind::Return => Stroke {
+            color: css::SLATE_GRAY,
+            dash: Some((8., 6.)),
+            head: Head::Open,
+        },
+        ast::ConnectionKind::Modal => Stroke {
+            color: css::DARK_ORANGE,
+            dash: None,
+            head: Head::Diamond,
+        },
+        ast::ConnectionKind::Data => Stroke {
+            color: css::SEA_GREEN,
+            dash: Some((2., 4.)),
+            head: Head::Bar,
+        },
+    }
+}
+
+/// Returns the line segments of an arrow from `start` to `end`.
+fn lines(start: Vec2, end: Vec2, stroke: &Stroke) -> Vec<[Vec2; 2]> {
+    let direction = (end - start).normalize_or_zero();
+    if direction == Vec2::ZERO {
+        return vec![];
+    }
+
+    // The sides of the arrowhead, and the point where it meets the line.
+    let back = end - direction * HEAD_LENGTH;
+    let left = back + direction.perp() * HEAD_LENGTH / 2.;
+    let right = back - direction.perp() * HEAD_LENGTH / 2.;
+
+    // Closed arrowheads are not crossed by the line.
+    let (shaft, head) = match stroke.head {
+        Head::Triangle => (back, vec![[left, end], [end, right], [right, left]]),
+        Head::Open => (end, vec![[left, end], [end, right]]),
+        Head::Diamond => {
+            let back = end - direction * HEAD_LENGTH * 2.;
+            (
+                back,
+                vec![[end, left], [left, back], [back, right], [right, end]],
+            )
+        }
+        Head::Bar => (
+            end,
+            vec![
+                [left, end],
+                [end, right],
+                [
+                    left - direction * HEAD_LENGTH / 2.,
+                    right - direction * HEAD_LENGTH / 2.,
+                ],
+            ],
+        ),
+    };
+
+    let length = start.distance(shaft);
+    let mut lines = match stroke.dash {
+        Some((dash, gap)) if dash > 0. => {
+            let mut lines = vec![];
+            let mut offset = 0.;
+
+            while offset < length {
+                let to = (offset + dash).min(length);
+                lines.push([start + direction * offset, start + direction * to]);
+                offset = to + gap;
+            }
+
+            lines
+        }
+        _ => vec![[start, shaft]],
+    };
+
+    lines.extend(head);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn test_strokes() {
+        let kinds = [
+            ast::ConnectionKind::Navigate,
+            ast::ConnectionKind::Return,
+            ast::ConnectionKind::Modal,
+            ast::ConnectionKind::Data,
+        ];
+
+        let mut snapshot = String::new();
+        for kind in kinds {
+            let stroke = stroke(kind);
+            let Stroke { color, dash, head } = stroke;
+
+            writeln!(snapshot, "{kind:?}: {} {dash:?} {head:?}", color.to_hex()).unwrap();
+            for [from, to] in lines(Vec2::ZERO, Vec2::new(40., 0.), &stroke) {
+                writeln!(
+                    snapshot,
+                    "  ({:.1}, {:.1}) -> ({:.1}, {:.1})",
+                    from.x, from.y, to.x, to.y
+                )
+                .unwrap();
+            }
+        }
+
+        insta::assert_snapshot!(snapshot);
+
+        // Every kind is drawn differently.
+        for (i, a) in kinds.iter().enumerate() {
+            for b in &kinds[i + 1..] {
+                let (a, b) = (stroke(*a), stroke(*b));
+                assert!(a.color != b.color && a.head != b.head);
+            }
         }
     }
 }
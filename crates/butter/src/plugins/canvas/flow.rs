@@ -0,0 +1,181 @@
+//! Flow Plugin: Playing User Flows Through a Breadboard
+//!
+//! The [`FlowPlugin`] keeps track of the *flows* of the loaded breadboard, as defined by `flow`
+//! blocks in the DSL. A flow can be played step by step from the navigation bar, moving the camera
+//! [`Target`] to the place of each step, using the same tweening as the keyboard navigation.
+
+use crate::{
+    plugins::{file_watcher::FileLoadedEvent, input::Target},
+    prelude::*,
+};
+
+use super::{place::Place, shared::Index, CanvasSet};
+
+/// Plays the flows of a breadboard.
+pub(super) struct FlowPlugin;
+
+impl Plugin for FlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Flows>()
+            .init_resource::<FlowPlayback>()
+            .add_systems(
+                Update,
+                (
+                    store.run_if(on_event::<FileLoadedEvent>),
+                    focus_step.run_if(resource_changed::<FlowPlayback>),
+                )
+                    .chain()
+                    .in_set(CanvasSet::Place),
+            );
+    }
+}
+
+/// A flow of the loaded breadboard, with its steps resolved to place indices.
+#[derive(Debug)]
+pub(super) struct Flow {
+    name: String,
+
+    /// The [`Index`] of the place visited by each step.
+    ///
+    /// Steps visiting unknown places are skipped.
+    steps: Vec<usize>,
+}
+
+/// The flows of the most recently loaded breadboard.
+#[derive(Resource, Deref, Debug, Default)]
+pub(super) struct Flows(Vec<Flow>);
+
+/// The flow that is currently being played, if any, and the step it is at.
+#[derive(Resource, Debug, Default)]
+pub(super) struct FlowPlayback {
+    flow: Option<usize>,
+    step: usize,
+}
+
+/// Stores the flows of a newly loaded breadboard, and stops any playing flow.
+#[instrument(skip_all)]
+fn store(
+    mut loaded: EventReader<FileLoadedEvent>,
+    mut flows: ResMut<Flows>,
+    mut playback: ResMut<FlowPlayback>,
+) {
+    for FileLoadedEvent { breadboard, .. } in loaded.read() {
+        flows.0 = breadboard
+            .flows
+            .iter()
+            .map(|flow| Flow {
+                name: flow.name.clone(),
+                steps: flow
+                    .steps
+                    .iter()
+                    .filter_map(|step| {
+                        breadboard
+                            .places
+                            .iter()
+                            .position(|place| place.name == step.place)
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        *playback = FlowPlayback::default();
+    }
+}
+
+/// Moves the camera [`Target`] to the place of the current step of the playing flow.
+#[instrument(skip_all)]
+fn focus_step(
+    flows: Res<Flows>,
+    playback: Res<FlowPlayback>,
+    places: Query<(Entity, &Index), With<Place>>,
+    mut target: ResMut<Target>,
+) {
+    let Some(index) = playback
+        .flow
+        .and_then(|flow| flows.get(flow))
+        .and_then(|flow| flow.steps.get(playback.step))
+    else {
+        return;
+    };
+
+    let Some(place) = places
+        .iter()
+        .find_map(|(entity, i)| (**i == *index).then_some(entity))
+    else {
+        return;
+    };
+
+    target.set(place);
+}
+
+#[derive(SystemParam)]
+pub(crate) struct FlowPlayer<'w> {
+    flows: Res<'w, Flows>,
+    playback: ResMut<'w, FlowPlayback>,
+}
+
+impl WidgetSystem for FlowPlayer<'_> {
+    type Args = ();
+    type Output = ();
+
+    fn system(
+        world: &mut World,
+        state: &mut SystemState<Self>,
+        ui: &mut egui::Ui,
+        _: Self::Args,
+    ) -> Self::Output {
+        let FlowPlayer {
+            flows,
+            mut playback,
+        } = state.get_mut(world);
+
+        if flows.is_empty() {
+            return;
+        }
+
+        let mut selected = playback.flow;
+        let current = selected
+            .and_then(|flow| flows.get(flow))
+            .map_or("Play flow…", |flow| flow.name.as_str());
+
+        egui::ComboBox::from_id_salt("flow")
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for (i, flow) in flows.iter().enumerate() {
+                    ui.selectable_value(&mut selected, Some(i), flow.name.as_str());
+                }
+            });
+
+        if selected != playback.flow {
+            *playback = FlowPlayback {
+                flow: selected,
+                step: 0,
+            };
+        }
+
+        let Some(steps) = playback
+            .flow
+            .and_then(|flow| flows.get(flow))
+            .map(|flow| flow.steps.len())
+        else {
+            return;
+        };
+
+        let step = playback.step;
+        if ui
+            .add_enabled(step > 0, egui::Button::new("Previous"))
+            .clicked()
+        {
+            playback.step -= 1;
+        }
+
+        ui.label(format!("{}/{steps}", (step + 1).min(steps)));
+
+        if ui
+            .add_enabled(step + 1 < steps, egui::Button::new("Next"))
+            .clicked()
+        {
+            playback.step += 1;
+        }
+    }
+}
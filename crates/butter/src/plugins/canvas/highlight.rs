@@ -0,0 +1,113 @@
+//! Highlighting of the changes between two versions of a breadboard, and of its coverage.
+//!
+//! When two breadboards are compared, the canvas renders their [`Overlay`]. Added places and
+//! affordances are shown in green, removed ones are ghosted in red, and those that changed
+//! (including affordances with changed connections) in orange.
+//!
+//! When a [`Coverage`] overlay is loaded, connections followed by the recorded trace are drawn in
+//! green, and the others in red. Affordances whose connections were all followed are shown in
+//! green as well, partially covered ones in yellow, and uncovered ones in red.
+//!
+//! A legend explains the colors.
+
+use bevy::color::ColorToPacked as _;
+use bevy_egui::egui::{Align2, Color32};
+use coverage::Coverage;
+use diff::{Overlay, Status};
+
+use crate::{prelude::*, widget::RootWidgetSystem};
+
+/// The changes to highlight on the canvas.
+///
+/// This is empty, unless two versions of a breadboard are compared.
+#[derive(Resource, Deref, DerefMut, Debug, Default)]
+pub(crate) struct Highlights(pub Overlay);
+
+/// The coverage of the breadboard by a recorded navigation trace.
+///
+/// This is empty, unless a coverage overlay is loaded.
+#[derive(Resource, Deref, DerefMut, Debug, Default)]
+pub(crate) struct CoverageOverlay(pub Coverage);
+
+/// Returns the color of the title of a place or affordance with the given status.
+pub(super) fn color(status: Status) -> Color {
+    match status {
+        Status::Added => css::SEA_GREEN.into(),
+        Status::Removed => css::RED.with_alpha(0.4).into(),
+        Status::Changed => css::ORANGE_RED.into(),
+    }
+}
+
+/// Returns the color of the title of an affordance, or of a connection, with the given coverage.
+pub(super) fn coverage_color(status: coverage::Status) -> Color {
+    match status {
+        coverage::Status::Covered => css::SEA_GREEN.into(),
+        coverage::Status::Partial => css::GOLDENROD.into(),
+        coverage::Status::Uncovered => css::CRIMSON.into(),
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct Legend<'w> {
+    highlights: Res<'w, Highlights>,
+    overlay: Res<'w, CoverageOverlay>,
+}
+
+impl RootWidgetSystem for Legend<'_> {
+    type Args = ();
+    type Output = ();
+
+    fn system(
+        world: &mut World,
+        state: &mut SystemState<Self>,
+        ctx: &mut egui::Context,
+        _: Self::Args,
+    ) {
+        let Legend {
+            highlights,
+            overlay,
+        } = state.get_mut(world);
+
+        if !overlay.places.is_empty() {
+            egui::Window::new("Coverage")
+                .anchor(Align2::RIGHT_BOTTOM, [-10., -10.])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    for (status, label) in [
+                        (coverage::Status::Covered, "Covered"),
+                        (coverage::Status::Partial, "Partially covered"),
+                        (coverage::Status::Uncovered, "Uncovered"),
+                    ] {
+                        ui.colored_label(color32(coverage_color(status)), label);
+                    }
+
+                    let summary = overlay.summary();
+                    ui.label(format!("Connections: {}", summary.connections));
+                });
+        }
+
+        if highlights.is_empty() {
+            return;
+        }
+
+        egui::Window::new("Changes")
+            .anchor(Align2::LEFT_BOTTOM, [10., -10.])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                for (status, label) in [
+                    (Status::Added, "Added"),
+                    (Status::Removed, "Removed"),
+                    (Status::Changed, "Changed"),
+                ] {
+                    ui.colored_label(color32(color(status)), label);
+                }
+            });
+    }
+}
+
+fn color32(color: Color) -> Color32 {
+    let [r, g, b, a] = color.to_srgba().to_u8_array();
+    Color32::from_rgba_unmultiplied(r, g, b, a)
+}
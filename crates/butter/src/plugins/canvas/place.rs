@@ -15,6 +15,7 @@ use ast::Coordinate;
 use bevy::asset::Assets;
 use bevy::picking::events::{Click, Pointer};
 use bevy::sprite::Sprite;
+use diff::Status;
 use tracing::field;
 
 use crate::{plugins::input::Target, prelude::*};
@@ -22,6 +23,7 @@ use crate::{plugins::input::Target, prelude::*};
 use super::shared::{TitleNumberSpan, TitleNumberSpanBundle};
 use super::{
     breadboard::{BreadboardCreatedEvent, ShowNumbers},
+    highlight::{self, Highlights},
     shared::{Body, BodyBundle, Description, HeaderBundle, Index, Title, TitleBundle},
     CanvasSet,
 };
@@ -34,25 +36,29 @@ pub(super) struct PlacePlugin;
 
 impl Plugin for PlacePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PlaceCreatedEvent>().add_systems(
-            Update,
-            (
+        app.init_resource::<PlaceFilter>()
+            .init_resource::<Highlights>()
+            .add_event::<PlaceCreatedEvent>()
+            .add_systems(
+                Update,
                 (
-                    create.run_if(on_event::<BreadboardCreatedEvent>),
-                    redraw_underline.run_if(run_redraw_underline),
-                    position_body.run_if(run_position_body),
+                    (
+                        create.run_if(on_event::<BreadboardCreatedEvent>),
+                        redraw_underline.run_if(run_redraw_underline),
+                        position_body.run_if(run_position_body),
+                    )
+                        .chain(),
+                    // position_place.map(err),
+                    position_place
+                        .map(err)
+                        .run_if(any_with_component::<RequiresPositioning>),
+                    toggle_numbering.run_if(resource_changed::<ShowNumbers>),
+                    filter_places.run_if(resource_changed::<PlaceFilter>),
+                    focus_next.run_if(input_just_pressed(KeyCode::ArrowRight)),
+                    focus_last.run_if(input_just_pressed(KeyCode::ArrowLeft)),
                 )
-                    .chain(),
-                // position_place.map(err),
-                position_place
-                    .map(err)
-                    .run_if(any_with_component::<RequiresPositioning>),
-                toggle_numbering.run_if(resource_changed::<ShowNumbers>),
-                focus_next.run_if(input_just_pressed(KeyCode::ArrowRight)),
-                focus_last.run_if(input_just_pressed(KeyCode::ArrowLeft)),
-            )
-                .in_set(CanvasSet::Place),
-        );
+                    .in_set(CanvasSet::Place),
+            );
     }
 }
 
@@ -64,6 +70,46 @@ impl Plugin for PlacePlugin {
 #[derive(Component, Default)]
 pub(super) struct Place;
 
+/// The key/value metadata of a place, as defined by its `@key value` lines in the DSL.
+#[derive(Component, Deref)]
+struct Attributes(ast::Attributes);
+
+/// Filters the visible places by their [`Attributes`].
+///
+/// The filter is either a `key=value` pair, matching places with that exact attribute, or a
+/// `key`, matching places that have the attribute with any value. An empty filter shows all
+/// places.
+#[derive(Resource, Deref, DerefMut, Debug, Default)]
+pub(super) struct PlaceFilter(String);
+
+impl PlaceFilter {
+    fn matches(&self, attributes: Option<&Attributes>) -> bool {
+        let filter = self.trim();
+        if filter.is_empty() {
+            return true;
+        }
+
+        let Some(attributes) = attributes else {
+            return false;
+        };
+
+        match filter.split_once('=') {
+            Some((key, value)) => attributes
+                .get(key.trim())
+                .is_some_and(|v| v == value.trim()),
+            None => attributes.contains_key(filter),
+        }
+    }
+
+    fn visibility(&self, attributes: Option<&Attributes>) -> Visibility {
+        if self.matches(attributes) {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        }
+    }
+}
+
 /// A place that requires placement relative to another place.
 #[derive(Component)]
 struct RequiresPositioning {
@@ -94,12 +140,13 @@ impl Default for PlaceBundle {
 /// Signifies the creation of a place entity within the breadboard.
 ///
 /// Dispatched upon the successful creation of a place entity, this event carries the entity's
-/// identifier and a list of its affordances as defined in the breadboard's DSL. It enables other
+/// identifier, name and a list of its affordances as defined in the breadboard's DSL. It enables other
 /// systems and components to react to the addition of new places, facilitating further
 /// initialization or modification of affordances associated with the place.
 #[derive(Event)]
 pub(crate) struct PlaceCreatedEvent {
     pub entity: Entity,
+    pub name: String,
     pub affordances: Vec<ast::Affordance>,
 }
 
@@ -121,6 +168,7 @@ fn create(
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     mut rng: Rng,
     tokens: Res<DesignTokens>,
+    highlights: Res<Highlights>,
 ) {
     for &BreadboardCreatedEvent {
         entity: breadboard,
@@ -135,6 +183,7 @@ fn create(
             ast::Place {
                 name,
                 description,
+                attributes,
                 items,
                 position,
                 ..
@@ -147,6 +196,7 @@ fn create(
                 .spawn(PlaceBundle::default())
                 .insert(ChildOf(breadboard))
                 .insert(Index(index))
+                .insert(Attributes(attributes))
                 .id();
             span.record("place", format!("{place:?}"));
 
@@ -167,7 +217,8 @@ fn create(
             let header = create_header(
                 &mut cmd,
                 index,
-                name,
+                name.clone(),
+                highlights.place(&name),
                 &asset_server,
                 &mut texture_atlases,
                 &mut rng,
@@ -191,6 +242,7 @@ fn create(
             // TODO: Should this trigger *after* title & underline are positioned?
             created.write(PlaceCreatedEvent {
                 entity: place,
+                name,
                 affordances,
             });
         }
@@ -237,6 +289,7 @@ fn create_header(
     cmd: &mut Commands,
     index: usize,
     name: String,
+    status: Option<Status>,
     asset_server: &AssetServer,
     atlasses: &mut Assets<TextureAtlasLayout>,
     rng: &mut RngComponent,
@@ -248,7 +301,7 @@ fn create_header(
         .load("embedded://bnb_butter/plugins/../../assets/fonts/PermanentMarker-Regular.ttf");
     let image = asset_server.load("embedded://bnb_butter/plugins/../../assets/textures/lines.png");
 
-    let title = create_title(cmd, index + 1, &name, font, tokens);
+    let title = create_title(cmd, index + 1, &name, status, font, tokens);
     let underline = create_underline(cmd, atlasses, image, rng);
     cmd.entity(title).add_child(underline);
 
@@ -278,6 +331,7 @@ fn create_title(
     cmd: &mut Commands,
     index: usize,
     name: &str,
+    status: Option<Status>,
     font: Handle<Font>,
     tokens: &DesignTokens,
 ) -> Entity {
@@ -287,7 +341,7 @@ fn create_title(
         ..default()
     };
 
-    let name_color = TextColor(css::BLACK.into());
+    let name_color = TextColor(status.map_or(css::BLACK.into(), highlight::color));
 
     let numbers_font = TextFont {
         font: font.clone(),
@@ -558,6 +612,8 @@ fn position_place(
     >,
     sizes: ComputedSizeParam<()>,
     children: Query<&ChildOf>,
+    attributes: Query<&Attributes>,
+    filter: Res<PlaceFilter>,
 ) -> Result<(), Error> {
     for (place, RequiresPositioning { x, y }) in &positioning {
         debug!(?place, ?x, ?y, "Positioning place.");
@@ -688,13 +744,60 @@ fn position_place(
                 translation: position.extend(0.0),
                 ..default()
             },
-            Visibility::Visible,
+            filter.visibility(attributes.get(place).ok()),
         ));
     }
 
     Ok(())
 }
 
+/// Shows or hides positioned places, based on the current [`PlaceFilter`].
+///
+/// Places that still require positioning are left hidden, they apply the filter once positioned.
+#[instrument(skip_all)]
+fn filter_places(
+    filter: Res<PlaceFilter>,
+    mut places: Query<
+        (Option<&Attributes>, &mut Visibility),
+        (With<Place>, Without<RequiresPositioning>),
+    >,
+    mut redraw: ResMut<ForceRedraw>,
+) {
+    for (attributes, mut visibility) in &mut places {
+        visibility.set_if_neq(filter.visibility(attributes));
+    }
+
+    redraw.set();
+}
+
+#[derive(SystemParam)]
+pub(crate) struct PlaceFilterInput<'w> {
+    filter: ResMut<'w, PlaceFilter>,
+}
+
+impl WidgetSystem for PlaceFilterInput<'_> {
+    type Args = ();
+    type Output = ();
+
+    fn system(
+        world: &mut World,
+        state: &mut SystemState<Self>,
+        ui: &mut egui::Ui,
+        _: Self::Args,
+    ) -> Self::Output {
+        let PlaceFilterInput { mut filter } = state.get_mut(world);
+
+        let mut curr = filter.as_str().to_owned();
+        let input = egui::TextEdit::singleline(&mut curr)
+            .hint_text("Filter places, e.g. owner=payments")
+            .desired_width(240.);
+
+        if ui.add(input).changed() {
+            **filter = curr;
+        }
+    }
+}
+
 fn toggle_numbering(
     show: Res<ShowNumbers>,
     // mut titles: Query<(&ChildOf, &mut Text), With<Title>>,
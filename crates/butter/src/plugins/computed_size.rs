@@ -43,7 +43,6 @@ pub enum ComputedSize {
 }
 
 impl ComputedSize {
-    #[expect(dead_code)]
     pub fn size(self) -> Option<Vec2> {
         match self {
             ComputedSize::Static(size) => Some(size),
@@ -1,15 +1,50 @@
-use std::path::{Path, PathBuf};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
+use convert::{Options, Registry};
 use rfd::FileDialog;
 
-use crate::prelude::*;
+use crate::{
+    plugins::canvas::{CoverageOverlay, Highlights},
+    prelude::*,
+};
 
 /// Plugin to load and reload files from the file system.
-pub(crate) struct FileWatcherPlugin;
+pub(crate) struct FileWatcherPlugin {
+    /// The file to load on startup, if any.
+    pub file: Option<PathBuf>,
+
+    /// The earlier version of the file to compare it to, if any.
+    pub compare: Option<PathBuf>,
+
+    /// The coverage overlay to load on startup, if any.
+    pub coverage: Option<PathBuf>,
+}
 
 impl Plugin for FileWatcherPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SelectedFile>()
+        match &self.file {
+            Some(file) => app.insert_resource(SelectedFile(file.clone())),
+            None => app.init_resource::<SelectedFile>(),
+        };
+
+        let overlay = self
+            .coverage
+            .as_deref()
+            .and_then(read_overlay)
+            .unwrap_or_default();
+
+        app.insert_resource(ComparedFile(self.compare.clone()))
+            .insert_resource(CoverageOverlay(overlay))
+            .insert_resource(Formats(
+                Registry::default()
+                    .with(converter_json::Json)
+                    .with(converter_toml::Toml)
+                    .with(converter_yaml::Yaml),
+            ))
             .add_event::<FileLoadedEvent>()
             .add_systems(Update, load.run_if(resource_changed::<SelectedFile>));
     }
@@ -20,7 +55,7 @@ impl Plugin for FileWatcherPlugin {
 /// The `load` system is triggered when this resource changes, which means the current breadboard
 /// needs to be unloaded, and the new one loaded.
 #[derive(Resource, Deref, DerefMut)]
-struct SelectedFile(PathBuf);
+pub(crate) struct SelectedFile(PathBuf);
 
 impl Default for SelectedFile {
     fn default() -> Self {
@@ -34,6 +69,19 @@ impl AsRef<Path> for SelectedFile {
     }
 }
 
+/// The source path of the earlier version of the [`SelectedFile`], if the two are compared.
+///
+/// When set, the `load` system renders the overlay of both versions, with their
+/// changes highlighted.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub(crate) struct ComparedFile(Option<PathBuf>);
+
+/// The file formats that can be loaded.
+///
+/// Files are imported using the format matching their file extension.
+#[derive(Resource, Deref)]
+pub(crate) struct Formats(Registry);
+
 /// The watcher resource.
 ///
 /// This stores a receiver for a channel on which activity happens if the watched file is modified.
@@ -46,14 +94,20 @@ impl AsRef<Path> for SelectedFile {
 #[expect(dead_code)]
 struct Watcher(());
 
-/// Event triggered when a file was loaded.
+/// Event triggered when a file was loaded and imported.
 #[derive(Event)]
 pub(crate) struct FileLoadedEvent {
     pub name: String,
-    pub contents: String,
+    pub breadboard: ast::Breadboard,
 }
 
-fn load(source: Res<SelectedFile>, mut event: EventWriter<FileLoadedEvent>) {
+fn load(
+    source: Res<SelectedFile>,
+    compared: Res<ComparedFile>,
+    formats: Res<Formats>,
+    mut highlights: ResMut<Highlights>,
+    mut event: EventWriter<FileLoadedEvent>,
+) {
     if !source.is_file() {
         // TODO: Trigger `alert` widget.
         return;
@@ -64,17 +118,79 @@ fn load(source: Res<SelectedFile>, mut event: EventWriter<FileLoadedEvent>) {
         return;
     };
 
-    let Ok(contents) = std::fs::read_to_string(&*source) else {
-        // TODO: Trigger `alert` widget.
+    let Some(mut breadboard) = read(&source, &formats) else {
         return;
     };
 
-    event.write(FileLoadedEvent { name, contents });
+    *highlights = Highlights::default();
+    if let Some(old) = compared.as_deref().and_then(|path| read(path, &formats)) {
+        let overlay = diff::overlay(&old, &breadboard);
+        breadboard = overlay.breadboard.clone();
+        **highlights = overlay;
+    }
+
+    event.write(FileLoadedEvent { name, breadboard });
+}
+
+/// Reads the breadboard at the given path, using the format matching its file extension.
+fn read(path: &Path, formats: &Formats) -> Option<ast::Breadboard> {
+    // DSL files are loaded along with the files they import.
+    if formats.for_path(path).is_some_and(|f| f.name() == "bnb") {
+        return match parser::load(path, &parser::OsFileSystem) {
+            Ok(breadboard) => Some(breadboard),
+            Err(error) => {
+                // TODO: Trigger `alert` widget.
+                error!(file = %error.path().display(), %error, "Unable to load breadboard.");
+                None
+            }
+        };
+    }
+
+    let Some(importer) = formats.for_path(path).and_then(|f| f.importer()) else {
+        // TODO: Trigger `alert` widget.
+        error!(file = %path.display(), "Unsupported breadboard file format.");
+        return None;
+    };
+
+    let Ok(file) = File::open(path) else {
+        // TODO: Trigger `alert` widget.
+        return None;
+    };
+
+    match importer.import(&mut BufReader::new(file), &Options::default()) {
+        Ok(breadboard) => Some(breadboard),
+        Err(error) => {
+            // TODO: Trigger `alert` widget.
+            error!(?error, "Unable to import breadboard.");
+            None
+        }
+    }
+}
+
+/// Reads the coverage overlay at the given path.
+fn read_overlay(path: &Path) -> Option<coverage::Coverage> {
+    let Ok(file) = File::open(path) else {
+        // TODO: Trigger `alert` widget.
+        error!(file = %path.display(), "Unable to open coverage overlay.");
+        return None;
+    };
+
+    match coverage::read_overlay(BufReader::new(file)) {
+        Ok(coverage) => Some(coverage),
+        Err(error) => {
+            // TODO: Trigger `alert` widget.
+            error!(%error, "Unable to read coverage overlay.");
+            None
+        }
+    }
 }
 
 #[derive(SystemParam)]
 pub(crate) struct LoadButton<'w> {
+    formats: Res<'w, Formats>,
     load_path: ResMut<'w, SelectedFile>,
+    compared: ResMut<'w, ComparedFile>,
+    overlay: ResMut<'w, CoverageOverlay>,
     redraw: ResMut<'w, ForceRedraw>,
 }
 
@@ -89,18 +205,30 @@ impl WidgetSystem for LoadButton<'_> {
         _: Self::Args,
     ) -> Self::Output {
         let LoadButton {
+            formats,
             mut load_path,
+            mut compared,
+            mut overlay,
             mut redraw,
         } = state.get_mut(world);
 
         if ui.button("Load Breadboard…").clicked() {
+            let extensions: Vec<_> = formats
+                .importers()
+                .flat_map(convert::Format::extensions)
+                .collect();
+
             if let Some(file) = FileDialog::new()
                 .set_title("Open Breadboard File")
-                .add_filter("breadboard", &["bnb"])
+                .add_filter("breadboard", &extensions)
                 .set_directory(&*load_path)
                 .pick_file()
             {
                 **load_path = file;
+                **compared = None;
+
+                // The overlay belongs to the breadboard it was recorded against.
+                *overlay = CoverageOverlay::default();
             }
 
             // TODO: this is supposed to help with the active button state not changing unless you
@@ -0,0 +1,190 @@
+//! History Plugin: Browsing Earlier Revisions of a Breadboard
+//!
+//! When a DSL file tracked in a git repository is loaded, the [`HistoryPlugin`] reads its earlier
+//! revisions in the background, as running git can take a while for long histories. A slider in
+//! the navigation bar re-renders the breadboard as of any of these revisions, overlaid on the
+//! previous revision to highlight the changes made by it.
+
+use std::path::Path;
+
+use bevy::{
+    tasks::{futures::check_ready, AsyncComputeTaskPool, Task},
+    window::RequestRedraw,
+};
+use diff::Overlay;
+
+use crate::{
+    plugins::{
+        canvas::Highlights,
+        file_watcher::{FileLoadedEvent, Formats, SelectedFile},
+    },
+    prelude::*,
+};
+
+/// Reads the git history of the loaded breadboard.
+pub(crate) struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Timeline>().add_systems(
+            Update,
+            (
+                load.run_if(resource_changed::<SelectedFile>),
+                poll.run_if(|timeline: Res<'_, Timeline>| timeline.task.is_some()),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// A revision of the loaded breadboard that parsed.
+struct Revision {
+    /// The short hash and summary of the commit.
+    label: String,
+
+    /// The breadboard of the commit, overlaid on the previous revision that parsed.
+    overlay: Overlay,
+}
+
+/// The revisions of the loaded breadboard, oldest first.
+#[derive(Resource, Default)]
+pub(super) struct Timeline {
+    /// The name of the loaded file.
+    name: String,
+    revisions: Vec<Revision>,
+
+    /// The revision that is shown, or `None` for the file as it is on disk.
+    selected: Option<usize>,
+
+    /// The task reading the revisions, until it completes.
+    task: Option<Task<Vec<Revision>>>,
+}
+
+/// Starts reading the revisions of a newly selected file.
+///
+/// Files that are not tracked in a git repository, or are not in the DSL format, have no
+/// revisions. Replacing the timeline drops any task still reading the previous file.
+#[instrument(skip_all)]
+fn load(source: Res<SelectedFile>, formats: Res<Formats>, mut timeline: ResMut<Timeline>) {
+    *timeline = Timeline::default();
+
+    let is_dsl = formats.for_path(&source).is_some_and(|f| f.name() == "bnb");
+    if !source.is_file() || !is_dsl {
+        return;
+    }
+
+    timeline.name = source
+        .file_name()
+        .map(|v| v.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let path = source.to_path_buf();
+    timeline.task = Some(AsyncComputeTaskPool::get().spawn(async move { revisions(&path) }));
+}
+
+/// Stores the revisions once they are read.
+#[instrument(skip_all)]
+fn poll(mut timeline: ResMut<Timeline>, mut redraw: EventWriter<RequestRedraw>) {
+    // Keep the app updating while the task runs, so that the slider shows up as soon as the
+    // revisions are read.
+    redraw.write(RequestRedraw);
+
+    if let Some(revisions) = timeline.task.as_mut().and_then(check_ready) {
+        timeline.task = None;
+        timeline.revisions = revisions;
+    }
+}
+
+/// Reads the revisions of a file, each overlaid on the previous revision that parsed.
+fn revisions(path: &Path) -> Vec<Revision> {
+    let revisions = match history::revisions(path, &history::Git) {
+        Ok(revisions) => revisions,
+        Err(error) => {
+            debug!(%error, "No history available.");
+            return vec![];
+        }
+    };
+
+    let mut previous = ast::Breadboard::default();
+    revisions
+        .into_iter()
+        .filter_map(|revision| {
+            let breadboard = revision.breadboard.ok()?;
+            let commit = revision.commit;
+
+            let revision = Revision {
+                label: format!(
+                    "{} {}",
+                    commit.id.get(..7).unwrap_or(&commit.id),
+                    commit.summary
+                ),
+                overlay: diff::overlay(&previous, &breadboard),
+            };
+            previous = breadboard;
+
+            Some(revision)
+        })
+        .collect()
+}
+
+#[derive(SystemParam)]
+pub(crate) struct TimelineSlider<'w> {
+    timeline: ResMut<'w, Timeline>,
+    source: ResMut<'w, SelectedFile>,
+    highlights: ResMut<'w, Highlights>,
+    loaded: EventWriter<'w, FileLoadedEvent>,
+}
+
+impl WidgetSystem for TimelineSlider<'_> {
+    type Args = ();
+    type Output = ();
+
+    fn system(
+        world: &mut World,
+        state: &mut SystemState<Self>,
+        ui: &mut egui::Ui,
+        _: Self::Args,
+    ) -> Self::Output {
+        let TimelineSlider {
+            mut timeline,
+            mut source,
+            mut highlights,
+            mut loaded,
+        } = state.get_mut(world);
+
+        let count = timeline.revisions.len();
+        if count == 0 {
+            return;
+        }
+
+        // The last position of the slider is the file as it is on disk.
+        let mut position = timeline.selected.unwrap_or(count);
+        let label = timeline
+            .revisions
+            .get(position)
+            .map_or("Working copy", |revision| revision.label.as_str())
+            .to_owned();
+
+        let slider = egui::Slider::new(&mut position, 0..=count)
+            .show_value(false)
+            .text(label);
+
+        if !ui.add(slider).changed() {
+            return;
+        }
+
+        let Some(revision) = timeline.revisions.get(position) else {
+            // Reloading the file from disk also resets the timeline.
+            source.set_changed();
+            return;
+        };
+
+        **highlights = revision.overlay.clone();
+        loaded.write(FileLoadedEvent {
+            name: timeline.name.clone(),
+            breadboard: revision.overlay.breadboard.clone(),
+        });
+
+        timeline.selected = Some(position);
+    }
+}
@@ -6,7 +6,7 @@ use bevy_egui::{
 };
 use dtoken::types::color::Color;
 
-use crate::{prelude::*, widget::WorldWidgetSystemExt as _};
+use crate::{plugins::canvas::Legend, prelude::*, widget::WorldWidgetSystemExt as _};
 
 pub(crate) struct UiPlugin;
 
@@ -158,4 +158,5 @@ fn apply_base_theme(tokens: Res<DesignTokens>, mut contexts: EguiContexts) {
 #[instrument(level = "trace", skip_all)]
 fn render(world: &mut World) {
     world.root_widget_with::<navbar::NavBar>("navbar", ());
+    world.root_widget_with::<Legend>("legend", ());
 }
@@ -2,7 +2,11 @@ use bevy::ecs::system::{SystemParam, SystemState};
 use bevy_egui::egui::{self, Vec2};
 
 use crate::{
-    plugins::{canvas::ShowNumbersCheckbox, file_watcher::LoadButton},
+    plugins::{
+        canvas::{FlowPlayer, PlaceFilterInput, ShowNumbersCheckbox},
+        file_watcher::LoadButton,
+        history::TimelineSlider,
+    },
     prelude::*,
     widget::RootWidgetSystem,
 };
@@ -29,6 +33,9 @@ impl RootWidgetSystem for NavBar {
                     ui.style_mut().spacing.button_padding = Vec2::splat(10.);
                     ui.add_system::<LoadButton>(world, "load_button");
                     ui.add_system::<ShowNumbersCheckbox>(world, "show_numbers");
+                    ui.add_system::<PlaceFilterInput>(world, "place_filter");
+                    ui.add_system::<FlowPlayer>(world, "flow_player");
+                    ui.add_system::<TimelineSlider>(world, "timeline_slider");
                 });
             });
     }
@@ -0,0 +1,15 @@
+//! The `bnb` subcommands, one module per command.
+
+pub(crate) mod check;
+pub(crate) mod convert;
+pub(crate) mod coverage;
+pub(crate) mod diff;
+pub(crate) mod fmt;
+pub(crate) mod formats;
+pub(crate) mod history;
+pub(crate) mod open;
+pub(crate) mod paths;
+pub(crate) mod query;
+pub(crate) mod rename;
+pub(crate) mod schema;
+pub(crate) mod stats;
@@ -0,0 +1,125 @@
+//! `bnb check`: Parse and validate breadboard files.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_ast::validate::Config;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Parse and validate breadboard files.
+///
+/// Exits with `1` if any of the files is invalid.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The files to check, `-` reads from standard input.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// The format of the files, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+
+    /// The allowed affordance kinds, e.g. `button,input`. Any kind is allowed by default.
+    #[arg(long, value_name = "KINDS", value_delimiter = ',')]
+    affordance_kinds: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    valid: bool,
+    files: Vec<FileReport>,
+}
+
+#[derive(Serialize)]
+struct FileReport {
+    path: PathBuf,
+    valid: bool,
+    errors: Vec<Diagnostic>,
+}
+
+#[derive(Serialize)]
+struct Diagnostic {
+    kind: &'static str,
+    message: String,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let mut files = vec![];
+
+    let config = Config {
+        affordance_kinds: args.affordance_kinds,
+    };
+
+    for path in args.files {
+        let errors = match input::load(&path, args.from.as_deref()) {
+            Ok(breadboard) => breadboard
+                .validate_with(&config)
+                .into_iter()
+                .map(|violation| Diagnostic {
+                    kind: "validation",
+                    message: violation.to_string(),
+                })
+                .collect(),
+            Err(Error::Convert {
+                source: bnb_convert::Error::Invalid(source),
+                ..
+            }) => vec![Diagnostic {
+                kind: "invalid",
+                message: source.to_string(),
+            }],
+            Err(Error::Load { source, .. })
+                if !matches!(source, bnb_parser::LoadError::Read { .. }) =>
+            {
+                vec![Diagnostic {
+                    kind: "invalid",
+                    message: source.to_string(),
+                }]
+            }
+            Err(error) => return Err(error),
+        };
+
+        files.push(FileReport {
+            path: input::display(&path),
+            valid: errors.is_empty(),
+            errors,
+        });
+    }
+
+    let report = Report {
+        valid: files.iter().all(|file| file.valid),
+        files,
+    };
+
+    output::report(format, &report);
+
+    Ok(if report.valid {
+        Status::Success
+    } else {
+        Status::Failure
+    })
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            let path = file.path.display();
+
+            if file.valid {
+                writeln!(f, "{path}: ok")?;
+                continue;
+            }
+
+            for error in &file.errors {
+                writeln!(f, "{path}: {} error: {}", error.kind, error.message)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+//! `bnb convert`: Convert a breadboard between formats.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_convert::Options;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Convert a breadboard between the DSL and other formats.
+///
+/// A report is printed only if the output is written to a file, since standard output receives the
+/// converted breadboard.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The file to convert, `-` reads from standard input.
+    #[arg(default_value = input::STDIO)]
+    input: PathBuf,
+
+    /// The file to write to, `-` writes to standard output.
+    #[arg(short, long, default_value = input::STDIO)]
+    output: PathBuf,
+
+    /// The format of the input, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+
+    /// The format of the output, detected from the file extension by default.
+    #[arg(long)]
+    to: Option<String>,
+
+    /// Produce human-readable output, if supported by the output format.
+    #[arg(long)]
+    pretty: bool,
+
+    /// Produce output with a stable layout (e.g. sorted keys), if supported by the output format.
+    #[arg(long)]
+    canonical: bool,
+}
+
+#[derive(Serialize)]
+struct Report {
+    input: PathBuf,
+    output: PathBuf,
+    format: String,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        input,
+        output,
+        from,
+        to,
+        pretty,
+        canonical,
+    } = args;
+
+    let registry = input::registry();
+
+    // Standard output has no extension to detect the format from, so it defaults to the DSL, the
+    // same as standard input.
+    let to = input::format(&registry, to.as_deref(), &output)?;
+    let breadboard = input::load(&input, from.as_deref())?;
+
+    let error = |source| Error::Convert {
+        path: output.clone(),
+        source,
+    };
+    let exporter = to.exporter().ok_or_else(|| {
+        error(bnb_convert::Error::Unsupported {
+            format: to.name().to_owned(),
+            operation: "export",
+        })
+    })?;
+
+    let mut contents = vec![];
+    exporter
+        .export(&mut contents, &breadboard, &Options { pretty, canonical })
+        .map_err(error)?;
+
+    input::write(&output, &contents)?;
+
+    if !input::is_stdio(&output) {
+        let report = Report {
+            input: input::display(&input),
+            output,
+            format: to.name().to_owned(),
+        };
+
+        output::report(format, &report);
+    }
+
+    Ok(Status::Success)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: converted to {} ({})",
+            self.input.display(),
+            self.output.display(),
+            self.format
+        )
+    }
+}
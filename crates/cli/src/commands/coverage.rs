@@ -0,0 +1,192 @@
+//! `bnb coverage`: Report which parts of a breadboard a recorded navigation trace covers.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_coverage::{Drift, Ratio, Summary};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Report the share of places, affordances and connections of a breadboard covered by a recorded
+/// navigation trace, and the transitions of the trace that do not exist in the breadboard.
+///
+/// The trace is a JSON-lines file of `{"place": …, "affordance": …, "target": …}` events.
+/// Exits with `1` if any transition of the trace does not exist in the breadboard.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The breadboard file.
+    file: PathBuf,
+
+    /// The trace file, `-` reads from standard input.
+    trace: PathBuf,
+
+    /// Write a coverage overlay to the given file, to show the coverage with `bnb open --coverage`.
+    ///
+    /// `-` writes the overlay to standard output, instead of the report.
+    #[arg(long, value_name = "FILE")]
+    overlay: Option<PathBuf>,
+
+    /// The format of the breadboard file, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    places: Share,
+    affordances: Share,
+    connections: Share,
+
+    /// The connections that no event of the trace followed.
+    uncovered: Vec<Uncovered>,
+
+    /// The events of the trace that do not exist in the breadboard.
+    drift: Vec<Drift>,
+}
+
+#[derive(Serialize)]
+struct Share {
+    covered: usize,
+    total: usize,
+    percent: f64,
+}
+
+impl From<Ratio> for Share {
+    fn from(ratio: Ratio) -> Self {
+        Self {
+            covered: ratio.covered,
+            total: ratio.total,
+            percent: ratio.percent(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Uncovered {
+    place: String,
+    affordance: String,
+    description: Option<String>,
+    target: String,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        file,
+        trace: trace_path,
+        overlay,
+        from,
+    } = args;
+    let breadboard = input::load(&file, from.as_deref())?;
+    let trace =
+        bnb_coverage::read_trace(input::read(&trace_path)?.as_bytes()).map_err(|source| {
+            Error::Trace {
+                path: trace_path.clone(),
+                source,
+            }
+        })?;
+
+    let coverage = bnb_coverage::coverage(&breadboard, &trace);
+
+    if let Some(path) = &overlay {
+        let mut contents = vec![];
+        bnb_coverage::write_overlay(&mut contents, &coverage)
+            .expect("coverage is always serializable");
+        input::write(path, &contents)?;
+    }
+
+    let mut uncovered = vec![];
+    for place in &coverage.places {
+        for affordance in &place.affordances {
+            for connection in affordance.connections.iter().filter(|c| c.hits == 0) {
+                uncovered.push(Uncovered {
+                    place: place.name.clone(),
+                    affordance: affordance.name.clone(),
+                    description: connection.description.clone(),
+                    target: connection.target.clone(),
+                });
+            }
+        }
+    }
+
+    let Summary {
+        places,
+        affordances,
+        connections,
+    } = coverage.summary();
+    let status = if coverage.drift.is_empty() {
+        Status::Success
+    } else {
+        Status::Failure
+    };
+
+    // An overlay written to standard output cannot be followed by the report.
+    if !overlay.as_deref().is_some_and(input::is_stdio) {
+        output::report(
+            format,
+            &Report {
+                places: places.into(),
+                affordances: affordances.into(),
+                connections: connections.into(),
+                uncovered,
+                drift: coverage.drift,
+            },
+        );
+    }
+
+    Ok(status)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (label, share) in [
+            ("places", &self.places),
+            ("affordances", &self.affordances),
+            ("connections", &self.connections),
+        ] {
+            writeln!(
+                f,
+                "{label:<12} {}/{} ({:.1}%)",
+                share.covered, share.total, share.percent
+            )?;
+        }
+
+        if !self.uncovered.is_empty() {
+            writeln!(f, "\nuncovered connections:")?;
+
+            for Uncovered {
+                place,
+                affordance,
+                description,
+                target,
+            } in &self.uncovered
+            {
+                write!(f, "  {place}: {affordance} -> ")?;
+                if let Some(description) = description {
+                    write!(f, "({description}) ")?;
+                }
+                writeln!(f, "{target}")?;
+            }
+        }
+
+        if !self.drift.is_empty() {
+            writeln!(f, "\ndrift, transitions missing from the breadboard:")?;
+
+            for Drift {
+                event,
+                reason,
+                count,
+            } in &self.drift
+            {
+                let events = if *count == 1 { "event" } else { "events" };
+                writeln!(f, "  {event} ({reason}, {count} {events})")?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! `bnb diff`: Compare two breadboards at the design level.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_diff::Diff;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Compare two breadboards, listing the places, affordances and connections that changed.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The old version of the breadboard, `-` reads from standard input.
+    old: PathBuf,
+
+    /// The new version of the breadboard, `-` reads from standard input.
+    new: PathBuf,
+
+    /// The format of both files, detected from the file extensions by default.
+    #[arg(long)]
+    from: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(transparent)]
+struct Report(Diff);
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args { old, new, from } = args;
+    let old = input::load(&old, from.as_deref())?;
+    let new = input::load(&new, from.as_deref())?;
+
+    output::report(format, &Report(bnb_diff::diff(&old, &new)));
+
+    Ok(Status::Success)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,155 @@
+//! `bnb fmt`: Format breadboard files.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Format breadboard files in-place.
+///
+/// Formatting goes through the AST, which does not retain regular `//` comments. Files containing
+/// such comments are skipped, unless `--strip-comments` is passed.
+///
+/// The AST also keeps places and components apart, so formatting moves every component after the
+/// places, in the order they are defined.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The files to format, `-` formats standard input to standard output.
+    #[arg(default_value = input::STDIO)]
+    files: Vec<PathBuf>,
+
+    /// Do not write any files, exit with `1` if any file is not formatted.
+    #[arg(long)]
+    check: bool,
+
+    /// Format files containing regular `//` comments, removing those comments.
+    #[arg(long)]
+    strip_comments: bool,
+}
+
+#[derive(Serialize)]
+struct Report {
+    files: Vec<FileReport>,
+}
+
+#[derive(Serialize)]
+struct FileReport {
+    path: PathBuf,
+    status: FileStatus,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum FileStatus {
+    /// The file was already formatted.
+    Unchanged,
+
+    /// The file was formatted.
+    Formatted,
+
+    /// The file is not formatted (only reported with `--check`).
+    Unformatted,
+
+    /// The file was skipped, because formatting would remove comments.
+    Skipped,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let mut files = vec![];
+    let mut skipped_stdin = false;
+    let mut wrote_stdout = false;
+
+    for path in args.files {
+        let contents = input::read(&path)?;
+        let breadboard = input::import(&path, &contents, &bnb_convert::Bnb)?;
+        let formatted = bnb_parser::print(&breadboard);
+
+        let status = if formatted == contents {
+            FileStatus::Unchanged
+        } else if !args.strip_comments && bnb_parser::has_comments(&contents) {
+            FileStatus::Skipped
+        } else if args.check {
+            FileStatus::Unformatted
+        } else {
+            FileStatus::Formatted
+        };
+
+        // Standard input is always written to standard output, even if nothing changed, so the
+        // report cannot be printed to standard output as well.
+        if input::is_stdio(&path) && !args.check {
+            if status == FileStatus::Skipped {
+                eprintln!("{}", Skipped(&input::display(&path)));
+                input::write(&path, contents.as_bytes())?;
+                skipped_stdin = true;
+            } else {
+                input::write(&path, formatted.as_bytes())?;
+            }
+
+            wrote_stdout = true;
+            continue;
+        }
+
+        if status == FileStatus::Formatted {
+            input::write(&path, formatted.as_bytes())?;
+        }
+
+        files.push(FileReport {
+            path: input::display(&path),
+            status,
+        });
+    }
+
+    let report = Report { files };
+    if !wrote_stdout {
+        output::report(format, &report);
+    }
+
+    let failed = skipped_stdin
+        || report
+            .files
+            .iter()
+            .any(|file| matches!(file.status, FileStatus::Unformatted | FileStatus::Skipped));
+
+    Ok(if failed {
+        Status::Failure
+    } else {
+        Status::Success
+    })
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            let path = file.path.display();
+            match file.status {
+                FileStatus::Unchanged => {}
+                FileStatus::Formatted => writeln!(f, "{path}: formatted")?,
+                FileStatus::Unformatted => writeln!(f, "{path}: not formatted")?,
+                FileStatus::Skipped => writeln!(f, "{}", Skipped(&file.path))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Skipped<'a>(&'a Path);
+
+impl fmt::Display for Skipped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: skipped, formatting would remove `//` comments (see --strip-comments)",
+            self.0.display()
+        )
+    }
+}
@@ -0,0 +1,66 @@
+//! `bnb formats`: List the supported file formats.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// List the supported file formats, for use with `--from` and `--to`.
+#[derive(clap::Args)]
+pub(crate) struct Args {}
+
+#[derive(Serialize)]
+struct Report {
+    formats: Vec<FormatReport>,
+}
+
+#[derive(Serialize)]
+struct FormatReport {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    import: bool,
+    export: bool,
+}
+
+pub(crate) fn run(_: &Args, format: OutputFormat) -> Status {
+    let formats = input::registry()
+        .formats()
+        .map(|format| FormatReport {
+            name: format.name(),
+            extensions: format.extensions(),
+            import: format.importer().is_some(),
+            export: format.exporter().is_some(),
+        })
+        .collect();
+
+    output::report(format, &Report { formats });
+
+    Status::Success
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for format in &self.formats {
+            let operations = match (format.import, format.export) {
+                (true, true) => "import, export",
+                (true, false) => "import",
+                (false, true) => "export",
+                (false, false) => "none",
+            };
+
+            writeln!(
+                f,
+                "{:<8} .{:<12} {operations}",
+                format.name,
+                format.extensions.join(", .")
+            )?;
+        }
+
+        Ok(())
+    }
+}
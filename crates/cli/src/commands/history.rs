@@ -0,0 +1,57 @@
+//! `bnb history`: Print the structural changes of a breadboard file, commit by commit.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_history::{Entry, Git};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Print the structural changes made to a breadboard file by each commit in its git history.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The breadboard file, which must be tracked in a git repository.
+    file: PathBuf,
+}
+
+#[derive(Serialize)]
+struct Report {
+    revisions: Vec<Entry>,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args { file } = args;
+    let revisions = bnb_history::revisions(&file, &Git).map_err(Error::History)?;
+
+    output::report(
+        format,
+        &Report {
+            revisions: bnb_history::timeline(&revisions),
+        },
+    );
+
+    Ok(Status::Success)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Entry { commit, diff } in &self.revisions {
+            let id = commit.id.get(..7).unwrap_or(&commit.id);
+            writeln!(f, "{id} {}", commit.summary)?;
+
+            if diff.is_empty() {
+                writeln!(f, "  no changes")?;
+            }
+
+            for change in &diff.changes {
+                writeln!(f, "  {change}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
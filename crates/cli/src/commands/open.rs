@@ -0,0 +1,112 @@
+//! `bnb open`: Open a breadboard in the Butter GUI.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// The name of the Butter GUI binary.
+const BUTTER: &str = "bnb-butter";
+
+/// The environment variable that overrides the path to the Butter GUI binary.
+const BUTTER_ENV: &str = "BNB_BUTTER";
+
+/// Open a breadboard in the Butter GUI.
+///
+/// The `bnb-butter` binary is looked up in the `BNB_BUTTER` environment variable, next to the
+/// `bnb` binary, and in the `PATH`, in that order.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The breadboard file to open.
+    file: PathBuf,
+
+    /// An earlier version of the file, to highlight the changes made since.
+    #[arg(long, value_name = "OLD")]
+    compare: Option<PathBuf>,
+
+    /// A coverage overlay written by `bnb coverage --overlay`, to tint covered and uncovered
+    /// affordances.
+    #[arg(long, value_name = "OVERLAY")]
+    coverage: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    file: PathBuf,
+
+    /// The Butter GUI binary that was launched.
+    butter: PathBuf,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        file,
+        compare,
+        coverage,
+    } = args;
+
+    // Fail early, instead of launching a GUI with nothing to show.
+    for path in std::iter::once(&file).chain(&compare).chain(&coverage) {
+        if let Err(source) = std::fs::metadata(path) {
+            return Err(Error::Read {
+                path: path.clone(),
+                source,
+            });
+        }
+    }
+
+    let butter = butter();
+    let mut command = Command::new(&butter);
+    command.arg(&file);
+
+    if let Some(old) = compare {
+        command.arg("--compare").arg(old);
+    }
+
+    if let Some(overlay) = coverage {
+        command.arg("--coverage").arg(overlay);
+    }
+
+    command.spawn().map_err(Error::Launch)?;
+
+    output::report(format, &Report { file, butter });
+
+    Ok(Status::Success)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: opened in {}",
+            self.file.display(),
+            self.butter.display()
+        )
+    }
+}
+
+fn butter() -> PathBuf {
+    if let Some(path) = std::env::var_os(BUTTER_ENV) {
+        return PathBuf::from(path);
+    }
+
+    std::env::current_exe()
+        .ok()
+        .as_deref()
+        .and_then(Path::parent)
+        .map(|dir| {
+            dir.join(BUTTER)
+                .with_extension(std::env::consts::EXE_EXTENSION)
+        })
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(BUTTER))
+}
@@ -0,0 +1,371 @@
+//! `bnb paths`: Generate test paths that follow every connection of a breadboard.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_ast::Breadboard;
+use bnb_graph::{Edge, Graph};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Generate a minimal set of test paths from an entry place, which together follow every
+/// connection that can be reached from it at least once.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The file to generate paths for, `-` reads from standard input.
+    #[arg(default_value = input::STDIO)]
+    file: PathBuf,
+
+    /// The place every path starts at, the first place by default.
+    #[arg(long, value_name = "PLACE")]
+    entry: Option<String>,
+
+    /// The format of the test plan.
+    #[arg(long, value_enum, default_value_t)]
+    to: Plan,
+
+    /// The format of the file, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+}
+
+/// The format of a test plan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum Plan {
+    /// A Markdown checklist per path.
+    #[default]
+    Markdown,
+
+    /// A CSV table with a row per step, followed by a row per unreachable connection, with
+    /// `unreachable` as its path.
+    Csv,
+
+    /// A Gherkin feature with a scenario per path.
+    Gherkin,
+}
+
+#[derive(Serialize)]
+struct Report {
+    #[serde(skip)]
+    plan: Plan,
+
+    entry: String,
+    paths: Vec<Vec<Step>>,
+
+    /// The connections that cannot be reached from the entry place.
+    unreachable: Vec<Step>,
+}
+
+#[derive(Serialize)]
+struct Step {
+    place: String,
+    affordance: String,
+    description: Option<String>,
+    target: String,
+}
+
+impl From<&Edge> for Step {
+    fn from(edge: &Edge) -> Self {
+        Self {
+            place: edge.from.clone(),
+            affordance: edge.affordance.clone(),
+            description: edge.description.clone(),
+            target: edge.to.clone(),
+        }
+    }
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        file,
+        entry,
+        to,
+        from,
+    } = args;
+    let breadboard = input::load(&file, from.as_deref())?;
+
+    output::report(format, &report(&breadboard, entry, to)?);
+
+    Ok(Status::Success)
+}
+
+fn report(breadboard: &Breadboard, entry: Option<String>, plan: Plan) -> Result<Report, Error> {
+    let graph = Graph::new(breadboard);
+
+    let mut report = Report {
+        plan,
+        entry: String::new(),
+        paths: vec![],
+        unreachable: vec![],
+    };
+
+    // A breadboard without places has nothing to cover.
+    if let Some(entry) = entry.or_else(|| graph.places().next().map(str::to_owned)) {
+        let cover = graph.cover(&entry).map_err(Error::Graph)?;
+
+        report.paths = cover
+            .paths
+            .into_iter()
+            .map(|path| path.into_iter().map(Step::from).collect())
+            .collect();
+        report.unreachable = cover.unreachable.into_iter().map(Step::from).collect();
+        report.entry = entry;
+    }
+
+    Ok(report)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.plan {
+            Plan::Markdown => self.fmt_markdown(f),
+            Plan::Csv => self.fmt_csv(f),
+            Plan::Gherkin => self.fmt_gherkin(f),
+        }
+    }
+}
+
+impl Report {
+    fn fmt_markdown(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Test paths from {}", self.entry)?;
+
+        for (i, path) in self.paths.iter().enumerate() {
+            writeln!(f, "\n## Path {}\n", i + 1)?;
+
+            for step in path {
+                write!(f, "- [ ] In {}, trigger {}", step.place, step.affordance)?;
+                if let Some(description) = &step.description {
+                    write!(f, " ({description})")?;
+                }
+                writeln!(f, ", expect {}", step.target)?;
+            }
+        }
+
+        if !self.unreachable.is_empty() {
+            writeln!(f, "\n## Unreachable\n")?;
+            writeln!(
+                f,
+                "These connections cannot be reached from {}:\n",
+                self.entry
+            )?;
+
+            for step in &self.unreachable {
+                writeln!(f, "- {}", Transition(step))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fmt_csv(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "path,step,place,affordance,description,target")?;
+
+        let paths = self.paths.iter().enumerate().map(|(i, path)| (i + 1, path));
+        for (i, path) in paths {
+            for (j, step) in path.iter().enumerate() {
+                fmt_csv_row(f, &i, j + 1, step)?;
+            }
+        }
+
+        for (j, step) in self.unreachable.iter().enumerate() {
+            fmt_csv_row(f, &"unreachable", j + 1, step)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_gherkin(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entry = gherkin(&self.entry);
+
+        writeln!(f, "Feature: Navigation from {entry}")?;
+        writeln!(
+            f,
+            "  Every connection reachable from {entry} is followed at least once."
+        )?;
+
+        for (i, path) in self.paths.iter().enumerate() {
+            let last = path
+                .last()
+                .map_or(entry.clone(), |step| gherkin(&step.target));
+
+            writeln!(f, "\n  Scenario: Path {} to {last}", i + 1)?;
+            writeln!(f, "    Given I am on {entry}")?;
+
+            for step in path {
+                write!(f, "    When I trigger {}", gherkin(&step.affordance))?;
+                if let Some(description) = &step.description {
+                    write!(f, " with outcome {}", gherkin(description))?;
+                }
+                writeln!(f, "\n    Then I am on {}", gherkin(&step.target))?;
+            }
+        }
+
+        if !self.unreachable.is_empty() {
+            writeln!(f, "\n  # Unreachable from {entry}:")?;
+
+            for step in &self.unreachable {
+                writeln!(f, "  # - {}", Transition(step))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays a step as `Place: Affordance -> (description) Target`.
+struct Transition<'a>(&'a Step);
+
+impl fmt::Display for Transition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Step {
+            place,
+            affordance,
+            description,
+            target,
+        } = self.0;
+
+        write!(f, "{place}: {affordance} -> ")?;
+        if let Some(description) = description {
+            write!(f, "({description}) ")?;
+        }
+        write!(f, "{target}")
+    }
+}
+
+/// Writes a step as a CSV row.
+fn fmt_csv_row(
+    f: &mut fmt::Formatter<'_>,
+    path: &dyn fmt::Display,
+    index: usize,
+    step: &Step,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "{path},{index},{},{},{},{}",
+        csv(&step.place),
+        csv(&step.affordance),
+        csv(step.description.as_deref().unwrap_or_default()),
+        csv(&step.target)
+    )
+}
+
+/// Returns the value as a CSV field, quoted if necessary.
+fn csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Returns the value as a quoted Gherkin step argument.
+fn gherkin(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const INPUT: &str = indoc! {"
+        place Home
+          Sign In -> Login
+          Help -> Support
+
+        place Login
+          Submit -> (success) Home
+                 -> (failure, \"again\") Login
+
+        place Support
+
+        place Orphan
+          Go -> Home
+    "};
+
+    fn render(plan: Plan) -> String {
+        let breadboard = bnb_parser::parse(INPUT).unwrap();
+
+        report(&breadboard, None, plan).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            render(Plan::Markdown),
+            indoc! {r#"
+                # Test paths from Home
+
+                ## Path 1
+
+                - [ ] In Home, trigger Sign In, expect Login
+                - [ ] In Login, trigger Submit (failure, "again"), expect Login
+                - [ ] In Login, trigger Submit (success), expect Home
+                - [ ] In Home, trigger Help, expect Support
+
+                ## Unreachable
+
+                These connections cannot be reached from Home:
+
+                - Orphan: Go -> Home
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            render(Plan::Csv),
+            indoc! {r#"
+                path,step,place,affordance,description,target
+                1,1,Home,Sign In,,Login
+                1,2,Login,Submit,"failure, ""again""",Login
+                1,3,Login,Submit,success,Home
+                1,4,Home,Help,,Support
+                unreachable,1,Orphan,Go,,Home
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_gherkin() {
+        assert_eq!(
+            render(Plan::Gherkin),
+            indoc! {r#"
+                Feature: Navigation from "Home"
+                  Every connection reachable from "Home" is followed at least once.
+
+                  Scenario: Path 1 to "Support"
+                    Given I am on "Home"
+                    When I trigger "Sign In"
+                    Then I am on "Login"
+                    When I trigger "Submit" with outcome "failure, \"again\""
+                    Then I am on "Login"
+                    When I trigger "Submit" with outcome "success"
+                    Then I am on "Home"
+                    When I trigger "Help"
+                    Then I am on "Support"
+
+                  # Unreachable from "Home":
+                  # - Orphan: Go -> Home
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_unknown_entry() {
+        let breadboard = bnb_parser::parse(INPUT).unwrap();
+
+        assert!(matches!(
+            report(&breadboard, Some("Away".to_owned()), Plan::Markdown),
+            Err(Error::Graph(_))
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+//! `bnb query`: List places and components by their attributes.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_ast::{Attributes, Place};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// List the places and components of a breadboard, filtered by their `@key value` attributes.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The file to query, `-` reads from standard input.
+    #[arg(default_value = input::STDIO)]
+    file: PathBuf,
+
+    /// Only list places with the attribute, given as `key=value`, or as `key` to match any value.
+    ///
+    /// Can be repeated, in which case all filters have to match.
+    #[arg(long = "where", value_name = "FILTER")]
+    filters: Vec<String>,
+
+    /// The format of the file, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    matches: Vec<Match>,
+}
+
+#[derive(Serialize)]
+struct Match {
+    kind: &'static str,
+    name: String,
+    attributes: Attributes,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        file,
+        filters,
+        from,
+    } = args;
+    let breadboard = input::load(&file, from.as_deref())?;
+
+    let places = breadboard.places.iter().map(|place| ("place", place));
+    let components = breadboard
+        .components
+        .iter()
+        .map(|component| ("component", &**component));
+
+    let matches = places
+        .chain(components)
+        .filter(|(_, place)| filters.iter().all(|filter| matches(place, filter)))
+        .map(|(kind, place)| Match {
+            kind,
+            name: place.name.clone(),
+            attributes: place.attributes.clone(),
+        })
+        .collect();
+
+    output::report(format, &Report { matches });
+
+    Ok(Status::Success)
+}
+
+/// Returns `true` if the place has the attribute described by the `key=value` or `key` filter.
+fn matches(place: &Place, filter: &str) -> bool {
+    match filter.split_once('=') {
+        Some((key, value)) => place.attributes.get(key).is_some_and(|v| v == value),
+        None => place.attributes.contains_key(filter),
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for Match {
+            kind,
+            name,
+            attributes,
+        } in &self.matches
+        {
+            write!(f, "{kind} {name}")?;
+            for (key, value) in attributes {
+                write!(f, " @{key}={value}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
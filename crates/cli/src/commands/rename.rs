@@ -0,0 +1,150 @@
+//! `bnb rename`: Rename a place, component or affordance.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_convert::Options;
+use bnb_refactor::Symbol;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Rename a place, component or affordance, and update every reference to it.
+///
+/// DSL files are edited in-place, keeping their formatting and comments. Files in other formats
+/// are rewritten in the same format.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The kind of symbol to rename.
+    kind: Kind,
+
+    /// The current name.
+    name: String,
+
+    /// The new name.
+    new_name: String,
+
+    /// The file to edit, `-` reads from standard input and writes to standard output.
+    #[arg(default_value = input::STDIO)]
+    file: PathBuf,
+
+    /// The place or component defining the affordance to rename.
+    #[arg(
+        long = "in",
+        value_name = "PLACE",
+        required_if_eq("kind", "affordance")
+    )]
+    place: Option<String>,
+
+    /// The format of the file, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+
+    /// Do not write the file, only check that the rename is possible.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Kind {
+    Place,
+    Component,
+    Affordance,
+}
+
+#[derive(Serialize)]
+struct Report {
+    path: PathBuf,
+    symbol: String,
+    new_name: String,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args {
+        file,
+        kind,
+        name,
+        new_name,
+        place,
+        from,
+        dry_run,
+    } = args;
+
+    let symbol = match kind {
+        Kind::Place => Symbol::Place(name),
+        Kind::Component => Symbol::Component(name),
+        Kind::Affordance => Symbol::Affordance {
+            place: place.unwrap_or_default(),
+            affordance: name,
+        },
+    };
+
+    let error = |source| Error::Rename {
+        path: file.clone(),
+        source,
+    };
+
+    let registry = input::registry();
+    let file_format = input::format(&registry, from.as_deref(), &file)?;
+    let contents = input::read(&file)?;
+
+    // The DSL is edited in-place, other formats are rewritten from the renamed breadboard.
+    let renamed = if file_format.name() == "bnb" {
+        let edits = bnb_refactor::rename_source(&contents, &symbol, &new_name).map_err(error)?;
+        bnb_refactor::apply(&contents, &edits).into_bytes()
+    } else {
+        let mut breadboard = input::import(&file, &contents, file_format)?;
+        bnb_refactor::rename(&mut breadboard, &symbol, &new_name).map_err(error)?;
+
+        let error = |source| Error::Convert {
+            path: file.clone(),
+            source,
+        };
+        let exporter = file_format.exporter().ok_or_else(|| {
+            error(bnb_convert::Error::Unsupported {
+                format: file_format.name().to_owned(),
+                operation: "export",
+            })
+        })?;
+
+        let mut contents = vec![];
+        exporter
+            .export(&mut contents, &breadboard, &Options::default())
+            .map_err(error)?;
+        contents
+    };
+
+    if !dry_run {
+        input::write(&file, &renamed)?;
+    }
+
+    // Standard input is written to standard output, so the report cannot be printed there as well.
+    if !input::is_stdio(&file) || dry_run {
+        let report = Report {
+            path: input::display(&file),
+            symbol: symbol.to_string(),
+            new_name,
+        };
+
+        output::report(format, &report);
+    }
+
+    Ok(Status::Success)
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: renamed {} to `{}`",
+            self.path.display(),
+            self.symbol,
+            self.new_name
+        )
+    }
+}
@@ -0,0 +1,22 @@
+//! `bnb schema`: Print the JSON Schema of the breadboard JSON format.
+
+use crate::{error::Error, input, output::OutputFormat, Status};
+
+/// Print the JSON Schema of the breadboard JSON format.
+///
+/// The schema is pretty-printed as text, and printed on a single line with `--format json`.
+#[derive(clap::Args)]
+pub(crate) struct Args {}
+
+pub(crate) fn run(_: &Args, format: OutputFormat) -> Result<Status, Error> {
+    let schema = bnb_converter_json::schema();
+    let schema = match format {
+        OutputFormat::Text => serde_json::to_string_pretty(&schema),
+        OutputFormat::Json => serde_json::to_string(&schema),
+    }
+    .expect("schema is always serializable");
+
+    input::write(input::STDIO.as_ref(), format!("{schema}\n").as_bytes())?;
+
+    Ok(Status::Success)
+}
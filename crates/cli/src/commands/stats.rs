@@ -0,0 +1,95 @@
+//! `bnb stats`: Print statistics about a breadboard.
+
+use std::{fmt, path::PathBuf};
+
+use bnb_ast::{Breadboard, Item};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    input,
+    output::{self, OutputFormat},
+    Status,
+};
+
+/// Print statistics about a breadboard.
+#[derive(clap::Args)]
+pub(crate) struct Args {
+    /// The file to inspect, `-` reads from standard input.
+    #[arg(default_value = input::STDIO)]
+    file: PathBuf,
+
+    /// The format of the file, detected from the file extension by default.
+    #[arg(long)]
+    from: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct Report {
+    places: usize,
+    components: usize,
+    affordances: usize,
+    references: usize,
+    connections: usize,
+    positions: usize,
+    sketches: usize,
+    areas: usize,
+    violations: usize,
+}
+
+pub(crate) fn run(args: Args, format: OutputFormat) -> Result<Status, Error> {
+    let Args { file, from } = args;
+    let breadboard = input::load(&file, from.as_deref())?;
+
+    output::report(format, &Report::new(&breadboard));
+
+    Ok(Status::Success)
+}
+
+impl Report {
+    fn new(breadboard: &Breadboard) -> Self {
+        let mut report = Self {
+            places: breadboard.places.len(),
+            components: breadboard.components.len(),
+            violations: breadboard.validate().len(),
+            ..Self::default()
+        };
+
+        let places = breadboard.places.iter();
+        let components = breadboard.components.iter().map(|c| &**c);
+        for place in places.chain(components) {
+            for item in &place.items {
+                match item {
+                    Item::Affordance(affordance) => {
+                        report.affordances += 1;
+                        report.connections += affordance.connections.len();
+                    }
+                    Item::Reference(_) => report.references += 1,
+                }
+            }
+
+            report.positions += usize::from(place.position.is_some());
+
+            if let Some(sketch) = &place.sketch {
+                report.sketches += 1;
+                report.areas += sketch.areas.len();
+            }
+        }
+
+        report
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "places:      {}", self.places)?;
+        writeln!(f, "components:  {}", self.components)?;
+        writeln!(f, "affordances: {}", self.affordances)?;
+        writeln!(f, "references:  {}", self.references)?;
+        writeln!(f, "connections: {}", self.connections)?;
+        writeln!(f, "positions:   {}", self.positions)?;
+        writeln!(f, "sketches:    {}", self.sketches)?;
+        writeln!(f, "areas:       {}", self.areas)?;
+        writeln!(f, "violations:  {}", self.violations)
+    }
+}
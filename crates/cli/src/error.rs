@@ -0,0 +1,112 @@
+use std::{path::PathBuf, process::ExitCode};
+
+/// Errors that prevent a command from completing.
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to write {}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to convert {}: {source}", path.display())]
+    Convert {
+        path: PathBuf,
+        source: bnb_convert::Error,
+    },
+
+    #[error("failed to load {}: {source}", path.display())]
+    Load {
+        path: PathBuf,
+        source: bnb_parser::LoadError,
+    },
+
+    #[error("failed to rename in {}: {source}", path.display())]
+    Rename {
+        path: PathBuf,
+        source: bnb_refactor::Error,
+    },
+
+    #[error(transparent)]
+    History(bnb_history::Error),
+
+    #[error(transparent)]
+    Graph(bnb_graph::Error),
+
+    #[error("invalid trace {}: {source}", path.display())]
+    Trace {
+        path: PathBuf,
+        source: bnb_coverage::Error,
+    },
+
+    #[error("unable to detect format of {}, use `--from` or `--to`", path.display())]
+    UnknownFormat { path: PathBuf },
+
+    #[error("failed to launch butter: {0}")]
+    Launch(std::io::Error),
+}
+
+impl Error {
+    /// The exit code reported for this error.
+    ///
+    /// Invalid input exits with `1`, any other error with `2`.
+    pub(crate) fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Convert {
+                source: bnb_convert::Error::Invalid(_),
+                ..
+            }
+            | Self::Rename {
+                source: bnb_refactor::Error::Parse(_),
+                ..
+            }
+            | Self::Trace { .. } => ExitCode::from(1),
+            Self::Load { source, .. } if !matches!(source, bnb_parser::LoadError::Read { .. }) => {
+                ExitCode::from(1)
+            }
+            Self::Read { .. }
+            | Self::Load { .. }
+            | Self::Write { .. }
+            | Self::Convert { .. }
+            | Self::Rename { .. }
+            | Self::History(_)
+            | Self::Graph(_)
+            | Self::UnknownFormat { .. }
+            | Self::Launch(_) => ExitCode::from(2),
+        }
+    }
+
+    /// A short, stable identifier for the kind of error, used in JSON output.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Read { .. }
+            | Self::Load {
+                source: bnb_parser::LoadError::Read { .. },
+                ..
+            } => "read",
+            Self::Write { .. } => "write",
+            Self::Convert {
+                source: bnb_convert::Error::Invalid(_),
+                ..
+            }
+            | Self::Rename {
+                source: bnb_refactor::Error::Parse(_),
+                ..
+            }
+            | Self::Load { .. }
+            | Self::Trace { .. } => "invalid",
+            Self::Convert { .. } => "convert",
+            Self::Rename { .. } => "rename",
+            Self::History(_) => "history",
+            Self::Graph(_) => "graph",
+            Self::UnknownFormat { .. } => "unknown_format",
+            Self::Launch(_) => "launch",
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Reading and writing breadboards from files or standard input/output.
+
+use std::{
+    io::{Read as _, Write as _},
+    path::{Path, PathBuf},
+};
+
+use bnb_ast::Breadboard;
+use bnb_convert::{Format, Options, Registry};
+
+use crate::error::Error;
+
+/// The path that denotes standard input or output.
+pub(crate) const STDIO: &str = "-";
+
+/// The format assumed for standard input, if none is given.
+const DEFAULT_FORMAT: &str = "bnb";
+
+/// Returns the registry of all formats known to the CLI.
+pub(crate) fn registry() -> Registry {
+    Registry::default()
+        .with(bnb_converter_json::Json)
+        .with(bnb_converter_toml::Toml)
+        .with(bnb_converter_typescript::TypeScript)
+        .with(bnb_converter_yaml::Yaml)
+}
+
+/// Returns `true` if the path refers to standard input or output.
+pub(crate) fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Finds the format by name, or detects it from the path.
+pub(crate) fn format<'a>(
+    registry: &'a Registry,
+    name: Option<&str>,
+    path: &Path,
+) -> Result<&'a dyn Format, Error> {
+    match name {
+        Some(name) => registry.get(name).ok_or_else(|| Error::Convert {
+            path: path.to_owned(),
+            source: bnb_convert::Error::UnknownFormat(name.to_owned()),
+        }),
+        None if is_stdio(path) => Ok(registry
+            .get(DEFAULT_FORMAT)
+            .expect("default format is always registered")),
+        None => registry.for_path(path).ok_or_else(|| Error::UnknownFormat {
+            path: path.to_owned(),
+        }),
+    }
+}
+
+/// Reads the contents of the given path, or standard input if the path is `-`.
+pub(crate) fn read(path: &Path) -> Result<String, Error> {
+    let error = |source| Error::Read {
+        path: path.to_owned(),
+        source,
+    };
+
+    if is_stdio(path) {
+        let mut contents = String::new();
+        std::io::stdin()
+            .read_to_string(&mut contents)
+            .map_err(error)?;
+
+        return Ok(contents);
+    }
+
+    std::fs::read_to_string(path).map_err(error)
+}
+
+/// Writes the contents to the given path, or standard output if the path is `-`.
+pub(crate) fn write(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let error = |source| Error::Write {
+        path: path.to_owned(),
+        source,
+    };
+
+    if is_stdio(path) {
+        return std::io::stdout().write_all(contents).map_err(error);
+    }
+
+    std::fs::write(path, contents).map_err(error)
+}
+
+/// Reads and imports a breadboard from the given path.
+///
+/// If no format is given, it is detected from the file extension, with standard input defaulting
+/// to the DSL. DSL files are loaded along with the files they `import`, which is not supported for
+/// standard input.
+pub(crate) fn load(path: &Path, format: Option<&str>) -> Result<Breadboard, Error> {
+    let registry = registry();
+    let format = self::format(&registry, format, path)?;
+
+    if format.name() == "bnb" && !is_stdio(path) {
+        return bnb_parser::load(path, &bnb_parser::OsFileSystem).map_err(|source| Error::Load {
+            path: path.to_owned(),
+            source,
+        });
+    }
+
+    let contents = read(path)?;
+    import(path, &contents, format)
+}
+
+/// Imports a breadboard from the contents of a file in the given format.
+pub(crate) fn import(
+    path: &Path,
+    contents: &str,
+    format: &dyn Format,
+) -> Result<Breadboard, Error> {
+    let error = |source| Error::Convert {
+        path: path.to_owned(),
+        source,
+    };
+
+    let importer = format.importer().ok_or_else(|| {
+        error(bnb_convert::Error::Unsupported {
+            format: format.name().to_owned(),
+            operation: "import",
+        })
+    })?;
+
+    importer
+        .import(&mut contents.as_bytes(), &Options::default())
+        .map_err(error)
+}
+
+/// The display name of a path, used in command output.
+pub(crate) fn display(path: &Path) -> PathBuf {
+    if is_stdio(path) {
+        PathBuf::from("<stdin>")
+    } else {
+        path.to_owned()
+    }
+}
@@ -0,0 +1,119 @@
+//! # Bread'n'Butter CLI
+//!
+//! **A buttery smooth command-line experience.**
+//!
+//! The `bnb` binary exposes the Bread'n'Butter libraries to scripts and CI pipelines, without the
+//! need to write any Rust.
+//!
+//! ## Commands
+//!
+//! - `bnb check`: Parse and validate breadboard files.
+//! - `bnb convert`: Convert a breadboard between the DSL and other formats.
+//! - `bnb fmt`: Format breadboard files.
+//! - `bnb stats`: Print statistics about a breadboard.
+//! - `bnb diff`: Compare two breadboards at the design level.
+//! - `bnb history`: Print the structural changes of a breadboard file, commit by commit.
+//! - `bnb query`: List places and components by their attributes.
+//! - `bnb paths`: Generate test paths that follow every connection of a breadboard.
+//! - `bnb coverage`: Report which parts of a breadboard a recorded navigation trace covers.
+//! - `bnb rename`: Rename a place, component or affordance, updating every reference.
+//! - `bnb open`: Open a breadboard in the Butter GUI.
+//! - `bnb formats`: List the supported file formats.
+//! - `bnb schema`: Print the JSON Schema of the breadboard JSON format.
+//!
+//! Pass `--format json` to any command to get machine-readable output. Commands that write a
+//! breadboard to standard output, such as `convert` without `--output`, print only the breadboard.
+//!
+//! ## Exit Codes
+//!
+//! - `0`: The command succeeded.
+//! - `1`: The input is invalid (e.g. it failed to parse or validate), `fmt --check` found
+//!   unformatted files, or `coverage` found transitions missing from the breadboard.
+//! - `2`: The command could not run, for example due to invalid arguments or I/O errors.
+
+mod commands;
+mod error;
+mod input;
+mod output;
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use crate::output::OutputFormat;
+
+/// Command-line tool for Bread'n'Butter breadboards.
+#[derive(Parser)]
+#[command(name = "bnb", version, about)]
+struct Cli {
+    /// The format in which command results are printed.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Check(commands::check::Args),
+    Convert(commands::convert::Args),
+    Fmt(commands::fmt::Args),
+    Stats(commands::stats::Args),
+    Diff(commands::diff::Args),
+    History(commands::history::Args),
+    Query(commands::query::Args),
+    Paths(commands::paths::Args),
+    Coverage(commands::coverage::Args),
+    Rename(commands::rename::Args),
+    Open(commands::open::Args),
+    Formats(commands::formats::Args),
+    Schema(commands::schema::Args),
+}
+
+/// The outcome of a command that ran to completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// The command succeeded.
+    Success,
+
+    /// The command ran, but its input did not pass the checks.
+    Failure,
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Success => ExitCode::SUCCESS,
+            Status::Failure => ExitCode::from(1),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let Cli { format, command } = Cli::parse();
+
+    let result = match command {
+        Command::Check(args) => commands::check::run(args, format),
+        Command::Convert(args) => commands::convert::run(args, format),
+        Command::Fmt(args) => commands::fmt::run(args, format),
+        Command::Stats(args) => commands::stats::run(args, format),
+        Command::Diff(args) => commands::diff::run(args, format),
+        Command::History(args) => commands::history::run(args, format),
+        Command::Query(args) => commands::query::run(args, format),
+        Command::Paths(args) => commands::paths::run(args, format),
+        Command::Coverage(args) => commands::coverage::run(args, format),
+        Command::Rename(args) => commands::rename::run(args, format),
+        Command::Open(args) => commands::open::run(args, format),
+        Command::Formats(args) => Ok(commands::formats::run(&args, format)),
+        Command::Schema(args) => commands::schema::run(&args, format),
+    };
+
+    match result {
+        Ok(status) => status.into(),
+        Err(error) => {
+            output::error(format, &error);
+            error.exit_code()
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Printing of command results, either as human-readable text or as JSON.
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// The format in which command results are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+
+    /// A single JSON document, for consumption by other tools.
+    Json,
+}
+
+/// Prints the report of a command to standard output.
+pub(crate) fn report<T: Serialize + Display>(format: OutputFormat, report: &T) {
+    match format {
+        OutputFormat::Text => print!("{report}"),
+        OutputFormat::Json => println!("{}", json(report)),
+    }
+}
+
+/// Prints an error that prevented a command from completing.
+///
+/// Text errors are printed to standard error, JSON errors to standard output, so that tools
+/// consuming the output always receive a JSON document.
+pub(crate) fn error(format: OutputFormat, error: &Error) {
+    #[derive(Serialize)]
+    struct ErrorReport {
+        error: &'static str,
+        message: String,
+    }
+
+    match format {
+        OutputFormat::Text => eprintln!("error: {error}"),
+        OutputFormat::Json => println!(
+            "{}",
+            json(&ErrorReport {
+                error: error.kind(),
+                message: error.to_string(),
+            })
+        ),
+    }
+}
+
+fn json(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("reports are always serializable")
+}
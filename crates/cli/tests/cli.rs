@@ -0,0 +1,282 @@
+//! Runs the `bnb` binary against the breadboards in `tests/fixtures`.
+
+use std::{fs, path::PathBuf};
+
+use assert_cmd::Command;
+use indoc::indoc;
+use predicates::str::{contains, starts_with};
+use serde_json::{json, Value};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn bnb() -> Command {
+    let mut command = Command::cargo_bin("bnb").unwrap();
+    command.current_dir(fixtures());
+    command
+}
+
+/// Runs `bnb` with `--format json`, and returns the JSON document printed to standard output.
+fn json(args: &[&str], code: i32) -> Value {
+    let output = bnb()
+        .arg("--format")
+        .arg("json")
+        .args(args)
+        .assert()
+        .code(code)
+        .get_output()
+        .stdout
+        .clone();
+
+    serde_json::from_slice(&output).unwrap()
+}
+
+#[test]
+fn test_check() {
+    bnb()
+        .args(["check", "valid.bnb"])
+        .assert()
+        .code(0)
+        .stdout("valid.bnb: ok\n");
+
+    bnb()
+        .args(["check", "valid.bnb", "invalid.bnb"])
+        .assert()
+        .code(1)
+        .stdout(starts_with(
+            "valid.bnb: ok\ninvalid.bnb: validation error: ",
+        ));
+
+    bnb()
+        .args(["check", "unparsable.bnb"])
+        .assert()
+        .code(1)
+        .stdout(starts_with("unparsable.bnb: invalid error: "));
+
+    bnb()
+        .args(["check", "missing.bnb"])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(starts_with(
+            "error: failed to load missing.bnb: failed to read missing.bnb: ",
+        ));
+}
+
+#[test]
+fn test_check_json() {
+    let report = json(&["check", "valid.bnb", "invalid.bnb"], 1);
+
+    assert_eq!(report["valid"], false);
+    assert_eq!(
+        report["files"][0],
+        json!({ "path": "valid.bnb", "valid": true, "errors": [] })
+    );
+    assert_eq!(report["files"][1]["path"], "invalid.bnb");
+    assert_eq!(report["files"][1]["valid"], false);
+    assert_eq!(report["files"][1]["errors"][0]["kind"], "validation");
+
+    let report = json(&["check", "missing.bnb"], 2);
+
+    assert_eq!(report["error"], "read");
+    assert!(report["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("failed to load missing.bnb: failed to read missing.bnb: "));
+}
+
+#[test]
+fn test_convert() {
+    let output = bnb()
+        .args(["convert", "valid.bnb", "--to", "json"])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let breadboard: Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(breadboard["version"], 1);
+    assert_eq!(breadboard["breadboard"]["places"][0]["name"], "Home");
+    assert_eq!(breadboard["breadboard"]["components"][0]["name"], "Header");
+
+    // Converting back to the DSL gives the original, formatted, breadboard.
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("valid.json");
+    fs::write(&path, output).unwrap();
+
+    bnb()
+        .arg("convert")
+        .arg(&path)
+        .args(["--to", "bnb"])
+        .assert()
+        .code(0)
+        .stdout(fs::read_to_string(fixtures().join("valid.bnb")).unwrap());
+
+    bnb()
+        .args(["convert", "valid.bnb", "--to", "unknown"])
+        .assert()
+        .code(2)
+        .stdout("");
+
+    bnb()
+        .args(["convert", "unparsable.bnb", "--to", "json"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_convert_json() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("valid.yaml");
+
+    let report = json(&["convert", "valid.bnb", "-o", path.to_str().unwrap()], 0);
+
+    assert_eq!(
+        report,
+        json!({ "input": "valid.bnb", "output": path, "format": "yaml" })
+    );
+    assert!(fs::read_to_string(&path).unwrap().contains("name: Home"));
+}
+
+#[test]
+fn test_fmt() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("unformatted.bnb");
+    fs::copy(fixtures().join("unformatted.bnb"), &path).unwrap();
+
+    bnb()
+        .args(["fmt", "--check"])
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(contains("unformatted.bnb: not formatted"));
+
+    bnb()
+        .arg("fmt")
+        .arg(&path)
+        .assert()
+        .code(0)
+        .stdout(contains("unformatted.bnb: formatted"));
+
+    // Components are moved after the places.
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        indoc! {"
+            place Home
+              include Header
+              Sign Up -> Registration
+
+            place Registration
+              Back -> Home
+
+            component Header
+              Logo -> Home
+        "}
+    );
+
+    bnb()
+        .args(["fmt", "--check"])
+        .arg(&path)
+        .assert()
+        .code(0)
+        .stdout("");
+
+    bnb()
+        .arg("fmt")
+        .write_stdin("place Home\n  Go   ->   Home\n")
+        .assert()
+        .code(0)
+        .stdout("place Home\n  Go -> Home\n");
+
+    // No report follows the formatted standard input.
+    bnb()
+        .args(["--format", "json", "fmt"])
+        .write_stdin("place Home\n  Go   ->   Home\n")
+        .assert()
+        .code(0)
+        .stdout("place Home\n  Go -> Home\n");
+}
+
+#[test]
+fn test_fmt_json() {
+    let report = json(&["fmt", "--check", "valid.bnb", "unformatted.bnb"], 1);
+
+    assert_eq!(
+        report,
+        json!({
+            "files": [
+                { "path": "valid.bnb", "status": "unchanged" },
+                { "path": "unformatted.bnb", "status": "unformatted" },
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_stats() {
+    bnb()
+        .args(["stats", "valid.bnb"])
+        .assert()
+        .code(0)
+        .stdout(starts_with(
+            "places:      2\ncomponents:  1\naffordances: 3\n",
+        ));
+
+    // The global `--format` option can also follow the command.
+    let output = bnb()
+        .args(["stats", "valid.bnb", "--format", "json"])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(
+        serde_json::from_slice::<Value>(&output).unwrap(),
+        json!({
+            "places": 2,
+            "components": 1,
+            "affordances": 3,
+            "references": 2,
+            "connections": 3,
+            "positions": 0,
+            "sketches": 0,
+            "areas": 0,
+            "violations": 0,
+        })
+    );
+}
+
+#[test]
+fn test_coverage_overlay() {
+    let trace = r#"{"place": "Home", "affordance": "Sign Up", "target": "Registration"}"#;
+
+    bnb()
+        .args(["coverage", "valid.bnb", "-"])
+        .write_stdin(trace)
+        .assert()
+        .code(0)
+        .stdout(starts_with("places       2/2 (100.0%)\n"));
+
+    // Only the overlay is written to standard output.
+    let output = bnb()
+        .args(["--format", "json", "coverage", "valid.bnb", "-"])
+        .args(["--overlay", "-"])
+        .write_stdin(trace)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+
+    let overlay = bnb_coverage::read_overlay(output.as_slice()).unwrap();
+    assert_eq!(overlay.summary().connections.covered, 1);
+}
+
+#[test]
+fn test_invalid_arguments() {
+    bnb().arg("check").assert().code(2);
+    bnb().args(["stats", "--format", "xml"]).assert().code(2);
+}
@@ -0,0 +1,494 @@
+//! # Bread'n'Butter Codegen
+//!
+//! **A buttery smooth code generation experience.**
+//!
+//! The `bnb_codegen` crate generates typed Rust code from a [`Breadboard`], to check the routing
+//! of an application against its design at compile time. Renaming a place or an affordance in the
+//! breadboard breaks the build wherever the code still uses the old name.
+//!
+//! The generated code contains:
+//!
+//! - A `Place` enum, with a variant for each place.
+//! - An `affordance` module, with an enum for each place, with a variant for each of its
+//!   affordances (including those of the components it includes).
+//! - An `Affordance` enum, with a variant for each place, wrapping its affordance enum.
+//! - A `navigate(place, affordance)` function, which returns the places the affordance connects
+//!   to, in the order of its connections.
+//!
+//! The names of places and affordances are turned into `PascalCase` identifiers, e.g. `Sign In`
+//! becomes `SignIn`.
+//!
+//! ## Usage
+//!
+//! Code generation works within a [build script], similar to `dtoken::build`:
+//!
+//! ```no_run
+//! // build.rs
+//! bnb_codegen::build("app.bnb").unwrap();
+//! ```
+//!
+//! The generated code is written to `breadboard.rs` in the `OUT_DIR` of the build, and can be
+//! included in any module:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/breadboard.rs"));
+//!
+//! assert_eq!(navigate(Place::Home, affordance::Home::SignIn), &[Place::Login]);
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+//! [build script]: https://doc.rust-lang.org/cargo/reference/build-scripts.html
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+use bnb_ast::{Breadboard, Place};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+/// The name of the generated file, in the `OUT_DIR` of the build.
+const OUTPUT: &str = "breadboard.rs";
+
+/// Generates the code for the breadboard file at the given path, and its imports.
+///
+/// This is meant to be called from a build script. The build script is rerun whenever any of the
+/// breadboard files changes.
+///
+/// # Errors
+///
+/// Returns an error if the breadboard cannot be loaded, its code cannot be generated (see
+/// [`generate`]), or the code cannot be written.
+pub fn build(path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    let breadboard = bnb_parser::load(path, &bnb_parser::OsFileSystem)?;
+
+    let files: BTreeSet<_> = breadboard
+        .places
+        .iter()
+        .chain(breadboard.components.iter().map(|c| &**c))
+        .filter_map(|place| place.origin.as_deref())
+        .chain([path])
+        .collect();
+
+    for file in files {
+        println!("cargo:rerun-if-changed={}", file.display());
+    }
+
+    let code = generate(&breadboard)?;
+    let output = Path::new(&std::env::var("OUT_DIR")?).join(OUTPUT);
+
+    std::fs::write(&output, code).map_err(|source| Error::Write {
+        path: output.clone(),
+        source,
+    })?;
+
+    rustfmt(&output)
+}
+
+/// Generates the code for the breadboard.
+///
+/// See the [crate documentation](crate) for the generated code.
+///
+/// # Errors
+///
+/// Returns an error if a place is defined more than once, if the name of a place or affordance
+/// cannot be turned into an identifier, if the names of two places (or two affordances of the
+/// same place) turn into the same identifier, or if an affordance connects to an unknown place.
+pub fn generate(breadboard: &Breadboard) -> Result<String, Error> {
+    let mut places = Identifiers::default();
+    for place in &breadboard.places {
+        if places.try_get(&place.name).is_some() {
+            return Err(Error::DuplicatePlace {
+                place: place.name.clone(),
+            });
+        }
+
+        places.insert(&place.name)?;
+    }
+
+    let mut place_idents = vec![];
+    let mut place_names = vec![];
+    let mut modules = vec![];
+    let mut transitions = vec![];
+
+    for place in &breadboard.places {
+        let (module, place_transitions) = generate_place(breadboard, place, &places)?;
+
+        modules.push(module);
+        transitions.extend(place_transitions);
+        place_idents.push(places.get(&place.name));
+        place_names.push(place.name.as_str());
+    }
+
+    let code = quote! {
+        /// The places of the breadboard.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Place {
+            #(#place_idents,)*
+        }
+
+        impl Place {
+            /// All places, in the order of the breadboard.
+            pub const ALL: &'static [Self] = &[#(Self::#place_idents,)*];
+
+            /// Returns the name of the place in the breadboard.
+            #[must_use]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#place_idents => #place_names,)*
+                }
+            }
+        }
+
+        /// The affordances of each place of the breadboard.
+        pub mod affordance {
+            #(#modules)*
+        }
+
+        /// An affordance of any place of the breadboard.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Affordance {
+            #(#place_idents(affordance::#place_idents),)*
+        }
+
+        impl Affordance {
+            /// Returns the place of the affordance.
+            #[must_use]
+            pub const fn place(self) -> Place {
+                match self {
+                    #(Self::#place_idents(_) => Place::#place_idents,)*
+                }
+            }
+
+            /// Returns the name of the affordance in the breadboard.
+            #[must_use]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#place_idents(affordance) => affordance.name(),)*
+                }
+            }
+        }
+
+        #(
+            impl From<affordance::#place_idents> for Affordance {
+                fn from(affordance: affordance::#place_idents) -> Self {
+                    Self::#place_idents(affordance)
+                }
+            }
+        )*
+
+        /// Returns the places the affordance of the place connects to.
+        ///
+        /// Returns no places if the affordance does not belong to the place.
+        #[must_use]
+        pub fn navigate(place: Place, affordance: impl Into<Affordance>) -> &'static [Place] {
+            match (place, affordance.into()) {
+                #(#transitions)*
+                _ => &[],
+            }
+        }
+    };
+
+    Ok(code.to_string())
+}
+
+/// Generates the affordance enum of the place, and the transitions of its affordances.
+fn generate_place(
+    breadboard: &Breadboard,
+    place: &Place,
+    places: &Identifiers,
+) -> Result<(TokenStream, Vec<TokenStream>), Error> {
+    let ident = places.get(&place.name);
+
+    let mut affordances = Identifiers::default();
+    let mut targets: Vec<(Ident, Vec<Ident>)> = vec![];
+
+    for affordance in breadboard.unique_affordances(place) {
+        let affordance_ident = affordances.insert(&affordance.name)?;
+        let mut affordance_targets = vec![];
+
+        for connection in &affordance.connections {
+            let Some(target) = places.try_get(&connection.target_place) else {
+                return Err(Error::UnknownTarget {
+                    place: place.name.clone(),
+                    affordance: affordance.name.clone(),
+                    target: connection.target_place.clone(),
+                });
+            };
+
+            if !affordance_targets.contains(&target) {
+                affordance_targets.push(target);
+            }
+        }
+
+        targets.push((affordance_ident, affordance_targets));
+    }
+
+    let (affordance_idents, affordance_names): (Vec<_>, Vec<_>) =
+        affordances.idents.into_iter().unzip();
+    let doc = format!(" The affordances of the `{}` place.", place.name);
+
+    let module = quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum #ident {
+            #(#affordance_idents,)*
+        }
+
+        impl #ident {
+            /// All affordances of the place, in the order of the breadboard.
+            pub const ALL: &'static [Self] = &[#(Self::#affordance_idents,)*];
+
+            /// Returns the name of the affordance in the breadboard.
+            #[must_use]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(Self::#affordance_idents => #affordance_names,)*
+                }
+            }
+        }
+    };
+
+    let transitions = targets
+        .into_iter()
+        .filter(|(_, targets)| !targets.is_empty())
+        .map(|(affordance, targets)| {
+            quote! {
+                (Place::#ident, Affordance::#ident(affordance::#ident::#affordance)) => {
+                    &[#(Place::#targets,)*]
+                }
+            }
+        })
+        .collect();
+
+    Ok((module, transitions))
+}
+
+/// The identifiers of a set of names, in the order they were inserted.
+#[derive(Default)]
+struct Identifiers {
+    idents: Vec<(Ident, String)>,
+    names: HashMap<String, Ident>,
+}
+
+impl Identifiers {
+    /// Inserts the name, and returns its identifier.
+    ///
+    /// Inserting the same name again returns the same identifier.
+    fn insert(&mut self, name: &str) -> Result<Ident, Error> {
+        if let Some(ident) = self.names.get(name) {
+            return Ok(ident.clone());
+        }
+
+        let ident = ident(name)?;
+        if let Some((_, other)) = self.idents.iter().find(|(i, _)| *i == ident) {
+            return Err(Error::DuplicateIdent {
+                first: other.clone(),
+                second: name.to_owned(),
+                ident: ident.to_string(),
+            });
+        }
+
+        self.idents.push((ident.clone(), name.to_owned()));
+        self.names.insert(name.to_owned(), ident.clone());
+
+        Ok(ident)
+    }
+
+    fn get(&self, name: &str) -> Ident {
+        self.try_get(name).expect("name is inserted")
+    }
+
+    fn try_get(&self, name: &str) -> Option<Ident> {
+        self.names.get(name).cloned()
+    }
+}
+
+/// Returns the `PascalCase` identifier of the name.
+fn ident(name: &str) -> Result<Ident, Error> {
+    let ident: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) || ident == "Self" {
+        return Err(Error::InvalidName {
+            name: name.to_owned(),
+        });
+    }
+
+    Ok(Ident::new(&ident, Span::call_site()))
+}
+
+#[cfg(feature = "rustfmt")]
+fn rustfmt(path: &Path) -> Result<(), Error> {
+    use std::process::Command;
+
+    let output = Command::new(std::env::var("RUSTFMT").unwrap_or_else(|_| "rustfmt".to_owned()))
+        .args(["--emit", "files"])
+        .arg(path)
+        .output()
+        .map_err(|error| Error::Fmt(error.to_string()))?;
+
+    if !output.status.success() {
+        return Err(Error::Fmt(format!(
+            "{}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "rustfmt"))]
+#[expect(clippy::unnecessary_wraps)]
+fn rustfmt(_path: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+/// An error that occurred while generating code with [`build`] or [`generate`].
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Load(#[from] bnb_parser::LoadError),
+
+    #[error("duplicate place `{place}`")]
+    DuplicatePlace { place: String },
+
+    #[error("`{name}` cannot be turned into a Rust identifier")]
+    InvalidName { name: String },
+
+    #[error("`{first}` and `{second}` both turn into the Rust identifier `{ident}`")]
+    DuplicateIdent {
+        first: String,
+        second: String,
+        ident: String,
+    },
+
+    #[error("affordance `{affordance}` in `{place}` connects to unknown place `{target}`")]
+    UnknownTarget {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+
+    #[error("failed to read environment variable: {0}")]
+    Var(#[from] std::env::VarError),
+
+    #[error("failed to write {}: {source}", path.display())]
+    Write { path: PathBuf, source: io::Error },
+
+    #[error("failed to run rustfmt: {0}")]
+    Fmt(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn generate_pretty(input: &str) -> String {
+        let breadboard = bnb_parser::parse(input).unwrap();
+        let code = generate(&breadboard).unwrap();
+
+        prettyplease::unparse(&syn::parse_file(&code).unwrap())
+    }
+
+    #[test]
+    fn test_generate() {
+        let code = generate_pretty(indoc! {"
+            place Home
+              include Header
+              Sign In -> Login
+              > Remember Me
+              Help -> Support
+                   -> Support.Contact
+
+            place Login
+              Submit -> Home
+              Cancel <- Home
+
+            place Support
+
+            component Header
+              Logo -> Home
+        "});
+
+        insta::assert_snapshot!(code);
+    }
+
+    #[test]
+    fn test_ident() {
+        let cases = [
+            ("Home", Ok("Home")),
+            ("Sign In", Ok("SignIn")),
+            ("sign-in flow", Ok("SignInFlow")),
+            ("iOS Settings", Ok("IOSSettings")),
+            ("\"Quoted\" (name)", Ok("QuotedName")),
+            ("404 Page", Err(())),
+            ("->", Err(())),
+            ("self", Err(())),
+        ];
+
+        for (name, expected) in cases {
+            let ident = ident(name).map(|ident| ident.to_string()).map_err(|_| ());
+
+            assert_eq!(ident.as_deref().map_err(|_| ()), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let cases = [
+            (
+                "place Home\n  Go -> Home\n\nplace Home\n",
+                "duplicate place `Home`",
+            ),
+            (
+                "place Sign In\n\nplace sign-in\n",
+                "`Sign In` and `sign-in` both turn into the Rust identifier `SignIn`",
+            ),
+            (
+                "place Home\n  Go -> Away\n",
+                "affordance `Go` in `Home` connects to unknown place `Away`",
+            ),
+            (
+                "place 404\n",
+                "`404` cannot be turned into a Rust identifier",
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let breadboard = bnb_parser::parse(input).unwrap();
+
+            assert_eq!(generate(&breadboard).unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[cfg(feature = "rustfmt")]
+    #[test]
+    fn test_rustfmt_error() {
+        let path = std::env::temp_dir().join(format!("bnb-codegen-{}.rs", std::process::id()));
+        std::fs::write(&path, "fn {").unwrap();
+
+        let error = rustfmt(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(
+            matches!(&error, Error::Fmt(message) if message.contains("error")),
+            "{error}"
+        );
+    }
+}
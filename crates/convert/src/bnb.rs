@@ -0,0 +1,50 @@
+use std::io::{Read, Write};
+
+use bnb_ast::Breadboard;
+
+use crate::{Error, Exporter, Format, Importer, Options};
+
+/// The Bread'n'Butter DSL format, backed by `bnb-parser`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bnb;
+
+impl Format for Bnb {
+    fn name(&self) -> &'static str {
+        "bnb"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bnb"]
+    }
+
+    fn importer(&self) -> Option<&dyn Importer> {
+        Some(self)
+    }
+
+    fn exporter(&self) -> Option<&dyn Exporter> {
+        Some(self)
+    }
+}
+
+impl Importer for Bnb {
+    fn import(&self, reader: &mut dyn Read, _: &Options) -> Result<Breadboard, Error> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+
+        bnb_parser::parse(&input).map_err(Error::invalid)
+    }
+}
+
+impl Exporter for Bnb {
+    /// Exports the breadboard as DSL source.
+    ///
+    /// The DSL is always printed in its canonical (pretty) layout.
+    fn export(
+        &self,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        _: &Options,
+    ) -> Result<(), Error> {
+        Ok(writer.write_all(bnb_parser::print(breadboard).as_bytes())?)
+    }
+}
@@ -0,0 +1,344 @@
+//! # Bread'n'Butter Conversion
+//!
+//! **A buttery smooth conversion experience.**
+//!
+//! The `bnb_convert` crate defines the shared interface implemented by all breadboard converters,
+//! and a [`Registry`] to look up converters at runtime.
+//!
+//! ## Overview
+//!
+//! - [`Importer`]: Reads a [`Breadboard`] from some format.
+//! - [`Exporter`]: Writes a [`Breadboard`] to some format.
+//! - [`Format`]: Describes a format by name and file extensions, and provides its importer and/or
+//!   exporter.
+//! - [`Registry`]: A collection of formats, keyed by name and file extension.
+//!
+//! The [`Bnb`] format (the DSL itself) is built-in, other formats live in their own crates (such
+//! as `bnb-converter-json`), and are registered by the application.
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_convert::{Options, Registry};
+//!
+//! let registry = Registry::default();
+//! let format = registry.for_extension("bnb").unwrap();
+//!
+//! let mut input = "place Home".as_bytes();
+//! let breadboard = format
+//!     .importer()
+//!     .unwrap()
+//!     .import(&mut input, &Options::default())
+//!     .unwrap();
+//!
+//! assert_eq!(breadboard.places[0].name, "Home");
+//! ```
+
+mod bnb;
+
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+use bnb_ast::Breadboard;
+
+pub use bnb::Bnb;
+
+/// Options shared by all converters.
+///
+/// Converters ignore options that do not apply to their format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Produce human-readable output, e.g. indented JSON.
+    pub pretty: bool,
+
+    /// Produce output with a stable layout, e.g. sorted JSON keys, to keep diffs small.
+    pub canonical: bool,
+}
+
+/// Reads a [`Breadboard`] from a specific format.
+pub trait Importer {
+    /// Imports a breadboard from the given reader.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading fails, or [`Error::Invalid`] if the input is not a valid
+    /// breadboard in this format.
+    fn import(&self, reader: &mut dyn Read, options: &Options) -> Result<Breadboard, Error>;
+}
+
+/// Writes a [`Breadboard`] to a specific format.
+pub trait Exporter {
+    /// Exports the breadboard to the given writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if writing fails, or [`Error::Unrepresentable`] if the breadboard
+    /// cannot be represented in this format.
+    fn export(
+        &self,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        options: &Options,
+    ) -> Result<(), Error>;
+}
+
+/// A breadboard file format, providing an [`Importer`], an [`Exporter`], or both.
+pub trait Format: Send + Sync + 'static {
+    /// The unique name of the format, e.g. `json`.
+    fn name(&self) -> &'static str;
+
+    /// The file extensions used by the format, without the leading dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// The importer of the format, if the format can be read.
+    fn importer(&self) -> Option<&dyn Importer> {
+        None
+    }
+
+    /// The exporter of the format, if the format can be written.
+    fn exporter(&self) -> Option<&dyn Exporter> {
+        None
+    }
+}
+
+/// A collection of [`Format`]s, keyed by name and file extension.
+///
+/// The default registry contains the built-in [`Bnb`] format.
+pub struct Registry {
+    formats: Vec<Box<dyn Format>>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { formats: vec![] }
+    }
+
+    /// Adds a format to the registry, replacing any existing format with the same name.
+    pub fn register(&mut self, format: impl Format) -> &mut Self {
+        self.formats.retain(|f| f.name() != format.name());
+        self.formats.push(Box::new(format));
+        self
+    }
+
+    /// Adds a format to the registry, see [`Registry::register`].
+    #[must_use]
+    pub fn with(mut self, format: impl Format) -> Self {
+        self.register(format);
+        self
+    }
+
+    /// Returns the format with the given name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&dyn Format> {
+        self.formats().find(|format| format.name() == name)
+    }
+
+    /// Returns the first format that uses the given file extension.
+    ///
+    /// Extensions are compared case-insensitively.
+    #[must_use]
+    pub fn for_extension(&self, extension: &str) -> Option<&dyn Format> {
+        self.formats().find(|format| {
+            format
+                .extensions()
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    /// Returns the format matching the extension of the given path.
+    #[must_use]
+    pub fn for_path(&self, path: &Path) -> Option<&dyn Format> {
+        self.for_extension(path.extension()?.to_str()?)
+    }
+
+    /// Returns all registered formats, in registration order.
+    pub fn formats(&self) -> impl Iterator<Item = &dyn Format> {
+        self.formats.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns all formats that can be imported.
+    pub fn importers(&self) -> impl Iterator<Item = &dyn Format> {
+        self.formats().filter(|format| format.importer().is_some())
+    }
+
+    /// Returns all formats that can be exported.
+    pub fn exporters(&self) -> impl Iterator<Item = &dyn Format> {
+        self.formats().filter(|format| format.exporter().is_some())
+    }
+
+    /// Imports a breadboard using the format with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownFormat`] or [`Error::Unsupported`] if no importer is registered
+    /// under this name, or any error returned by the importer itself.
+    pub fn import(
+        &self,
+        name: &str,
+        reader: &mut dyn Read,
+        options: &Options,
+    ) -> Result<Breadboard, Error> {
+        let format = self
+            .get(name)
+            .ok_or_else(|| Error::UnknownFormat(name.to_owned()))?;
+        let importer = format.importer().ok_or_else(|| Error::Unsupported {
+            format: name.to_owned(),
+            operation: "import",
+        })?;
+
+        importer.import(reader, options)
+    }
+
+    /// Exports a breadboard using the format with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownFormat`] or [`Error::Unsupported`] if no exporter is registered
+    /// under this name, or any error returned by the exporter itself.
+    pub fn export(
+        &self,
+        name: &str,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        options: &Options,
+    ) -> Result<(), Error> {
+        let format = self
+            .get(name)
+            .ok_or_else(|| Error::UnknownFormat(name.to_owned()))?;
+        let exporter = format.exporter().ok_or_else(|| Error::Unsupported {
+            format: name.to_owned(),
+            operation: "export",
+        })?;
+
+        exporter.export(writer, breadboard, options)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new().with(Bnb)
+    }
+}
+
+/// The error type shared by all converters.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The input is not a valid breadboard in the given format.
+    #[error("invalid input: {0}")]
+    Invalid(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The breadboard cannot be represented in the given format.
+    #[error("unrepresentable breadboard: {0}")]
+    Unrepresentable(Box<dyn std::error::Error + Send + Sync>),
+
+    /// No format with the given name is registered.
+    #[error("unknown format: {0}")]
+    UnknownFormat(String),
+
+    /// The format does not support the requested operation.
+    #[error("format `{format}` does not support {operation}")]
+    Unsupported {
+        format: String,
+        operation: &'static str,
+    },
+}
+
+impl Error {
+    /// Creates an [`Error::Invalid`] from any error.
+    pub fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Invalid(Box::new(error))
+    }
+
+    /// Creates an [`Error::Unrepresentable`] from any error.
+    pub fn unrepresentable(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Unrepresentable(Box::new(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ExportOnly;
+
+    impl Format for ExportOnly {
+        fn name(&self) -> &'static str {
+            "export-only"
+        }
+
+        fn extensions(&self) -> &'static [&'static str] {
+            &["eo", "bnb"]
+        }
+
+        fn exporter(&self) -> Option<&dyn Exporter> {
+            Some(self)
+        }
+    }
+
+    impl Exporter for ExportOnly {
+        fn export(&self, writer: &mut dyn Write, _: &Breadboard, _: &Options) -> Result<(), Error> {
+            Ok(writer.write_all(b"exported")?)
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = Registry::default().with(ExportOnly);
+
+        assert_eq!(registry.get("bnb").map(Format::name), Some("bnb"));
+        assert_eq!(
+            registry.for_extension("EO").map(Format::name),
+            Some("export-only")
+        );
+        assert_eq!(
+            registry
+                .for_path(Path::new("foo/bar.bnb"))
+                .map(Format::name),
+            Some("bnb")
+        );
+        assert!(registry.get("json").is_none());
+
+        let names = |formats: &mut dyn Iterator<Item = &dyn Format>| {
+            formats.map(Format::name).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&mut registry.importers()), vec!["bnb"]);
+        assert_eq!(names(&mut registry.exporters()), vec!["bnb", "export-only"]);
+
+        let mut buffer = vec![];
+        registry
+            .export(
+                "export-only",
+                &mut buffer,
+                &Breadboard::default(),
+                &Options::default(),
+            )
+            .unwrap();
+        assert_eq!(buffer, b"exported");
+
+        assert!(matches!(
+            registry.import("export-only", &mut "".as_bytes(), &Options::default()),
+            Err(Error::Unsupported { .. })
+        ));
+        assert!(matches!(
+            registry.import("yaml", &mut "".as_bytes(), &Options::default()),
+            Err(Error::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_replaces() {
+        let mut registry = Registry::default();
+        registry.register(Bnb).register(Bnb);
+
+        assert_eq!(registry.formats().count(), 1);
+    }
+}
@@ -12,6 +12,22 @@
 //! - [`serialize`]: Converts a `Breadboard` instance into a JSON representation.
 //! - [`deserialize`]: Constructs a `Breadboard` instance from JSON data.
 //!
+//! The [`Json`] type exposes the same functionality through the [`bnb_convert`] traits, to be
+//! registered in a [`bnb_convert::Registry`].
+//!
+//! ## Versioning
+//!
+//! Breadboards are wrapped in an envelope that records the version of the JSON structure:
+//!
+//! ```json
+//! { "version": 1, "breadboard": { "places": [], "components": [] } }
+//! ```
+//!
+//! When the structure of the AST changes in an incompatible way, [`VERSION`] is bumped, and a
+//! migration is added that upgrades documents of the previous version. [`deserialize`] applies all
+//! migrations up to the current version, so previously written files keep loading. Documents
+//! without an envelope are treated as version `0`.
+//!
 //! ## Usage
 //!
 //! The crate is particularly useful in scenarios where breadboard configurations need to be saved
@@ -21,36 +37,237 @@
 //!
 //! See the function-level documentation for examples.
 
+#[cfg(feature = "schema")]
+mod schema;
+
 use std::io::{Read, Write};
 
 use bnb_ast::Breadboard;
-use serde_json::Result;
+use bnb_convert::{Exporter, Format, Importer};
+use serde::Serialize;
+use serde_json::Value;
+
+pub use bnb_convert::Options;
+#[cfg(feature = "schema")]
+pub use schema::{deserialize_validated, schema, SchemaViolation, SCHEMA_ID};
+
+/// The current version of the JSON structure, written by [`serialize`].
+pub const VERSION: u64 = 1;
 
-/// Serializes a `Breadboard` structure into JSON format.
+/// Migrations from each previous version to the next, indexed by the version they upgrade from.
+///
+/// The length of this list must always equal [`VERSION`].
+const MIGRATIONS: &[fn(Value) -> Value] = &[
+    // v0 (no envelope) has the same breadboard structure as v1.
+    |breadboard| breadboard,
+];
+
+const _: () = assert!(MIGRATIONS.len() as u64 == VERSION);
+
+/// Errors that can occur while serializing or deserializing breadboards.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The JSON is malformed, does not match the `Breadboard` structure, or an I/O error occurred.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// The document was written by a newer version of this crate.
+    #[error(
+        "unsupported version {version}, the latest supported version is {}",
+        VERSION
+    )]
+    UnsupportedVersion { version: u64 },
+
+    /// The document has a `version` field, but is not a valid envelope.
+    #[error("invalid envelope: {0}")]
+    InvalidEnvelope(&'static str),
+
+    /// The document does not match the JSON Schema of the breadboard format.
+    #[cfg(feature = "schema")]
+    #[error("does not match the breadboard schema: {}", schema_violations(.0))]
+    Schema(Vec<SchemaViolation>),
+}
+
+#[cfg(feature = "schema")]
+fn schema_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl Error {
+    /// Returns `true` if the error was caused by a failing reader or writer.
+    #[must_use]
+    pub fn is_io(&self) -> bool {
+        matches!(self, Self::Json(error) if error.is_io())
+    }
+}
+
+impl From<Error> for bnb_convert::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Json(error) if error.is_io() => Self::Io(error.into()),
+            error => Self::invalid(error),
+        }
+    }
+}
+
+/// The JSON [`Format`], for use with a [`bnb_convert::Registry`].
+///
+/// # Examples
+///
+/// ```
+/// use bnb_convert::Registry;
+/// use bnb_converter_json::Json;
+///
+/// let registry = Registry::default().with(Json);
+///
+/// assert!(registry.for_extension("json").is_some());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Format for Json {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn importer(&self) -> Option<&dyn Importer> {
+        Some(self)
+    }
+
+    fn exporter(&self) -> Option<&dyn Exporter> {
+        Some(self)
+    }
+}
+
+impl Importer for Json {
+    /// Imports a breadboard from JSON.
+    ///
+    /// With the `schema` feature enabled, the input is validated against the [`schema`] first.
+    fn import(&self, reader: &mut dyn Read, _: &Options) -> Result<Breadboard, bnb_convert::Error> {
+        #[cfg(feature = "schema")]
+        let breadboard = deserialize_validated(reader)?;
+
+        #[cfg(not(feature = "schema"))]
+        let breadboard = deserialize(reader)?;
+
+        Ok(breadboard)
+    }
+}
+
+impl Exporter for Json {
+    fn export(
+        &self,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        options: &Options,
+    ) -> Result<(), bnb_convert::Error> {
+        serialize(writer, breadboard, options).map_err(|error| match error {
+            Error::Json(error) if error.is_io() => bnb_convert::Error::Io(error.into()),
+            error => bnb_convert::Error::unrepresentable(error),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u64,
+    breadboard: &'a Breadboard,
+}
+
+/// Serializes a `Breadboard` structure into JSON format, wrapped in a versioned envelope.
+///
+/// With [`Options::pretty`], the JSON is indented and ends with a newline. With
+/// [`Options::canonical`], object keys are sorted, so that equal breadboards always produce the
+/// same output, which keeps diffs of checked-in files small. This includes the keys of attributes,
+/// which are otherwise written in the order they are defined.
 ///
 /// # Examples
 ///
 /// ```
 /// use bnb_ast::Breadboard;
-/// use bnb_converter_json::serialize;
+/// use bnb_converter_json::{serialize, Options};
 ///
-/// let breadboard = Breadboard { places: vec![], components: vec![] };
+/// let breadboard = Breadboard::default();
 /// let mut buffer = vec![];
-/// serialize(&mut buffer, &breadboard);
+/// serialize(&mut buffer, &breadboard, &Options::default()).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(buffer).unwrap(),
+///     r#"{"version":1,"breadboard":{"places":[],"components":[]}}"#,
+/// );
 /// ```
 ///
-#[allow(clippy::missing_panics_doc)]
-pub fn serialize(writer: impl Write, breadboard: &Breadboard) {
-    serde_json::to_writer(writer, breadboard).expect("Breadboard serialization cannot fail");
+/// # Errors
+///
+/// Returns an error if writing to the writer fails.
+pub fn serialize(
+    mut writer: impl Write,
+    breadboard: &Breadboard,
+    options: &Options,
+) -> Result<(), Error> {
+    if options.canonical {
+        let mut value = to_value(breadboard)?;
+        sort_keys(&mut value);
+
+        write(&mut writer, &value, options.pretty)
+    } else {
+        let envelope = Envelope {
+            version: VERSION,
+            breadboard,
+        };
+
+        write(&mut writer, &envelope, options.pretty)
+    }
+}
+
+/// Sorts the keys of all objects in the value.
+///
+/// `serde_json::Map` keeps its keys in insertion order, as this crate enables the `preserve_order`
+/// feature to keep attributes in the order they are defined.
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_keys);
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+fn write(mut writer: impl Write, value: &impl Serialize, pretty: bool) -> Result<(), Error> {
+    if !pretty {
+        return Ok(serde_json::to_writer(writer, value)?);
+    }
+
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+
+    Ok(())
 }
 
 /// Deserializes JSON data into a `Breadboard` structure.
 ///
+/// Documents written by older versions, including documents without an envelope, are migrated to
+/// the current structure before deserializing.
+///
 /// # Examples
 ///
 /// ```
 /// use bnb_converter_json::deserialize;
 ///
+/// let json = r#"{"version": 1, "breadboard": {"places": [], "components": []}}"#;
+/// let breadboard = deserialize(json.as_bytes()).unwrap();
+///
+/// // Unversioned documents are still supported.
 /// let json = r#"{"places": [], "components": []}"#;
 /// let breadboard = deserialize(json.as_bytes()).unwrap();
 /// ```
@@ -62,8 +279,80 @@ pub fn serialize(writer: impl Write, breadboard: &Breadboard) {
 /// data, for example required struct fields are missing from the JSON map or some number is too
 /// big to fit in the expected primitive type.
 ///
-pub fn deserialize(reader: impl Read) -> Result<Breadboard> {
-    serde_json::from_reader(reader)
+/// Documents with a version newer than [`VERSION`] are rejected with
+/// [`Error::UnsupportedVersion`].
+///
+pub fn deserialize(reader: impl Read) -> Result<Breadboard, Error> {
+    from_value(serde_json::from_reader(reader)?)
+}
+
+/// Converts a `Breadboard` into a JSON value, wrapped in a versioned envelope.
+///
+/// Together with [`from_value`], this allows converters for other serde-based data formats to
+/// share the versioned structure of the JSON format.
+///
+/// # Errors
+///
+/// This conversion cannot fail for breadboards, the `Result` is kept for future compatibility.
+pub fn to_value(breadboard: &Breadboard) -> Result<Value, Error> {
+    Ok(serde_json::to_value(Envelope {
+        version: VERSION,
+        breadboard,
+    })?)
+}
+
+/// Converts a JSON value into a `Breadboard`, see [`deserialize`].
+///
+/// # Errors
+///
+/// See [`deserialize`].
+pub fn from_value(value: Value) -> Result<Breadboard, Error> {
+    let (version, breadboard) = unwrap_envelope(value)?;
+    let breadboard = migrate(version, breadboard)?;
+
+    Ok(serde_json::from_value(breadboard)?)
+}
+
+/// Migrates a breadboard of the given version to the current [`VERSION`].
+pub(crate) fn migrate(version: u64, mut breadboard: Value) -> Result<Value, Error> {
+    let Some(migrations) = usize::try_from(version)
+        .ok()
+        .and_then(|version| MIGRATIONS.get(version..))
+    else {
+        return Err(Error::UnsupportedVersion { version });
+    };
+
+    for migrate in migrations {
+        breadboard = migrate(breadboard);
+    }
+
+    Ok(breadboard)
+}
+
+/// Splits a document into its version and breadboard.
+pub(crate) fn unwrap_envelope(value: Value) -> Result<(u64, Value), Error> {
+    let Value::Object(mut map) = value else {
+        return Ok((0, value));
+    };
+
+    let Some(version) = map.remove("version") else {
+        return Ok((0, Value::Object(map)));
+    };
+
+    let version = version.as_u64().ok_or(Error::InvalidEnvelope(
+        "`version` must be a non-negative integer",
+    ))?;
+    let breadboard = map
+        .remove("breadboard")
+        .ok_or(Error::InvalidEnvelope("missing `breadboard` field"))?;
+
+    if !map.is_empty() {
+        return Err(Error::InvalidEnvelope(
+            "unexpected field next to `version` and `breadboard`",
+        ));
+    }
+
+    Ok((version, breadboard))
 }
 
 #[cfg(test)]
@@ -71,8 +360,8 @@ mod tests {
     use std::path::PathBuf;
 
     use bnb_ast::{
-        Affordance, Area, Component, Connection, Coordinate, Item, Pivot, Place, Position,
-        Reference, Sketch,
+        Affordance, Area, Attributes, Component, Connection, ConnectionKind, Coordinate, Item,
+        Pivot, Place, Position, Reference, Sketch,
     };
 
     use super::*;
@@ -81,20 +370,32 @@ mod tests {
     #[allow(clippy::too_many_lines)]
     fn test_serialize_deserialize() {
         let breadboard = Breadboard {
+            imports: vec![],
             places: vec![
                 Place {
                     name: "Registration".to_owned(),
+                    attributes: Attributes::from([
+                        ("ticket".to_owned(), "ABC-12".to_owned()),
+                        ("owner".to_owned(), "payments".to_owned()),
+                    ]),
                     items: vec![
                         Item::Affordance(Affordance {
                             name: "Username".to_owned(),
                             connections: vec![],
                             description: vec![],
+                            kind: Some("input".to_owned()),
+                            attributes: Attributes::from([(
+                                "placeholder".to_owned(),
+                                "Your name".to_owned(),
+                            )]),
                             level: 0,
                         }),
                         Item::Affordance(Affordance {
                             name: "Password".to_owned(),
                             connections: vec![],
                             description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
                             level: 0,
                         }),
                         Item::Affordance(Affordance {
@@ -102,14 +403,20 @@ mod tests {
                             connections: vec![
                                 Connection {
                                     target_place: "Home".to_owned(),
+                                    target_affordance: None,
+                                    kind: ConnectionKind::Navigate,
                                     description: Some("success".to_owned()),
                                 },
                                 Connection {
                                     target_place: "Support".to_owned(),
+                                    target_affordance: None,
+                                    kind: ConnectionKind::Navigate,
                                     description: Some("failure".to_owned()),
                                 },
                             ],
                             description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
                             level: 0,
                         }),
                         Item::Reference(Reference {
@@ -135,23 +442,31 @@ mod tests {
                         }],
                     }),
                     description: vec![],
+                    origin: None,
                 },
                 Place {
                     name: "Support".to_owned(),
+                    attributes: Attributes::new(),
                     items: vec![
                         Item::Affordance(Affordance {
                             name: "Error Message".to_owned(),
                             connections: vec![],
                             description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
                             level: 0,
                         }),
                         Item::Affordance(Affordance {
                             name: "Try Again".to_owned(),
                             connections: vec![Connection {
                                 target_place: "Registration".to_owned(),
+                                target_affordance: None,
+                                kind: ConnectionKind::Navigate,
                                 description: None,
                             }],
                             description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
                             level: 0,
                         }),
                         Item::Reference(Reference {
@@ -170,14 +485,18 @@ mod tests {
                         }],
                     }),
                     description: vec![],
+                    origin: None,
                 },
                 Place {
                     name: "Home".to_owned(),
+                    attributes: Attributes::new(),
                     items: vec![
                         Item::Affordance(Affordance {
                             name: "Dashboard".to_owned(),
                             connections: vec![],
                             description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
                             level: 0,
                         }),
                         Item::Reference(Reference {
@@ -191,38 +510,125 @@ mod tests {
                         areas: vec![],
                     }),
                     description: vec![],
+                    origin: None,
                 },
             ],
             components: vec![Component::new(Place {
                 name: "Header".to_owned(),
+                attributes: Attributes::new(),
                 items: vec![
                     Item::Affordance(Affordance {
                         name: "Logo".to_owned(),
                         connections: vec![],
                         description: vec![],
+                        kind: None,
+                        attributes: Attributes::new(),
                         level: 0,
                     }),
                     Item::Affordance(Affordance {
                         name: "Contact".to_owned(),
                         connections: vec![],
                         description: vec![],
+                        kind: None,
+                        attributes: Attributes::new(),
                         level: 0,
                     }),
                 ],
                 position: None,
                 sketch: None,
                 description: vec![],
+                origin: None,
             })],
+            flows: vec![],
         };
 
         // Serialize the Breadboard
         let mut serialized_data = Vec::new();
-        serialize(&mut serialized_data, &breadboard);
+        serialize(&mut serialized_data, &breadboard, &Options::default())
+            .expect("Serialization failed");
 
         // Deserialize the Breadboard
         let deserialized_breadboard: Breadboard =
             deserialize(&mut serialized_data.as_slice()).expect("Deserialization failed");
 
+        // Attributes keep the order they are defined in.
+        let attributes = &deserialized_breadboard.places[0].attributes;
+        assert!(attributes.keys().eq(["ticket", "owner"]));
+
         insta::assert_json_snapshot!(deserialized_breadboard);
     }
+
+    #[test]
+    fn test_envelope() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![Place {
+                name: "Home".to_owned(),
+                attributes: Attributes::new(),
+                items: vec![],
+                position: None,
+                sketch: None,
+                description: vec![],
+                origin: None,
+            }],
+            components: vec![],
+            flows: vec![],
+        };
+
+        let mut compact = vec![];
+        serialize(&mut compact, &breadboard, &Options::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(compact).unwrap(),
+            r#"{"version":1,"breadboard":{"places":[{"name":"Home","description":[],"items":[],"position":null,"sketch":null}],"components":[]}}"#
+        );
+
+        let mut canonical = vec![];
+        let options = Options {
+            canonical: true,
+            ..Options::default()
+        };
+        serialize(&mut canonical, &breadboard, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(canonical).unwrap(),
+            r#"{"breadboard":{"components":[],"places":[{"description":[],"items":[],"name":"Home","position":null,"sketch":null}]},"version":1}"#
+        );
+
+        let mut pretty = vec![];
+        let options = Options {
+            pretty: true,
+            canonical: true,
+        };
+        serialize(&mut pretty, &breadboard, &options).unwrap();
+        assert!(pretty.ends_with(b"\n  \"version\": 1\n}\n"));
+        assert_eq!(deserialize(pretty.as_slice()).unwrap(), breadboard);
+    }
+
+    #[test]
+    fn test_migrate_unversioned() {
+        let json = r#"{"places": [{"name": "Home", "description": [], "items": [], "position": null, "sketch": null}], "components": []}"#;
+        let breadboard = deserialize(json.as_bytes()).unwrap();
+
+        assert_eq!(breadboard.places[0].name, "Home");
+    }
+
+    #[test]
+    fn test_invalid_envelope() {
+        let json = r#"{"version": 2, "breadboard": {"places": [], "components": []}}"#;
+        assert!(matches!(
+            deserialize(json.as_bytes()),
+            Err(Error::UnsupportedVersion { version: 2 })
+        ));
+
+        let json = r#"{"version": 1}"#;
+        assert!(matches!(
+            deserialize(json.as_bytes()),
+            Err(Error::InvalidEnvelope(_))
+        ));
+
+        let json = r#"{"version": 1, "breadboard": {"places": [], "components": []}, "extra": 1}"#;
+        assert!(matches!(
+            deserialize(json.as_bytes()),
+            Err(Error::InvalidEnvelope(_))
+        ));
+    }
 }
@@ -0,0 +1,209 @@
+//! JSON Schema of the versioned breadboard format.
+//!
+//! The schema is generated from the `bnb-ast` types, and published alongside this crate as
+//! `breadboard.schema.json`. A test ensures the published file stays in sync with the AST.
+
+use std::{fmt, io::Read, sync::OnceLock};
+
+use bnb_ast::Breadboard;
+use jsonschema::Validator;
+use schemars::{generate::SchemaSettings, SchemaGenerator};
+use serde_json::{json, Value};
+
+use crate::{migrate, sort_keys, unwrap_envelope, Error, VERSION};
+
+/// The `$id` of the published schema.
+pub const SCHEMA_ID: &str =
+    "https://raw.githubusercontent.com/dcdpr/BDSL/main/crates/converters/json/breadboard.schema.json";
+
+/// A location in a JSON document that does not match the [`schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// The JSON pointer to the offending value, e.g. `/breadboard/places/0/name`.
+    ///
+    /// For unversioned documents, the pointer is relative to the document root, e.g.
+    /// `/places/0/name`.
+    pub pointer: String,
+
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// Returns the JSON Schema of the versioned breadboard format written by
+/// [`serialize`](crate::serialize).
+///
+/// # Examples
+///
+/// ```
+/// let schema = bnb_converter_json::schema();
+///
+/// assert_eq!(schema["properties"]["version"]["const"], bnb_converter_json::VERSION);
+/// ```
+#[must_use]
+pub fn schema() -> Value {
+    let mut generator = SchemaGenerator::new(SchemaSettings::draft2020_12());
+    let breadboard = generator.subschema_for::<Breadboard>();
+    let definitions = generator.take_definitions(true);
+
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "Breadboard",
+        "description": "A Bread'n'Butter breadboard, wrapped in a versioned envelope.",
+        "type": "object",
+        "properties": {
+            "version": {
+                "description": "The version of the JSON structure.",
+                "const": VERSION,
+            },
+            "breadboard": breadboard,
+        },
+        "required": ["version", "breadboard"],
+        "additionalProperties": false,
+        "$defs": definitions,
+    });
+
+    // Keep the published schema stable, regardless of the order the generator adds keys in.
+    sort_keys(&mut schema);
+
+    schema
+}
+
+/// Deserializes JSON data into a `Breadboard` structure, validating it against the [`schema`]
+/// first.
+///
+/// This behaves like [`deserialize`](crate::deserialize), but reports every mismatch with the
+/// expected structure, including the JSON pointer to the offending value, instead of only the
+/// first error found by serde.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_converter_json::{deserialize_validated, Error};
+///
+/// let json = r#"{"version": 1, "breadboard": {"places": [{"name": 1}], "components": []}}"#;
+/// let Err(Error::Schema(violations)) = deserialize_validated(json.as_bytes()) else {
+///     panic!("expected schema violations");
+/// };
+///
+/// assert!(violations.iter().any(|v| v.pointer == "/breadboard/places/0/name"));
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error::Schema`] if the document does not match the schema, or any error returned by
+/// [`deserialize`](crate::deserialize).
+pub fn deserialize_validated(reader: impl Read) -> Result<Breadboard, Error> {
+    let value: Value = serde_json::from_reader(reader)?;
+    let (version, breadboard) = unwrap_envelope(value)?;
+    let breadboard = migrate(version, breadboard)?;
+
+    // Unversioned documents are validated as if they were wrapped in an envelope, so the pointers
+    // need to be unwrapped again to match the input.
+    let prefix = if version == 0 { "/breadboard" } else { "" };
+    let document = json!({ "version": VERSION, "breadboard": breadboard });
+
+    let violations: Vec<_> = validator()
+        .iter_errors(&document)
+        .map(|error| {
+            let pointer = error.instance_path().to_string();
+
+            SchemaViolation {
+                pointer: pointer
+                    .strip_prefix(prefix)
+                    .map(str::to_owned)
+                    .unwrap_or(pointer),
+                message: error.to_string(),
+            }
+        })
+        .collect();
+
+    if !violations.is_empty() {
+        return Err(Error::Schema(violations));
+    }
+
+    let Value::Object(mut document) = document else {
+        unreachable!("document is always an object");
+    };
+
+    Ok(serde_json::from_value(
+        document.remove("breadboard").unwrap_or_default(),
+    )?)
+}
+
+fn validator() -> &'static Validator {
+    static VALIDATOR: OnceLock<Validator> = OnceLock::new();
+
+    VALIDATOR.get_or_init(|| {
+        jsonschema::validator_for(&schema()).expect("generated schema is always valid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Regenerate the published schema with `BNB_UPDATE_SCHEMA=1 cargo test`.
+    #[test]
+    fn test_schema_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("breadboard.schema.json");
+        let generated = serde_json::to_string_pretty(&schema()).unwrap() + "\n";
+
+        if std::env::var_os("BNB_UPDATE_SCHEMA").is_some() {
+            std::fs::write(&path, &generated).unwrap();
+        }
+
+        let published = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            published == generated,
+            "breadboard.schema.json is out of date, run `BNB_UPDATE_SCHEMA=1 cargo test`"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_validated() {
+        let json = r#"{"places": [{"name": "Home", "description": [], "items": [], "position": {"x": 1, "y": "up"}, "sketch": null}], "components": []}"#;
+        let Err(Error::Schema(violations)) = deserialize_validated(json.as_bytes()) else {
+            panic!("expected schema violations");
+        };
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].pointer, "/places/0/position");
+
+        let json =
+            r#"{"version": 1, "breadboard": {"places": [{"name": "Home"}], "components": {}}}"#;
+        let Err(Error::Schema(violations)) = deserialize_validated(json.as_bytes()) else {
+            panic!("expected schema violations");
+        };
+
+        let pointers: Vec<_> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            vec![
+                "/breadboard/components",
+                "/breadboard/places/0",
+                "/breadboard/places/0"
+            ]
+        );
+
+        let json = r#"{"version": 1, "breadboard": {"places": [], "components": []}}"#;
+        assert_eq!(
+            deserialize_validated(json.as_bytes()).unwrap(),
+            Breadboard::default()
+        );
+    }
+}
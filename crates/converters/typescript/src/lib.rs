@@ -0,0 +1,514 @@
+//! # Bread'n'Butter TypeScript Converter
+//!
+//! **A buttery smooth TypeScript conversion experience.**
+//!
+//! The `bnb_converter_typescript` crate exports `Breadboard` structures as TypeScript types, to
+//! share the places and transitions of a design with a web front-end.
+//!
+//! ## Overview
+//!
+//! - [`serialize`]: Converts a `Breadboard` instance into a TypeScript module.
+//! - [`TypeScript`]: The same functionality through the [`bnb_convert`] traits. TypeScript can
+//!   only be exported, not imported.
+//!
+//! ## Structure
+//!
+//! The generated module exports:
+//!
+//! - `Place`: A union of the names of all places.
+//! - `Affordances`: An interface with a union of the names of the affordances of each place
+//!   (including those of the components it includes), and `Affordance<P>` to look them up.
+//! - `ConnectionKind`: A union of the kinds of connections, named as in the JSON format.
+//! - `Transition`: The target place and the kind of a connection.
+//! - `transitions`: A const map of the transitions of each affordance, by place, with the
+//!   descriptions of the affordances and their connections as `JSDoc`.
+//! - `Component`: A union of the names of all components.
+//! - `includedBy`: A const map of the places and components that include each component.
+//!
+//! The output only depends on the breadboard, so it can be checked in and diffed. By default,
+//! everything is in the order of the breadboard. With [`Options::canonical`], places, components
+//! and affordances are sorted by name instead, so reordering the breadboard does not change the
+//! output.
+//!
+//! The `satisfies` operator used to type the const maps requires TypeScript 4.9 or newer.
+//!
+//! ## Examples
+//!
+//! See the function-level documentation for examples.
+
+use std::io::Write;
+
+use bnb_ast::{Affordance, Breadboard, ConnectionKind, Item, Place};
+use bnb_convert::{Exporter, Format};
+
+pub use bnb_convert::Options;
+
+/// The first line of every generated module.
+const HEADER: &str = "// Generated from a breadboard by bnb. Do not edit.\n";
+
+/// The kinds of connections, in the order of the `ConnectionKind` union.
+const KINDS: [ConnectionKind; 4] = [
+    ConnectionKind::Navigate,
+    ConnectionKind::Return,
+    ConnectionKind::Modal,
+    ConnectionKind::Data,
+];
+
+/// Errors that can occur while serializing breadboards.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Writing failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// A place is defined more than once, which would repeat its key in the generated types.
+    #[error("duplicate place `{place}`")]
+    DuplicatePlace { place: String },
+
+    /// A connection leads to a place that does not exist, and cannot be typed.
+    #[error("affordance `{affordance}` in `{place}` connects to unknown place `{target}`")]
+    UnknownPlace {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+}
+
+/// The TypeScript [`Format`], for use with a [`bnb_convert::Registry`].
+///
+/// # Examples
+///
+/// ```
+/// use bnb_convert::Registry;
+/// use bnb_converter_typescript::TypeScript;
+///
+/// let registry = Registry::default().with(TypeScript);
+///
+/// assert!(registry.for_extension("ts").unwrap().importer().is_none());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TypeScript;
+
+impl Format for TypeScript {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ts"]
+    }
+
+    fn exporter(&self) -> Option<&dyn Exporter> {
+        Some(self)
+    }
+}
+
+impl Exporter for TypeScript {
+    fn export(
+        &self,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        options: &Options,
+    ) -> Result<(), bnb_convert::Error> {
+        serialize(writer, breadboard, options).map_err(|error| match error {
+            Error::Io(error) => bnb_convert::Error::Io(error),
+            error @ (Error::DuplicatePlace { .. } | Error::UnknownPlace { .. }) => {
+                bnb_convert::Error::unrepresentable(error)
+            }
+        })
+    }
+}
+
+/// Serializes a `Breadboard` structure into a TypeScript module.
+///
+/// [`Options::pretty`] has no effect, the output is always formatted.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_converter_typescript::{serialize, Options};
+///
+/// let breadboard = bnb_parser::parse("place Home\n  Sign In -> Login\n\nplace Login\n").unwrap();
+/// let mut buffer = vec![];
+/// serialize(&mut buffer, &breadboard, &Options::default()).unwrap();
+///
+/// let output = String::from_utf8(buffer).unwrap();
+/// assert!(output.contains("export type Place =\n  | \"Home\"\n  | \"Login\";\n"));
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if writing to the writer fails, if a place is defined more than once, or if a
+/// connection leads to an unknown place.
+pub fn serialize(
+    mut writer: impl Write,
+    breadboard: &Breadboard,
+    options: &Options,
+) -> Result<(), Error> {
+    for (i, place) in breadboard.places.iter().enumerate() {
+        if breadboard.places[..i].iter().any(|p| p.name == place.name) {
+            return Err(Error::DuplicatePlace {
+                place: place.name.clone(),
+            });
+        }
+    }
+
+    let mut places: Vec<&Place> = breadboard.places.iter().collect();
+    let mut components: Vec<&Place> = breadboard.components.iter().map(|c| &**c).collect();
+
+    if options.canonical {
+        places.sort_by(|a, b| a.name.cmp(&b.name));
+        components.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    let affordances: Vec<Vec<Affordance>> = places
+        .iter()
+        .map(|place| {
+            let mut affordances = breadboard.unique_affordances(place);
+
+            if options.canonical {
+                affordances.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+
+            affordances
+        })
+        .collect();
+
+    let mut out = String::from(HEADER);
+
+    out.push_str("\n/** The places of the breadboard. */\n");
+    out.push_str(&format!(
+        "export type Place ={};\n",
+        union(places.iter().map(|p| p.name.as_str()), 1)
+    ));
+
+    out.push_str("\n/** The affordances of each place. */\nexport interface Affordances {\n");
+    for (place, affordances) in places.iter().zip(&affordances) {
+        out.push_str(&jsdoc(1, &place.description, &[]));
+        out.push_str(&format!(
+            "  {}:{};\n",
+            string(&place.name),
+            union(affordances.iter().map(|a| a.name.as_str()), 2)
+        ));
+    }
+    out.push_str("}\n");
+
+    out.push_str(
+        "\n/** An affordance of the given place, or of any place. */\n\
+         export type Affordance<P extends Place = Place> = Affordances[P];\n",
+    );
+
+    out.push_str("\n/** The kinds of connections between places. */\n");
+    out.push_str(&format!(
+        "export type ConnectionKind ={};\n",
+        union(KINDS.map(kind).iter().map(String::as_str), 1)
+    ));
+
+    out.push_str(
+        "\n/** A connection from an affordance to a place. */\n\
+         export interface Transition {\n  \
+         readonly to: Place;\n  \
+         readonly kind: ConnectionKind;\n\
+         }\n",
+    );
+
+    out.push_str("\n/** The transitions of each affordance, by place. */\n");
+    out.push_str("export const transitions = {\n");
+    for (place, affordances) in places.iter().zip(&affordances) {
+        out.push_str(&format!("  {}: {{", string(&place.name)));
+
+        let mut transitions = String::new();
+        for affordance in affordances {
+            transitions.push_str(&transition(breadboard, place, affordance)?);
+        }
+
+        if transitions.is_empty() {
+            out.push_str("},\n");
+        } else {
+            out.push_str(&format!("\n{transitions}  }},\n"));
+        }
+    }
+    out.push_str(
+        "} as const satisfies {\n  \
+         readonly [P in Place]: { readonly [A in Affordance<P>]?: readonly Transition[] };\n};\n",
+    );
+
+    out.push_str("\n/** The components of the breadboard. */\n");
+    out.push_str(&format!(
+        "export type Component ={};\n",
+        union(components.iter().map(|c| c.name.as_str()), 1)
+    ));
+
+    out.push_str("\n/** The places and components that include each component. */\n");
+    out.push_str("export const includedBy = {\n");
+    for component in &components {
+        let includers: Vec<_> = places
+            .iter()
+            .chain(&components)
+            .filter(|place| includes(place, &component.name))
+            .map(|place| string(&place.name))
+            .collect();
+
+        out.push_str(&jsdoc(1, &component.description, &[]));
+        out.push_str(&format!(
+            "  {}: [{}],\n",
+            string(&component.name),
+            includers.join(", ")
+        ));
+    }
+    out.push_str(
+        "} as const satisfies {\n  \
+         readonly [C in Component]: readonly (Place | Component)[];\n};\n",
+    );
+
+    writer.write_all(out.as_bytes())?;
+
+    Ok(())
+}
+
+/// Returns the entry of the affordance in the `transitions` map, or an empty string if it has no
+/// connections.
+fn transition(
+    breadboard: &Breadboard,
+    place: &Place,
+    affordance: &Affordance,
+) -> Result<String, Error> {
+    let mut transitions = vec![];
+    let mut notes = vec![];
+
+    for connection in &affordance.connections {
+        let target = &connection.target_place;
+
+        if breadboard.place(target).is_none() {
+            return Err(Error::UnknownPlace {
+                place: place.name.clone(),
+                affordance: affordance.name.clone(),
+                target: target.clone(),
+            });
+        }
+
+        if let Some(description) = &connection.description {
+            notes.push(format!("- `{target}`: {description}"));
+        }
+
+        let transition = format!(
+            "{{ to: {}, kind: {} }}",
+            string(target),
+            string(&kind(connection.kind))
+        );
+        if !transitions.contains(&transition) {
+            transitions.push(transition);
+        }
+    }
+
+    if transitions.is_empty() {
+        return Ok(String::new());
+    }
+
+    Ok(format!(
+        "{}    {}: [{}],\n",
+        jsdoc(2, &affordance.description, &notes),
+        string(&affordance.name),
+        transitions.join(", ")
+    ))
+}
+
+/// Returns `true` if the place directly includes the component.
+fn includes(place: &Place, component: &str) -> bool {
+    place.items.iter().any(|item| match item {
+        Item::Reference(reference) => reference.name == component,
+        Item::Affordance(_) => false,
+    })
+}
+
+/// Returns a union of string literal types, one per line, or `never` if there are none.
+///
+/// The union starts with the separator to the name of the type, and is indented by the given
+/// level.
+fn union<'a>(names: impl Iterator<Item = &'a str>, level: usize) -> String {
+    let indent = "  ".repeat(level);
+
+    let mut union = String::new();
+    for name in names {
+        union.push_str(&format!("\n{indent}| {}", string(name)));
+    }
+
+    if union.is_empty() {
+        " never".to_owned()
+    } else {
+        union
+    }
+}
+
+/// Returns a `JSDoc` comment, indented by the given level, with the paragraphs of the description
+/// followed by the notes. Returns an empty string if both are empty.
+fn jsdoc(level: usize, description: &[String], notes: &[String]) -> String {
+    let indent = "  ".repeat(level);
+
+    let mut lines: Vec<&str> = description.iter().map(String::as_str).collect();
+    if !lines.is_empty() && !notes.is_empty() {
+        lines.push("");
+    }
+    lines.extend(notes.iter().map(String::as_str));
+
+    // A `*/` in the text would end the comment early.
+    let lines: Vec<_> = lines
+        .iter()
+        .map(|line| line.replace("*/", "*\\/"))
+        .collect();
+
+    match lines.as_slice() {
+        [] => String::new(),
+        [line] => format!("{indent}/** {line} */\n"),
+        lines => {
+            let mut out = format!("{indent}/**\n");
+            for line in lines {
+                match line.as_str() {
+                    "" => out.push_str(&format!("{indent} *\n")),
+                    line => out.push_str(&format!("{indent} * {line}\n")),
+                }
+            }
+            out.push_str(&format!("{indent} */\n"));
+
+            out
+        }
+    }
+}
+
+/// Returns the name as a TypeScript string literal.
+fn string(name: &str) -> String {
+    serde_json::to_string(name).expect("strings always serialize")
+}
+
+/// Returns the name of the kind of a connection, as in the JSON format.
+fn kind(kind: ConnectionKind) -> String {
+    match serde_json::to_value(kind) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => unreachable!("connection kinds serialize as strings"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn export(input: &str, options: &Options) -> Result<String, Error> {
+        let breadboard = bnb_parser::parse(input).unwrap();
+
+        let mut buffer = vec![];
+        serialize(&mut buffer, &breadboard, options)?;
+
+        Ok(String::from_utf8(buffer).unwrap())
+    }
+
+    #[test]
+    fn test_serialize() {
+        let input = indoc! {r#"
+            /// The place to register.
+            place Registration
+              include Header
+              Username [input]
+              /// Signs the user up.
+              Sign Up -> (success) Home
+                      -> (failure) Support
+                      -> Support.Contact
+
+            place Home
+              include Header
+              Dashboard
+
+            place "Support \"Desk\""
+
+            place Support
+              Try Again -> Registration
+              Terms ~> "Support \"Desk\""
+              Submit => Home
+              Back <- Registration
+
+            /// The header of every page, with a */ in it.
+            component Header
+              Logo -> Home
+              include Footer
+
+            component Footer
+        "#};
+
+        let output = export(input, &Options::default()).unwrap();
+
+        insta::assert_snapshot!(output);
+    }
+
+    #[test]
+    fn test_serialize_canonical() {
+        let ordered = "place A\n  X -> B\n  Y -> A\n\nplace B\n";
+        let reordered = "place B\n\nplace A\n  Y -> A\n  X -> B\n";
+        let canonical = Options {
+            canonical: true,
+            ..Options::default()
+        };
+
+        assert_ne!(
+            export(ordered, &Options::default()).unwrap(),
+            export(reordered, &Options::default()).unwrap()
+        );
+        assert_eq!(
+            export(ordered, &canonical).unwrap(),
+            export(reordered, &canonical).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_serialize_repeated_affordance() {
+        let input = indoc! {"
+            place Home
+              include Header
+              Logo -> Support
+
+            place Support
+
+            component Header
+              Logo -> Home
+        "};
+
+        let output = export(input, &Options::default()).unwrap();
+
+        assert!(
+            output.contains("  \"Home\":\n    | \"Logo\";\n"),
+            "{output}"
+        );
+        assert!(
+            output.contains(
+                "    \"Logo\": [{ to: \"Home\", kind: \"Navigate\" }, \
+                 { to: \"Support\", kind: \"Navigate\" }],\n"
+            ),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn test_serialize_unknown_place() {
+        let error = export("place Home\n  Go -> Away\n", &Options::default()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "affordance `Go` in `Home` connects to unknown place `Away`"
+        );
+    }
+
+    #[test]
+    fn test_serialize_duplicate_place() {
+        let input = "place Home\n  Go -> Home\n\nplace Home\n";
+
+        let error = export(input, &Options::default()).unwrap_err();
+        assert_eq!(error.to_string(), "duplicate place `Home`");
+
+        let breadboard = bnb_parser::parse(input).unwrap();
+
+        let error = TypeScript
+            .export(&mut vec![], &breadboard, &Options::default())
+            .unwrap_err();
+        assert!(matches!(error, bnb_convert::Error::Unrepresentable(_)));
+    }
+}
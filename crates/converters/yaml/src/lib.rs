@@ -0,0 +1,417 @@
+//! # Bread'n'Butter YAML Converter
+//!
+//! **A buttery smooth YAML conversion experience.**
+//!
+//! The `bnb_converter_yaml` crate provides utility functions for serializing and deserializing
+//! `Breadboard` structures to and from YAML format.
+//!
+//! ## Overview
+//!
+//! The crate mirrors the API of `bnb_converter_json`:
+//!
+//! - [`serialize`]: Converts a `Breadboard` instance into a YAML representation.
+//! - [`deserialize`]: Constructs a `Breadboard` instance from YAML data.
+//! - [`Yaml`]: The same functionality through the [`bnb_convert`] traits.
+//!
+//! ## Structure
+//!
+//! The YAML document has the same structure as the JSON format, including the versioned envelope
+//! and migrations of older documents. Some parts of the AST are represented explicitly:
+//!
+//! - Items are written as single-key maps (`affordance: {...}` or `reference: {...}`), instead of
+//!   the YAML tags (`!affordance`) `serde_norway` uses for enums by default, which most YAML
+//!   generators cannot produce.
+//! - A [`Coordinate`](bnb_ast::Coordinate) is either a plain integer for absolute coordinates, or
+//!   a map with `place`, `offset` and `pivot` keys for relative coordinates.
+//!
+//! ## Examples
+//!
+//! See the function-level documentation for examples.
+
+use std::io::{Read, Write};
+
+use bnb_ast::Breadboard;
+use bnb_convert::{Exporter, Format, Importer};
+use serde_json::Value;
+
+pub use bnb_convert::Options;
+
+/// Errors that can occur while serializing or deserializing breadboards.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The input is not valid YAML.
+    #[error(transparent)]
+    Yaml(#[from] serde_norway::Error),
+
+    /// The document does not match the breadboard structure.
+    #[error(transparent)]
+    Breadboard(#[from] bnb_converter_json::Error),
+}
+
+impl From<Error> for bnb_convert::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(error) => Self::Io(error),
+            error => Self::invalid(error),
+        }
+    }
+}
+
+/// The YAML [`Format`], for use with a [`bnb_convert::Registry`].
+///
+/// # Examples
+///
+/// ```
+/// use bnb_convert::Registry;
+/// use bnb_converter_yaml::Yaml;
+///
+/// let registry = Registry::default().with(Yaml);
+///
+/// assert!(registry.for_extension("yml").is_some());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Yaml;
+
+impl Format for Yaml {
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yaml", "yml"]
+    }
+
+    fn importer(&self) -> Option<&dyn Importer> {
+        Some(self)
+    }
+
+    fn exporter(&self) -> Option<&dyn Exporter> {
+        Some(self)
+    }
+}
+
+impl Importer for Yaml {
+    fn import(&self, reader: &mut dyn Read, _: &Options) -> Result<Breadboard, bnb_convert::Error> {
+        Ok(deserialize(reader)?)
+    }
+}
+
+impl Exporter for Yaml {
+    fn export(
+        &self,
+        writer: &mut dyn Write,
+        breadboard: &Breadboard,
+        options: &Options,
+    ) -> Result<(), bnb_convert::Error> {
+        serialize(writer, breadboard, options).map_err(|error| match error {
+            Error::Io(error) => bnb_convert::Error::Io(error),
+            error => bnb_convert::Error::unrepresentable(error),
+        })
+    }
+}
+
+/// Serializes a `Breadboard` structure into YAML format, wrapped in a versioned envelope.
+///
+/// YAML output is always human-readable, so [`Options::pretty`] has no effect. Keys are sorted,
+/// except for the keys of attributes, which are written in the order they are defined, unless
+/// [`Options::canonical`] is set.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_ast::Breadboard;
+/// use bnb_converter_yaml::{serialize, Options};
+///
+/// let breadboard = Breadboard::default();
+/// let mut buffer = vec![];
+/// serialize(&mut buffer, &breadboard, &Options::default()).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(buffer).unwrap(),
+///     "breadboard:\n  components: []\n  places: []\nversion: 1\n",
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if writing to the writer fails.
+pub fn serialize(
+    mut writer: impl Write,
+    breadboard: &Breadboard,
+    options: &Options,
+) -> Result<(), Error> {
+    let mut value = bnb_converter_json::to_value(breadboard)?;
+    sort_keys(&mut value, options.canonical);
+
+    writer.write_all(serde_norway::to_string(&value)?.as_bytes())?;
+
+    Ok(())
+}
+
+/// Deserializes YAML data into a `Breadboard` structure.
+///
+/// Documents written by older versions, including documents without an envelope, are migrated to
+/// the current structure before deserializing.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_converter_yaml::deserialize;
+///
+/// let yaml = "
+/// version: 1
+/// breadboard:
+///   places:
+///     - name: Home
+///       description: []
+///       items: []
+///       position: { x: 0, y: { place: Login, offset: 10, pivot: Bottom } }
+///       sketch: null
+///   components: []
+/// ";
+/// let breadboard = deserialize(yaml.as_bytes()).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// This conversion can fail if the input is not valid YAML, or if its structure does not match
+/// the structure expected by `Breadboard`.
+pub fn deserialize(mut reader: impl Read) -> Result<Breadboard, Error> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let value: Value = serde_norway::from_str(&input)?;
+
+    Ok(bnb_converter_json::from_value(value)?)
+}
+
+/// Sorts the keys of all maps in the value, for a stable output.
+///
+/// Attributes keep the order they are defined in, unless the output is `canonical`.
+fn sort_keys(value: &mut Value, canonical: bool) {
+    match value {
+        Value::Object(map) => {
+            map.sort_keys();
+
+            for (key, value) in map {
+                if key != "attributes" || canonical {
+                    sort_keys(value, canonical);
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| sort_keys(value, canonical)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bnb_ast::{
+        Affordance, Area, Attributes, Component, Connection, ConnectionKind, Coordinate, Item,
+        Pivot, Place, Position, Reference, Sketch,
+    };
+
+    use super::*;
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_serialize_deserialize() {
+        let breadboard = Breadboard {
+            imports: vec![],
+            places: vec![
+                Place {
+                    name: "Registration".to_owned(),
+                    attributes: Attributes::from([
+                        ("ticket".to_owned(), "ABC-12".to_owned()),
+                        ("owner".to_owned(), "payments".to_owned()),
+                    ]),
+                    items: vec![
+                        Item::Affordance(Affordance {
+                            name: "Username".to_owned(),
+                            connections: vec![],
+                            description: vec![],
+                            kind: Some("input".to_owned()),
+                            attributes: Attributes::from([(
+                                "placeholder".to_owned(),
+                                "Your name".to_owned(),
+                            )]),
+                            level: 0,
+                        }),
+                        Item::Affordance(Affordance {
+                            name: "Password".to_owned(),
+                            connections: vec![],
+                            description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
+                            level: 0,
+                        }),
+                        Item::Affordance(Affordance {
+                            name: "Sign Up".to_owned(),
+                            connections: vec![
+                                Connection {
+                                    target_place: "Home".to_owned(),
+                                    target_affordance: None,
+                                    kind: ConnectionKind::Navigate,
+                                    description: Some("success".to_owned()),
+                                },
+                                Connection {
+                                    target_place: "Support".to_owned(),
+                                    target_affordance: None,
+                                    kind: ConnectionKind::Navigate,
+                                    description: Some("failure".to_owned()),
+                                },
+                            ],
+                            description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
+                            level: 0,
+                        }),
+                        Item::Reference(Reference {
+                            name: "Header".to_owned(),
+                            level: 0,
+                        }),
+                    ],
+                    position: Some(Position {
+                        x: Coordinate::Absolute(-10),
+                        y: Coordinate::Relative {
+                            place: "Support".to_owned(),
+                            offset: 20,
+                            pivot: Pivot::Left,
+                        },
+                    }),
+                    sketch: Some(Sketch {
+                        path: std::path::PathBuf::from("sketches/registration.png"),
+                        areas: vec![Area {
+                            top_left: (50, 20),
+                            width: 110,
+                            height: 40,
+                            affordance: "Sign Up".to_owned(),
+                        }],
+                    }),
+                    description: vec![],
+                    origin: None,
+                },
+                Place {
+                    name: "Support".to_owned(),
+                    attributes: Attributes::new(),
+                    items: vec![
+                        Item::Affordance(Affordance {
+                            name: "Error Message".to_owned(),
+                            connections: vec![],
+                            description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
+                            level: 0,
+                        }),
+                        Item::Affordance(Affordance {
+                            name: "Try Again".to_owned(),
+                            connections: vec![Connection {
+                                target_place: "Registration".to_owned(),
+                                target_affordance: None,
+                                kind: ConnectionKind::Navigate,
+                                description: None,
+                            }],
+                            description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
+                            level: 0,
+                        }),
+                        Item::Reference(Reference {
+                            name: "Header".to_owned(),
+                            level: 0,
+                        }),
+                    ],
+                    position: None,
+                    sketch: Some(Sketch {
+                        path: PathBuf::from("sketches/support.png"),
+                        areas: vec![Area {
+                            top_left: (50, 20),
+                            width: 110,
+                            height: 40,
+                            affordance: "Try Again".to_owned(),
+                        }],
+                    }),
+                    description: vec![],
+                    origin: None,
+                },
+                Place {
+                    name: "Home".to_owned(),
+                    attributes: Attributes::new(),
+                    items: vec![
+                        Item::Affordance(Affordance {
+                            name: "Dashboard".to_owned(),
+                            connections: vec![],
+                            description: vec![],
+                            kind: None,
+                            attributes: Attributes::new(),
+                            level: 0,
+                        }),
+                        Item::Reference(Reference {
+                            name: "Header".to_owned(),
+                            level: 0,
+                        }),
+                    ],
+                    position: None,
+                    sketch: Some(Sketch {
+                        path: PathBuf::from("sketches/home.png"),
+                        areas: vec![],
+                    }),
+                    description: vec![],
+                    origin: None,
+                },
+            ],
+            components: vec![Component::new(Place {
+                name: "Header".to_owned(),
+                attributes: Attributes::new(),
+                items: vec![
+                    Item::Affordance(Affordance {
+                        name: "Logo".to_owned(),
+                        connections: vec![],
+                        description: vec![],
+                        kind: None,
+                        attributes: Attributes::new(),
+                        level: 0,
+                    }),
+                    Item::Affordance(Affordance {
+                        name: "Contact".to_owned(),
+                        connections: vec![],
+                        description: vec![],
+                        kind: None,
+                        attributes: Attributes::new(),
+                        level: 0,
+                    }),
+                ],
+                position: None,
+                sketch: None,
+                description: vec![],
+                origin: None,
+            })],
+            flows: vec![],
+        };
+
+        // Serialize the Breadboard
+        let mut serialized_data = Vec::new();
+        serialize(&mut serialized_data, &breadboard, &Options::default())
+            .expect("Serialization failed");
+
+        insta::assert_snapshot!(String::from_utf8(serialized_data.clone()).unwrap());
+
+        // Deserialize the Breadboard
+        let deserialized_breadboard: Breadboard =
+            deserialize(&mut serialized_data.as_slice()).expect("Deserialization failed");
+
+        // Attributes keep the order they are defined in.
+        let attributes = &deserialized_breadboard.places[0].attributes;
+        assert!(attributes.keys().eq(["ticket", "owner"]));
+
+        assert_eq!(deserialized_breadboard, breadboard);
+    }
+}
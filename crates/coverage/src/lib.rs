@@ -0,0 +1,617 @@
+//! # Bread'n'Butter Coverage
+//!
+//! **A buttery smooth coverage experience.**
+//!
+//! The `bnb_coverage` crate maps a trace of recorded navigation events, e.g. logged by an
+//! end-to-end test suite, onto the places, affordances and connections of a [`Breadboard`], to
+//! show which parts of the design were exercised.
+//!
+//! ## Traces
+//!
+//! A trace is a JSON-lines file, with one [`Event`] per line, read with [`read_trace`]:
+//!
+//! ```json
+//! {"place": "Login", "affordance": "Sign In", "target": "Home"}
+//! ```
+//!
+//! Other fields, such as timestamps, are ignored. Blank lines are skipped.
+//!
+//! ## Coverage
+//!
+//! [`coverage`] counts the events of each place, affordance and connection. Affordances included
+//! from components are covered separately in every place that includes them, affordances with the
+//! same name in a place are covered as one, and a connection is covered by any event that follows
+//! it to its target place, regardless of its description.
+//!
+//! Events that do not match the design are reported as [`Drift`]: transitions from unknown places,
+//! through unknown affordances, or to places the affordance has no connection to.
+//!
+//! The [`Coverage`] can be saved as a JSON overlay with [`write_overlay`], which Butter loads to
+//! tint each connection by its own coverage, so a covered affordance can still show which of its
+//! connections were never followed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_coverage::{coverage, read_trace};
+//!
+//! let breadboard = bnb_parser::parse(
+//!     "place Login\n  Sign In -> Home\n  Help -> Support\n\nplace Home\n\nplace Support\n",
+//! )
+//! .unwrap();
+//! let trace = r#"{"place": "Login", "affordance": "Sign In", "target": "Home"}"#;
+//! let trace = read_trace(trace.as_bytes()).unwrap();
+//!
+//! let coverage = coverage(&breadboard, &trace);
+//! let summary = coverage.summary();
+//!
+//! assert_eq!((summary.connections.covered, summary.connections.total), (1, 2));
+//! assert!(coverage.drift.is_empty());
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+use std::{
+    fmt,
+    io::{self, BufRead, Read, Write},
+};
+
+use bnb_ast::Breadboard;
+use serde::{Deserialize, Serialize};
+
+/// A recorded transition from one place to another, through an affordance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// The place the affordance was triggered in.
+    pub place: String,
+
+    /// The affordance that was triggered.
+    pub affordance: String,
+
+    /// The place the transition led to.
+    pub target: String,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.place, self.affordance, self.target)
+    }
+}
+
+/// The coverage of a breadboard by a trace, computed by [`coverage`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Coverage {
+    /// Every place of the breadboard, in the order they are defined in.
+    pub places: Vec<Place>,
+
+    /// The events that do not match the breadboard, in the order they first occurred in.
+    pub drift: Vec<Drift>,
+}
+
+/// The coverage of a place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Place {
+    pub name: String,
+
+    /// The number of events from or to the place.
+    pub hits: usize,
+
+    /// The affordances of the place, including those of the components it includes.
+    pub affordances: Vec<Affordance>,
+}
+
+/// The coverage of an affordance in a place.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affordance {
+    pub name: String,
+
+    /// The number of events through the affordance, including those that drifted.
+    pub hits: usize,
+
+    /// The connections of the affordance, including those of affordances with the same name.
+    pub connections: Vec<Connection>,
+}
+
+/// The coverage of a connection of an affordance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Connection {
+    /// The place the connection leads to.
+    pub target: String,
+
+    pub description: Option<String>,
+
+    /// The number of events that followed the connection.
+    pub hits: usize,
+}
+
+/// Events that do not match the breadboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    #[serde(flatten)]
+    pub event: Event,
+
+    /// Why the event does not match.
+    pub reason: Reason,
+
+    /// The number of identical events.
+    pub count: usize,
+}
+
+/// Why an event does not match the breadboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    /// The breadboard has no such place.
+    UnknownPlace,
+
+    /// The place has no such affordance.
+    UnknownAffordance,
+
+    /// The affordance has no connection to the target place.
+    UnknownConnection,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnknownPlace => "unknown place",
+            Self::UnknownAffordance => "unknown affordance",
+            Self::UnknownConnection => "unknown connection",
+        })
+    }
+}
+
+/// How much of an affordance is covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// All of its connections were followed, or it was triggered if it has none.
+    Covered,
+
+    /// Some, but not all of its connections were followed.
+    Partial,
+
+    /// None of its connections were followed.
+    Uncovered,
+}
+
+/// The number of covered items, out of all items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Ratio {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl Ratio {
+    /// Returns the covered share of the items, from 0 to 100, or 100 if there are none.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss, reason = "counts are far below 2^52")]
+    pub fn percent(self) -> f64 {
+        if self.total == 0 {
+            return 100.;
+        }
+
+        self.covered as f64 * 100. / self.total as f64
+    }
+
+    fn add(&mut self, covered: bool) {
+        self.total += 1;
+        self.covered += usize::from(covered);
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} ({:.1}%)",
+            self.covered,
+            self.total,
+            self.percent()
+        )
+    }
+}
+
+/// The covered share of the places, affordances and connections of a breadboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Summary {
+    pub places: Ratio,
+    pub affordances: Ratio,
+    pub connections: Ratio,
+}
+
+impl Coverage {
+    /// Returns the covered share of the places, affordances and connections.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary::default();
+
+        for place in &self.places {
+            summary.places.add(place.hits > 0);
+
+            for affordance in &place.affordances {
+                summary.affordances.add(affordance.hits > 0);
+
+                for connection in &affordance.connections {
+                    summary.connections.add(connection.hits > 0);
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Returns how much of the affordance in the given place is covered, or `None` if the place
+    /// has no such affordance.
+    #[must_use]
+    pub fn affordance(&self, place: &str, affordance: &str) -> Option<Status> {
+        let affordance = self
+            .places
+            .iter()
+            .find(|p| p.name == place)?
+            .affordances
+            .iter()
+            .find(|a| a.name == affordance)?;
+
+        let followed = affordance.connections.iter().filter(|c| c.hits > 0).count();
+
+        Some(match (followed, affordance.connections.len()) {
+            (0, 0) if affordance.hits > 0 => Status::Covered,
+            (0, _) => Status::Uncovered,
+            (followed, total) if followed == total => Status::Covered,
+            _ => Status::Partial,
+        })
+    }
+
+    /// Returns whether the connection from the affordance in the given place to the target place
+    /// was followed, or `None` if the affordance has no such connection.
+    ///
+    /// A connection is either [`Status::Covered`] or [`Status::Uncovered`], never partially.
+    #[must_use]
+    pub fn connection(&self, place: &str, affordance: &str, target: &str) -> Option<Status> {
+        let connection = self
+            .places
+            .iter()
+            .find(|p| p.name == place)?
+            .affordances
+            .iter()
+            .find(|a| a.name == affordance)?
+            .connections
+            .iter()
+            .find(|c| c.target == target)?;
+
+        Some(if connection.hits > 0 {
+            Status::Covered
+        } else {
+            Status::Uncovered
+        })
+    }
+}
+
+/// Returns the coverage of the breadboard by the events of the trace.
+#[must_use]
+pub fn coverage(breadboard: &Breadboard, trace: &[Event]) -> Coverage {
+    let mut coverage = Coverage::default();
+
+    for place in &breadboard.places {
+        // Places defined more than once are covered by their first definition.
+        if coverage.places.iter().any(|p| p.name == place.name) {
+            continue;
+        }
+
+        let affordances = breadboard
+            .unique_affordances(place)
+            .into_iter()
+            .map(|affordance| Affordance {
+                name: affordance.name,
+                hits: 0,
+                connections: affordance
+                    .connections
+                    .into_iter()
+                    .map(|connection| Connection {
+                        target: connection.target_place,
+                        description: connection.description,
+                        hits: 0,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        coverage.places.push(Place {
+            name: place.name.clone(),
+            hits: 0,
+            affordances,
+        });
+    }
+
+    for event in trace {
+        if let Some(reason) = coverage.record(event) {
+            match coverage.drift.iter_mut().find(|d| d.event == *event) {
+                Some(drift) => drift.count += 1,
+                None => coverage.drift.push(Drift {
+                    event: event.clone(),
+                    reason,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    coverage
+}
+
+impl Coverage {
+    /// Counts the event, and returns why it does not match the breadboard, if it does not.
+    fn record(&mut self, event: &Event) -> Option<Reason> {
+        if event.target != event.place {
+            if let Some(target) = self.places.iter_mut().find(|p| p.name == event.target) {
+                target.hits += 1;
+            }
+        }
+
+        let Some(place) = self.places.iter_mut().find(|p| p.name == event.place) else {
+            return Some(Reason::UnknownPlace);
+        };
+        place.hits += 1;
+
+        let Some(affordance) = place
+            .affordances
+            .iter_mut()
+            .find(|a| a.name == event.affordance)
+        else {
+            return Some(Reason::UnknownAffordance);
+        };
+        affordance.hits += 1;
+
+        let mut followed = false;
+        for connection in &mut affordance.connections {
+            if connection.target == event.target {
+                connection.hits += 1;
+                followed = true;
+            }
+        }
+
+        (!followed).then_some(Reason::UnknownConnection)
+    }
+}
+
+/// Reads a JSON-lines trace, with one [`Event`] per line.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, or if a line is not a valid event.
+pub fn read_trace(reader: impl BufRead) -> Result<Vec<Event>, Error> {
+    let mut trace = vec![];
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let event = serde_json::from_str(&line).map_err(|source| Error::Event {
+            line: i + 1,
+            source,
+        })?;
+        trace.push(event);
+    }
+
+    Ok(trace)
+}
+
+/// Reads a coverage overlay, written by [`write_overlay`].
+///
+/// # Errors
+///
+/// Returns an error if reading fails, or if the overlay is invalid.
+pub fn read_overlay(reader: impl Read) -> Result<Coverage, Error> {
+    serde_json::from_reader(reader).map_err(Error::Overlay)
+}
+
+/// Writes the coverage as a JSON overlay, to be loaded by Butter.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn write_overlay(mut writer: impl Write, coverage: &Coverage) -> Result<(), Error> {
+    serde_json::to_writer_pretty(&mut writer, coverage).map_err(Error::Overlay)?;
+    writer.write_all(b"\n")?;
+
+    Ok(())
+}
+
+/// Errors that can occur while reading traces and overlays.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// A line of the trace is not a valid event.
+    #[error("invalid event on line {line}: {source}")]
+    Event {
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    /// The overlay is not valid coverage.
+    #[error("invalid coverage overlay: {0}")]
+    Overlay(serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const INPUT: &str = indoc! {"
+        place Login
+          include Header
+          Sign In -> (success) Home
+                  -> (failure) Login
+          Help -> Support
+
+        place Home
+          include Header
+          Sign Out -> Login
+
+        place Support
+
+        component Header
+          Logo -> Home
+    "};
+
+    const TRACE: &str = indoc! {r#"
+        {"place": "Login", "affordance": "Sign In", "target": "Login", "time": 1}
+        {"place": "Login", "affordance": "Sign In", "target": "Home"}
+
+        {"place": "Home", "affordance": "Logo", "target": "Home"}
+        {"place": "Home", "affordance": "Sign Out", "target": "Support"}
+        {"place": "Home", "affordance": "Settings", "target": "Settings"}
+        {"place": "Settings", "affordance": "Back", "target": "Home"}
+        {"place": "Home", "affordance": "Sign Out", "target": "Support"}
+    "#};
+
+    fn coverage() -> Coverage {
+        let breadboard = bnb_parser::parse(INPUT).unwrap();
+        let trace = read_trace(TRACE.as_bytes()).unwrap();
+
+        super::coverage(&breadboard, &trace)
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = coverage().summary();
+
+        assert_eq!(
+            summary.places,
+            Ratio {
+                covered: 3,
+                total: 3
+            }
+        );
+        assert_eq!(
+            summary.affordances,
+            Ratio {
+                covered: 3,
+                total: 5
+            }
+        );
+        assert_eq!(
+            summary.connections,
+            Ratio {
+                covered: 3,
+                total: 6
+            }
+        );
+        assert_eq!(summary.connections.to_string(), "3/6 (50.0%)");
+        assert!((Ratio::default().percent() - 100.).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_affordance_status() {
+        let coverage = coverage();
+
+        assert_eq!(
+            coverage.affordance("Login", "Sign In"),
+            Some(Status::Covered)
+        );
+        assert_eq!(
+            coverage.affordance("Login", "Logo"),
+            Some(Status::Uncovered)
+        );
+        assert_eq!(
+            coverage.affordance("Login", "Help"),
+            Some(Status::Uncovered)
+        );
+        assert_eq!(coverage.affordance("Home", "Logo"), Some(Status::Covered));
+        assert_eq!(
+            coverage.affordance("Home", "Sign Out"),
+            Some(Status::Uncovered)
+        );
+        assert_eq!(coverage.affordance("Home", "Missing"), None);
+
+        assert_eq!(
+            coverage.connection("Login", "Sign In", "Home"),
+            Some(Status::Covered)
+        );
+        assert_eq!(
+            coverage.connection("Login", "Help", "Support"),
+            Some(Status::Uncovered)
+        );
+        assert_eq!(coverage.connection("Login", "Help", "Home"), None);
+
+        let breadboard = bnb_parser::parse("place Home\n  Go -> A\n     -> B\n").unwrap();
+        let trace =
+            read_trace(r#"{"place": "Home", "affordance": "Go", "target": "A"}"#.as_bytes())
+                .unwrap();
+        assert_eq!(
+            super::coverage(&breadboard, &trace).affordance("Home", "Go"),
+            Some(Status::Partial)
+        );
+    }
+
+    #[test]
+    fn test_drift() {
+        let drift: Vec<_> = coverage()
+            .drift
+            .iter()
+            .map(|d| format!("{} ({}, {})", d.event, d.reason, d.count))
+            .collect();
+
+        assert_eq!(
+            drift,
+            [
+                "Home: Sign Out -> Support (unknown connection, 2)",
+                "Home: Settings -> Settings (unknown affordance, 1)",
+                "Settings: Back -> Home (unknown place, 1)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeated_affordance() {
+        let breadboard = bnb_parser::parse(indoc! {"
+            place Home
+              include Header
+              Logo -> Support
+
+            place Support
+
+            component Header
+              Logo -> Home
+        "})
+        .unwrap();
+        let trace = read_trace(
+            r#"{"place": "Home", "affordance": "Logo", "target": "Support"}"#.as_bytes(),
+        )
+        .unwrap();
+
+        let coverage = super::coverage(&breadboard, &trace);
+        let home = &coverage.places[0];
+
+        assert!(coverage.drift.is_empty());
+        assert_eq!(home.affordances.len(), 1);
+        assert_eq!(home.affordances[0].connections.len(), 2);
+        assert_eq!(coverage.summary().connections.covered, 1);
+    }
+
+    #[test]
+    fn test_overlay() {
+        let coverage = coverage();
+
+        let mut buffer = vec![];
+        write_overlay(&mut buffer, &coverage).unwrap();
+
+        assert_eq!(read_overlay(buffer.as_slice()).unwrap(), coverage);
+        assert!(read_overlay("[]".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_trace_error() {
+        let error = read_trace("\n{\"place\": \"Home\"}\n".as_bytes()).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("invalid event on line 2: missing field `affordance`"));
+    }
+}
@@ -0,0 +1,1209 @@
+//! # Bread'n'Butter Diff
+//!
+//! **A buttery smooth diffing experience.**
+//!
+//! The `bnb_diff` crate compares two versions of a [`Breadboard`] at the design level, instead of
+//! line by line. The result of [`diff`] is a [`Diff`] of typed [`Change`]s, such as places being
+//! added or renamed, affordances moving between places, connections being retargeted, and flows
+//! being added or changed.
+//!
+//! Places (and components) are matched by name. Places that only exist in one of the versions are
+//! matched heuristically, as a rename, if at least half of their affordances, includes and incoming
+//! connections are the same. Connections to a renamed place are not reported as retargeted, and
+//! neither are the steps of flows in a renamed place, or positions relative to a renamed place.
+//! Flows are matched by name.
+//!
+//! A [`Diff`] renders as text through its [`Display`](fmt::Display) implementation, with one
+//! change per line, and serializes to JSON (or any other `serde` format) as a list of changes.
+//!
+//! To show changes in context, [`overlay`] combines both versions into a single breadboard, with
+//! the [`Status`] of every place and affordance that was added, removed or changed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_diff::{diff, Change};
+//!
+//! let old = bnb_parser::parse("place Home\n  Sign In -> Login\n\nplace Login\n").unwrap();
+//! let new = bnb_parser::parse("place Home\n  Sign In -> Auth\n\nplace Auth\n").unwrap();
+//!
+//! let diff = diff(&old, &new);
+//!
+//! assert_eq!(
+//!     diff.changes,
+//!     vec![Change::PlaceRenamed {
+//!         from: "Login".to_owned(),
+//!         to: "Auth".to_owned()
+//!     }]
+//! );
+//! assert_eq!(diff.to_string(), "~ place `Login` renamed to `Auth`\n");
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+mod overlay;
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use bnb_ast::{
+    Affordance, Attributes, Breadboard, Connection, ConnectionKind, Coordinate, Item, Place,
+    Position, Sketch, Step,
+};
+use serde::Serialize;
+
+pub use overlay::{overlay, Overlay, Status};
+
+/// The changes between two versions of a breadboard.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Diff {
+    /// The changes, grouped by the place they occur in, in the order of the old breadboard,
+    /// followed by the changes to flows.
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Returns `true` if both breadboards are structurally the same.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the new names of the places and components that were added or changed.
+    ///
+    /// Removed places are not included, as they no longer exist in the new breadboard, and neither
+    /// are flows.
+    #[must_use]
+    pub fn changed(&self) -> BTreeSet<&str> {
+        let mut changed = BTreeSet::new();
+
+        for change in &self.changes {
+            match change {
+                Change::PlaceRemoved { .. }
+                | Change::ComponentRemoved { .. }
+                | Change::FlowAdded { .. }
+                | Change::FlowRemoved { .. }
+                | Change::FlowDescriptionChanged { .. }
+                | Change::FlowStepsChanged { .. } => {}
+                Change::AffordanceMoved { from, to, .. } => {
+                    changed.insert(from.as_str());
+                    changed.insert(to.as_str());
+                }
+                Change::PlaceAdded { place }
+                | Change::ComponentAdded { component: place }
+                | Change::PlaceRenamed { to: place, .. }
+                | Change::ComponentRenamed { to: place, .. }
+                | Change::IncludeAdded { place, .. }
+                | Change::IncludeRemoved { place, .. }
+                | Change::AffordanceAdded { place, .. }
+                | Change::AffordanceRemoved { place, .. }
+                | Change::ConnectionAdded { place, .. }
+                | Change::ConnectionRemoved { place, .. }
+                | Change::ConnectionRetargeted { place, .. }
+                | Change::ConnectionKindChanged { place, .. }
+                | Change::ConnectionDescriptionChanged { place, .. }
+                | Change::DescriptionChanged { place, .. }
+                | Change::AttributesChanged { place, .. }
+                | Change::KindChanged { place, .. }
+                | Change::PositionChanged { place, .. }
+                | Change::SketchChanged { place, .. } => {
+                    changed.insert(place.as_str());
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+/// A single change between two versions of a breadboard.
+///
+/// Changes within a place or component refer to it by its new name. The `place` of affordance,
+/// include and connection changes can be either a place or a component.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    PlaceAdded {
+        place: String,
+    },
+    PlaceRemoved {
+        place: String,
+    },
+    PlaceRenamed {
+        from: String,
+        to: String,
+    },
+    ComponentAdded {
+        component: String,
+    },
+    ComponentRemoved {
+        component: String,
+    },
+    ComponentRenamed {
+        from: String,
+        to: String,
+    },
+    IncludeAdded {
+        place: String,
+        component: String,
+    },
+    IncludeRemoved {
+        place: String,
+        component: String,
+    },
+    AffordanceAdded {
+        place: String,
+        affordance: String,
+    },
+    AffordanceRemoved {
+        place: String,
+        affordance: String,
+    },
+
+    /// The affordance was removed from one place, and added to another.
+    AffordanceMoved {
+        affordance: String,
+        from: String,
+        to: String,
+    },
+
+    /// A connection was added to the affordance.
+    ///
+    /// The `target` is the name of the target place, followed by `.` and the name of the target
+    /// affordance, if the connection has one.
+    ConnectionAdded {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+    ConnectionRemoved {
+        place: String,
+        affordance: String,
+        target: String,
+    },
+
+    /// A connection of the affordance now leads to a different target.
+    ConnectionRetargeted {
+        place: String,
+        affordance: String,
+        from: String,
+        to: String,
+    },
+
+    /// The kind of a connection of the affordance changed, e.g. from `->` to `~>`.
+    ConnectionKindChanged {
+        place: String,
+        affordance: String,
+        target: String,
+        from: ConnectionKind,
+        to: ConnectionKind,
+    },
+
+    /// The description of a connection of the affordance changed.
+    ConnectionDescriptionChanged {
+        place: String,
+        affordance: String,
+        target: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+
+    /// The description of a place, or of one of its affordances, changed.
+    DescriptionChanged {
+        place: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        affordance: Option<String>,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+
+    /// The attributes of a place, or of one of its affordances, changed.
+    AttributesChanged {
+        place: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        affordance: Option<String>,
+        from: Attributes,
+        to: Attributes,
+    },
+
+    /// The kind of an affordance changed, e.g. from `[button]` to `[link]`.
+    KindChanged {
+        place: String,
+        affordance: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    PositionChanged {
+        place: String,
+        from: Option<Position>,
+        to: Option<Position>,
+    },
+    SketchChanged {
+        place: String,
+        from: Option<Sketch>,
+        to: Option<Sketch>,
+    },
+    FlowAdded {
+        flow: String,
+    },
+    FlowRemoved {
+        flow: String,
+    },
+    FlowDescriptionChanged {
+        flow: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+
+    /// The steps of the flow changed. Steps in renamed places are compared by their new name.
+    FlowStepsChanged {
+        flow: String,
+        from: Vec<Step>,
+        to: Vec<Step>,
+    },
+}
+
+/// Compares two versions of a breadboard.
+///
+/// See the [crate documentation](crate) for how places are matched between the versions.
+#[must_use]
+pub fn diff(old: &Breadboard, new: &Breadboard) -> Diff {
+    let places = Matching::new(
+        (old, old.places.iter().collect()),
+        (new, new.places.iter().collect()),
+    );
+    let components = Matching::new(
+        (old, old.components.iter().map(|c| &**c).collect()),
+        (new, new.components.iter().map(|c| &**c).collect()),
+    );
+
+    let mut differ = Differ {
+        places: places.renames(),
+        components: components.renames(),
+        slots: vec![],
+        removed: vec![],
+        added: vec![],
+    };
+
+    differ.containers(Kind::Place, &places);
+    differ.containers(Kind::Component, &components);
+    differ.moves();
+    differ.flows(old, new);
+
+    Diff {
+        changes: differ.slots.into_iter().flatten().collect(),
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PlaceAdded { place } => write!(f, "+ place `{place}`"),
+            Self::PlaceRemoved { place } => write!(f, "- place `{place}`"),
+            Self::PlaceRenamed { from, to } => write!(f, "~ place `{from}` renamed to `{to}`"),
+            Self::ComponentAdded { component } => write!(f, "+ component `{component}`"),
+            Self::ComponentRemoved { component } => write!(f, "- component `{component}`"),
+            Self::ComponentRenamed { from, to } => {
+                write!(f, "~ component `{from}` renamed to `{to}`")
+            }
+            Self::IncludeAdded { place, component } => {
+                write!(f, "+ include `{component}` in `{place}`")
+            }
+            Self::IncludeRemoved { place, component } => {
+                write!(f, "- include `{component}` in `{place}`")
+            }
+            Self::AffordanceAdded { place, affordance } => {
+                write!(f, "+ affordance `{affordance}` in `{place}`")
+            }
+            Self::AffordanceRemoved { place, affordance } => {
+                write!(f, "- affordance `{affordance}` in `{place}`")
+            }
+            Self::AffordanceMoved {
+                affordance,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ affordance `{affordance}` moved from `{from}` to `{to}`"
+            ),
+            Self::ConnectionAdded {
+                place,
+                affordance,
+                target,
+            } => write!(f, "+ connection `{affordance}` -> `{target}` in `{place}`"),
+            Self::ConnectionRemoved {
+                place,
+                affordance,
+                target,
+            } => write!(f, "- connection `{affordance}` -> `{target}` in `{place}`"),
+            Self::ConnectionRetargeted {
+                place,
+                affordance,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ connection `{affordance}` in `{place}` retargeted from `{from}` to `{to}`"
+            ),
+            Self::DescriptionChanged {
+                place,
+                affordance: None,
+                ..
+            } => write!(f, "~ description of `{place}`"),
+            Self::DescriptionChanged {
+                place,
+                affordance: Some(affordance),
+                ..
+            } => write!(f, "~ description of `{affordance}` in `{place}`"),
+            Self::ConnectionKindChanged {
+                place,
+                affordance,
+                target,
+                ..
+            } => write!(
+                f,
+                "~ kind of connection `{affordance}` -> `{target}` in `{place}`"
+            ),
+            Self::ConnectionDescriptionChanged {
+                place,
+                affordance,
+                target,
+                ..
+            } => write!(
+                f,
+                "~ description of connection `{affordance}` -> `{target}` in `{place}`"
+            ),
+            Self::AttributesChanged {
+                place,
+                affordance: None,
+                ..
+            } => write!(f, "~ attributes of `{place}`"),
+            Self::AttributesChanged {
+                place,
+                affordance: Some(affordance),
+                ..
+            } => write!(f, "~ attributes of `{affordance}` in `{place}`"),
+            Self::KindChanged {
+                place, affordance, ..
+            } => write!(f, "~ kind of `{affordance}` in `{place}`"),
+            Self::PositionChanged { place, .. } => write!(f, "~ position of `{place}`"),
+            Self::SketchChanged { place, .. } => write!(f, "~ sketch of `{place}`"),
+            Self::FlowAdded { flow } => write!(f, "+ flow `{flow}`"),
+            Self::FlowRemoved { flow } => write!(f, "- flow `{flow}`"),
+            Self::FlowDescriptionChanged { flow, .. } => {
+                write!(f, "~ description of flow `{flow}`")
+            }
+            Self::FlowStepsChanged { flow, .. } => write!(f, "~ steps of flow `{flow}`"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Place,
+    Component,
+}
+
+/// The pairing of the places (or components) of the old and new breadboard.
+struct Matching<'a> {
+    old: Vec<&'a Place>,
+    new: Vec<&'a Place>,
+
+    /// The index of the matching new place of each old place, if any.
+    matches: Vec<Option<usize>>,
+}
+
+impl<'a> Matching<'a> {
+    fn new(
+        (old_breadboard, old): (&'a Breadboard, Vec<&'a Place>),
+        (new_breadboard, new): (&'a Breadboard, Vec<&'a Place>),
+    ) -> Self {
+        let mut matches = vec![None; old.len()];
+        let mut taken = vec![false; new.len()];
+
+        for (i, place) in old.iter().enumerate() {
+            if let Some(j) = (0..new.len()).find(|&j| !taken[j] && new[j].name == place.name) {
+                matches[i] = Some(j);
+                taken[j] = true;
+            }
+        }
+
+        // Pair up the remaining places as renames, most similar first.
+        let mut candidates = vec![];
+        for i in (0..old.len()).filter(|&i| matches[i].is_none()) {
+            for j in (0..new.len()).filter(|&j| !taken[j]) {
+                let (common, total) = similarity(
+                    &features(old_breadboard, old[i]),
+                    &features(new_breadboard, new[j]),
+                );
+                if common > 0 && 2 * common >= total {
+                    candidates.push((common, total, i, j));
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            (b.0 * a.1)
+                .cmp(&(a.0 * b.1))
+                .then((a.2, a.3).cmp(&(b.2, b.3)))
+        });
+
+        for (_, _, i, j) in candidates {
+            if matches[i].is_none() && !taken[j] {
+                matches[i] = Some(j);
+                taken[j] = true;
+            }
+        }
+
+        Self { old, new, matches }
+    }
+
+    /// Returns the new name of each renamed place, by its old name.
+    fn renames(&self) -> HashMap<&'a str, &'a str> {
+        self.pairs()
+            .filter(|(old, new)| old.name != new.name)
+            .map(|(old, new)| (old.name.as_str(), new.name.as_str()))
+            .collect()
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&'a Place, &'a Place)> + '_ {
+        self.old
+            .iter()
+            .zip(&self.matches)
+            .filter_map(|(&old, j)| j.map(|j| (old, self.new[j])))
+    }
+
+    fn added(&self) -> impl Iterator<Item = &'a Place> + '_ {
+        self.new
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| !self.matches.contains(&Some(*j)))
+            .map(|(_, &place)| place)
+    }
+}
+
+/// A trait of a place, used to match renamed places.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Feature<'a> {
+    Affordance(&'a str),
+    Include(&'a str),
+
+    /// A connection to the place, from the given place and affordance.
+    Incoming(&'a str, &'a str),
+}
+
+fn features<'a>(breadboard: &'a Breadboard, place: &'a Place) -> HashSet<Feature<'a>> {
+    let items = place.items.iter().map(|item| match item {
+        Item::Affordance(affordance) => Feature::Affordance(&affordance.name),
+        Item::Reference(reference) => Feature::Include(&reference.name),
+    });
+
+    let sources = breadboard
+        .places
+        .iter()
+        .chain(breadboard.components.iter().map(|c| &**c));
+    let incoming = sources.flat_map(|source| {
+        affordances(source)
+            .filter(|affordance| {
+                affordance
+                    .connections
+                    .iter()
+                    .any(|connection| connection.target_place == place.name)
+            })
+            .map(|affordance| Feature::Incoming(&source.name, &affordance.name))
+    });
+
+    items.chain(incoming).collect()
+}
+
+/// Returns the number of features two places have in common, and the number of distinct features
+/// of both places together.
+fn similarity(old: &HashSet<Feature<'_>>, new: &HashSet<Feature<'_>>) -> (usize, usize) {
+    (old.intersection(new).count(), old.union(new).count())
+}
+
+struct Differ<'a> {
+    /// The new names of renamed places, by their old names.
+    places: HashMap<&'a str, &'a str>,
+
+    /// The new names of renamed components, by their old names.
+    components: HashMap<&'a str, &'a str>,
+
+    /// The changes found so far, in groups that can be replaced once moves are detected.
+    slots: Vec<Vec<Change>>,
+
+    /// The slot, place and affordance of each removed affordance.
+    removed: Vec<(usize, &'a str, &'a Affordance)>,
+
+    /// The slot, place and affordance of each added affordance.
+    added: Vec<(usize, &'a str, &'a Affordance)>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, change: Change) -> usize {
+        self.slots.push(vec![change]);
+        self.slots.len() - 1
+    }
+
+    fn containers(&mut self, kind: Kind, matching: &Matching<'a>) {
+        for (i, &old) in matching.old.iter().enumerate() {
+            let Some(j) = matching.matches[i] else {
+                self.push(match kind {
+                    Kind::Place => Change::PlaceRemoved {
+                        place: old.name.clone(),
+                    },
+                    Kind::Component => Change::ComponentRemoved {
+                        component: old.name.clone(),
+                    },
+                });
+                continue;
+            };
+
+            let new = matching.new[j];
+            if old.name != new.name {
+                let (from, to) = (old.name.clone(), new.name.clone());
+                self.push(match kind {
+                    Kind::Place => Change::PlaceRenamed { from, to },
+                    Kind::Component => Change::ComponentRenamed { from, to },
+                });
+            }
+
+            self.place(old, new);
+        }
+
+        for new in matching.added() {
+            self.push(match kind {
+                Kind::Place => Change::PlaceAdded {
+                    place: new.name.clone(),
+                },
+                Kind::Component => Change::ComponentAdded {
+                    component: new.name.clone(),
+                },
+            });
+        }
+    }
+
+    fn place(&mut self, old: &'a Place, new: &'a Place) {
+        let place = new.name.as_str();
+
+        if old.description != new.description {
+            self.push(Change::DescriptionChanged {
+                place: place.to_owned(),
+                affordance: None,
+                from: old.description.clone(),
+                to: new.description.clone(),
+            });
+        }
+
+        if old.attributes != new.attributes {
+            self.push(Change::AttributesChanged {
+                place: place.to_owned(),
+                affordance: None,
+                from: old.attributes.clone(),
+                to: new.attributes.clone(),
+            });
+        }
+
+        // Positions relative to a renamed place are compared by its new name.
+        let position = old.position.clone().map(|mut position| {
+            for coordinate in [&mut position.x, &mut position.y] {
+                if let Coordinate::Relative { place, .. } = coordinate {
+                    if let Some(&renamed) = self.places.get(place.as_str()) {
+                        renamed.clone_into(place);
+                    }
+                }
+            }
+
+            position
+        });
+
+        if position != new.position {
+            self.push(Change::PositionChanged {
+                place: place.to_owned(),
+                from: old.position.clone(),
+                to: new.position.clone(),
+            });
+        }
+
+        if old.sketch != new.sketch {
+            self.push(Change::SketchChanged {
+                place: place.to_owned(),
+                from: old.sketch.clone(),
+                to: new.sketch.clone(),
+            });
+        }
+
+        let old_includes: Vec<_> = includes(old)
+            .map(|name| *self.components.get(name).unwrap_or(&name))
+            .collect();
+        let new_includes: Vec<_> = includes(new).collect();
+
+        for &component in old_includes.iter().filter(|c| !new_includes.contains(c)) {
+            self.push(Change::IncludeRemoved {
+                place: place.to_owned(),
+                component: component.to_owned(),
+            });
+        }
+
+        for &component in new_includes.iter().filter(|c| !old_includes.contains(c)) {
+            self.push(Change::IncludeAdded {
+                place: place.to_owned(),
+                component: component.to_owned(),
+            });
+        }
+
+        let new_affordances: Vec<_> = affordances(new).collect();
+        for old in affordances(old) {
+            if let Some(new) = new_affordances.iter().find(|new| new.name == old.name) {
+                let changes = self.affordance(place, old, new);
+                self.slots.push(changes);
+                continue;
+            }
+
+            let slot = self.push(Change::AffordanceRemoved {
+                place: place.to_owned(),
+                affordance: old.name.clone(),
+            });
+            self.removed.push((slot, place, old));
+        }
+
+        for new in affordances(new) {
+            if affordances(old).any(|old| old.name == new.name) {
+                continue;
+            }
+
+            let slot = self.push(Change::AffordanceAdded {
+                place: place.to_owned(),
+                affordance: new.name.clone(),
+            });
+            self.added.push((slot, place, new));
+        }
+    }
+
+    fn affordance(&self, place: &str, old: &Affordance, new: &Affordance) -> Vec<Change> {
+        let mut changes = vec![];
+
+        if old.description != new.description {
+            changes.push(Change::DescriptionChanged {
+                place: place.to_owned(),
+                affordance: Some(new.name.clone()),
+                from: old.description.clone(),
+                to: new.description.clone(),
+            });
+        }
+
+        if old.kind != new.kind {
+            changes.push(Change::KindChanged {
+                place: place.to_owned(),
+                affordance: new.name.clone(),
+                from: old.kind.clone(),
+                to: new.kind.clone(),
+            });
+        }
+
+        if old.attributes != new.attributes {
+            changes.push(Change::AttributesChanged {
+                place: place.to_owned(),
+                affordance: Some(new.name.clone()),
+                from: old.attributes.clone(),
+                to: new.attributes.clone(),
+            });
+        }
+
+        let (old_targets, new_targets) = self.connections(place, old, new, &mut changes);
+
+        let retargeted = old_targets.len().min(new_targets.len());
+        let mut old_targets = old_targets.into_iter();
+        let mut new_targets = new_targets.into_iter();
+
+        for (from, to) in old_targets
+            .by_ref()
+            .zip(new_targets.by_ref())
+            .take(retargeted)
+        {
+            changes.push(Change::ConnectionRetargeted {
+                place: place.to_owned(),
+                affordance: new.name.clone(),
+                from,
+                to,
+            });
+        }
+
+        for target in old_targets {
+            changes.push(Change::ConnectionRemoved {
+                place: place.to_owned(),
+                affordance: new.name.clone(),
+                target,
+            });
+        }
+
+        for target in new_targets {
+            changes.push(Change::ConnectionAdded {
+                place: place.to_owned(),
+                affordance: new.name.clone(),
+                target,
+            });
+        }
+
+        changes
+    }
+
+    /// Pairs up the connections of both versions of the affordance that lead to the same target,
+    /// and adds the changes to their kind and description.
+    ///
+    /// Returns the targets of the old and new connections that could not be paired up.
+    fn connections(
+        &self,
+        place: &str,
+        old: &Affordance,
+        new: &Affordance,
+        changes: &mut Vec<Change>,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut old_connections: Vec<_> = old
+            .connections
+            .iter()
+            .map(|connection| (target(connection, &self.places), connection))
+            .collect();
+        let mut new_connections: Vec<_> = new
+            .connections
+            .iter()
+            .map(|connection| (target(connection, &HashMap::new()), connection))
+            .collect();
+
+        // Unchanged connections first, so connections to the same target are paired correctly.
+        old_connections.retain(|(target, old)| {
+            let Some(i) = new_connections
+                .iter()
+                .position(|(t, new)| t == target && new == old)
+            else {
+                return true;
+            };
+
+            new_connections.remove(i);
+            false
+        });
+
+        old_connections.retain(|(target, old)| {
+            let Some(i) = new_connections.iter().position(|(t, _)| t == target) else {
+                return true;
+            };
+            let (target, new_connection) = new_connections.remove(i);
+
+            if old.kind != new_connection.kind {
+                changes.push(Change::ConnectionKindChanged {
+                    place: place.to_owned(),
+                    affordance: new.name.clone(),
+                    target: target.clone(),
+                    from: old.kind,
+                    to: new_connection.kind,
+                });
+            }
+
+            if old.description != new_connection.description {
+                changes.push(Change::ConnectionDescriptionChanged {
+                    place: place.to_owned(),
+                    affordance: new.name.clone(),
+                    target,
+                    from: old.description.clone(),
+                    to: new_connection.description.clone(),
+                });
+            }
+
+            false
+        });
+
+        (
+            old_connections.into_iter().map(|(t, _)| t).collect(),
+            new_connections.into_iter().map(|(t, _)| t).collect(),
+        )
+    }
+
+    /// Replaces affordances that were removed from one place and added to another by moves.
+    fn moves(&mut self) {
+        let mut used = vec![false; self.added.len()];
+
+        for &(slot, from, old) in &self.removed {
+            let Some(i) = (0..self.added.len()).find(|&i| {
+                let (_, to, new) = self.added[i];
+                !used[i] && new.name == old.name && to != from
+            }) else {
+                continue;
+            };
+
+            used[i] = true;
+            let (added_slot, to, new) = self.added[i];
+
+            let mut changes = vec![Change::AffordanceMoved {
+                affordance: new.name.clone(),
+                from: from.to_owned(),
+                to: to.to_owned(),
+            }];
+            changes.extend(self.affordance(to, old, new));
+
+            self.slots[slot] = changes;
+            self.slots[added_slot].clear();
+        }
+    }
+
+    fn flows(&mut self, old: &Breadboard, new: &Breadboard) {
+        for old in &old.flows {
+            let Some(new) = new.flow(&old.name) else {
+                self.push(Change::FlowRemoved {
+                    flow: old.name.clone(),
+                });
+                continue;
+            };
+
+            if old.description != new.description {
+                self.push(Change::FlowDescriptionChanged {
+                    flow: new.name.clone(),
+                    from: old.description.clone(),
+                    to: new.description.clone(),
+                });
+            }
+
+            let steps: Vec<_> = old
+                .steps
+                .iter()
+                .map(|step| Step {
+                    place: self
+                        .places
+                        .get(step.place.as_str())
+                        .map_or_else(|| step.place.clone(), |&place| place.to_owned()),
+                    affordance: step.affordance.clone(),
+                })
+                .collect();
+
+            if steps != new.steps {
+                self.push(Change::FlowStepsChanged {
+                    flow: new.name.clone(),
+                    from: old.steps.clone(),
+                    to: new.steps.clone(),
+                });
+            }
+        }
+
+        for new in &new.flows {
+            if old.flow(&new.name).is_none() {
+                self.push(Change::FlowAdded {
+                    flow: new.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn includes(place: &Place) -> impl Iterator<Item = &str> {
+    place.items.iter().filter_map(|item| match item {
+        Item::Reference(reference) => Some(reference.name.as_str()),
+        Item::Affordance(_) => None,
+    })
+}
+
+fn affordances(place: &Place) -> impl Iterator<Item = &Affordance> {
+    place.items.iter().filter_map(|item| match item {
+        Item::Affordance(affordance) => Some(affordance),
+        Item::Reference(_) => None,
+    })
+}
+
+/// Returns the target of a connection, with renamed places replaced by their new name.
+fn target(connection: &Connection, renames: &HashMap<&str, &str>) -> String {
+    let place = renames
+        .get(connection.target_place.as_str())
+        .copied()
+        .unwrap_or(&connection.target_place);
+
+    match &connection.target_affordance {
+        Some(affordance) => format!("{place}.{affordance}"),
+        None => place.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> Diff {
+        super::diff(
+            &bnb_parser::parse(old).unwrap(),
+            &bnb_parser::parse(new).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = indoc! {"
+            /// The start.
+            place Home
+              include Header
+              Sign In -> Login
+              Help -> Support
+              Logo
+
+            place Login
+              Username
+              Password
+              Submit -> Home
+              Cancel -> Home
+
+            place Support
+              Contact
+
+            component Header
+              Menu
+        "};
+
+        let new = indoc! {"
+            /// The landing page.
+            place Home
+              include Navigation
+              Sign In -> Auth
+              Help -> Home
+              Register -> Auth
+
+            place Auth
+              Username
+              Password
+              Submit -> Home
+              Cancel -> Home
+              Logo
+
+            place Registration
+              Email
+
+            component Navigation
+              Menu
+
+              position 0, 10
+        "};
+
+        let diff = diff(old, new);
+
+        assert_eq!(
+            diff.changed().into_iter().collect::<Vec<_>>(),
+            vec!["Auth", "Home", "Navigation", "Registration"]
+        );
+        assert_eq!(
+            diff.to_string(),
+            indoc! {"
+                ~ description of `Home`
+                ~ connection `Help` in `Home` retargeted from `Support` to `Home`
+                ~ affordance `Logo` moved from `Home` to `Auth`
+                + affordance `Register` in `Home`
+                ~ place `Login` renamed to `Auth`
+                - place `Support`
+                + place `Registration`
+                ~ component `Header` renamed to `Navigation`
+                ~ position of `Navigation`
+            "}
+        );
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let source = "place Home\n  Go -> Away\n\nplace Away\n";
+
+        assert!(diff(source, source).is_empty());
+        assert_eq!(diff(source, source).to_string(), "");
+    }
+
+    #[test]
+    fn test_connections() {
+        let old = "place A\n  Go -> B\n     -> C\n  Stay\nplace B\nplace C\n";
+        let new = "place A\n  Go -> C\n     -> B.Field\n  Stay -> A\nplace B\nplace C\n";
+
+        assert_eq!(
+            diff(old, new).to_string(),
+            indoc! {"
+                ~ connection `Go` in `A` retargeted from `B` to `B.Field`
+                + connection `Stay` -> `A` in `A`
+            "}
+        );
+    }
+
+    #[test]
+    fn test_connection_details() {
+        let old = indoc! {"
+            place A
+              Submit -> (success) B
+                     -> (failure) A
+              Open -> B
+            place B
+        "};
+        let new = indoc! {"
+            place A
+              Submit -> (failure) A
+                     -> (done) B
+              Open ~> B
+            place B
+        "};
+
+        let diff = diff(old, new);
+
+        assert_eq!(
+            diff.to_string(),
+            indoc! {"
+                ~ description of connection `Submit` -> `B` in `A`
+                ~ kind of connection `Open` -> `B` in `A`
+            "}
+        );
+        assert_eq!(
+            diff.changes[1],
+            Change::ConnectionKindChanged {
+                place: "A".to_owned(),
+                affordance: "Open".to_owned(),
+                target: "B".to_owned(),
+                from: ConnectionKind::Navigate,
+                to: ConnectionKind::Modal,
+            }
+        );
+    }
+
+    #[test]
+    fn test_kinds_and_attributes() {
+        let old = indoc! {"
+            place Home
+              @owner payments
+              Name [input required=true]
+              Go [button]
+        "};
+        let new = indoc! {"
+            place Home
+              @owner growth
+              Name [input]
+              Go [link]
+        "};
+
+        assert_eq!(
+            diff(old, new).to_string(),
+            indoc! {"
+                ~ attributes of `Home`
+                ~ attributes of `Name` in `Home`
+                ~ kind of `Go` in `Home`
+            "}
+        );
+    }
+
+    #[test]
+    fn test_flows() {
+        let old = indoc! {"
+            place Login
+              Username
+              Submit -> Login
+
+            flow Retry
+              Login: Submit
+
+            flow Leave
+              Login: Submit
+        "};
+        let new = indoc! {"
+            place Auth
+              Username
+              Submit -> Auth
+
+            /// Trying again.
+            flow Retry
+              Auth: Submit
+
+            flow Stay
+              Auth: Submit
+              Auth: Submit
+        "};
+
+        let diff = diff(old, new);
+
+        assert_eq!(
+            diff.to_string(),
+            indoc! {"
+                ~ place `Login` renamed to `Auth`
+                ~ description of flow `Retry`
+                - flow `Leave`
+                + flow `Stay`
+            "}
+        );
+        assert_eq!(diff.changed().into_iter().collect::<Vec<_>>(), ["Auth"]);
+
+        let diff = super::diff(
+            &bnb_parser::parse(new).unwrap(),
+            &bnb_parser::parse(&new.replace("Auth: Submit\n  Auth", "Auth")).unwrap(),
+        );
+        assert_eq!(diff.to_string(), "~ steps of flow `Stay`\n");
+    }
+
+    #[test]
+    fn test_relative_position() {
+        let old = indoc! {"
+            place Home
+              Sign In -> Login
+              position > Login, 0
+
+            place Login
+              Username
+        "};
+
+        // Renaming the place the position is relative to does not move the place.
+        let renamed = diff(old, &old.replace("Login", "Auth"));
+        assert_eq!(renamed.to_string(), "~ place `Login` renamed to `Auth`\n");
+
+        let moved = diff(old, &old.replace("position > Login", "position < Login"));
+        assert_eq!(moved.to_string(), "~ position of `Home`\n");
+    }
+
+    #[test]
+    fn test_unmatched_places() {
+        // Places without enough in common are not considered renames.
+        let diff = diff(
+            "place Login\n  A\n  B\n  C\n",
+            "place Auth\n  A\n  D\n  E\n",
+        );
+
+        assert_eq!(
+            diff.changes,
+            vec![
+                Change::PlaceRemoved {
+                    place: "Login".to_owned()
+                },
+                Change::PlaceAdded {
+                    place: "Auth".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let diff = diff(
+            "place Home\n  /// Go.\n  Go\n",
+            "place Home\n  /// Leave.\n  Go\n",
+        );
+
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap(),
+            serde_json::json!({
+                "changes": [{
+                    "change": "description_changed",
+                    "place": "Home",
+                    "affordance": "Go",
+                    "from": ["Go."],
+                    "to": ["Leave."],
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! Combining two versions of a breadboard into one, to show their changes in context.
+
+use std::collections::HashMap;
+
+use bnb_ast::{Breadboard, Item, Place};
+use serde::Serialize;
+
+use crate::{diff, Change, Diff};
+
+/// The status of a place, component, affordance or flow in a [`Overlay`] breadboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// It only exists in the new breadboard.
+    Added,
+
+    /// It only exists in the old breadboard.
+    Removed,
+
+    /// It exists in both breadboards, but something in it changed.
+    Changed,
+}
+
+/// Both versions of a breadboard, combined into one by [`overlay`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Overlay {
+    /// The new breadboard, with everything that was removed from the old breadboard added back.
+    pub breadboard: Breadboard,
+
+    /// The status of each place and component that did not stay the same, by name.
+    places: HashMap<String, Status>,
+
+    /// The status of each affordance that did not stay the same, by place and affordance name.
+    affordances: HashMap<(String, String), Status>,
+
+    /// The status of each flow that did not stay the same, by name.
+    flows: HashMap<String, Status>,
+}
+
+impl Overlay {
+    /// Returns the status of the place or component, or `None` if it stayed the same.
+    #[must_use]
+    pub fn place(&self, place: &str) -> Option<Status> {
+        self.places.get(place).copied()
+    }
+
+    /// Returns the status of the affordance in the given place or component, or `None` if it
+    /// stayed the same.
+    #[must_use]
+    pub fn affordance(&self, place: &str, affordance: &str) -> Option<Status> {
+        self.affordances
+            .get(&(place.to_owned(), affordance.to_owned()))
+            .copied()
+    }
+
+    /// Returns the status of the flow, or `None` if it stayed the same.
+    #[must_use]
+    pub fn flow(&self, flow: &str) -> Option<Status> {
+        self.flows.get(flow).copied()
+    }
+
+    /// Returns `true` if both breadboards are structurally the same.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.places.is_empty() && self.flows.is_empty()
+    }
+
+    fn set_place(&mut self, place: &str, status: Status) {
+        self.places.insert(place.to_owned(), status);
+    }
+
+    fn change_place(&mut self, place: &str) {
+        self.places
+            .entry(place.to_owned())
+            .or_insert(Status::Changed);
+    }
+
+    fn set_affordance(&mut self, place: &str, affordance: &str, status: Status) {
+        self.affordances
+            .insert((place.to_owned(), affordance.to_owned()), status);
+        self.change_place(place);
+    }
+
+    fn set_flow(&mut self, flow: &str, status: Status) {
+        self.flows.insert(flow.to_owned(), status);
+    }
+
+    fn change_flow(&mut self, flow: &str) {
+        self.flows.entry(flow.to_owned()).or_insert(Status::Changed);
+    }
+
+    fn change_affordance(&mut self, place: &str, affordance: &str) {
+        self.affordances
+            .entry((place.to_owned(), affordance.to_owned()))
+            .or_insert(Status::Changed);
+        self.change_place(place);
+    }
+}
+
+/// Combines two versions of a breadboard into one.
+///
+/// The overlay is the new breadboard, with the places, components and flows that were
+/// removed added back at the end, and the affordances that were removed added back at the end of
+/// their place. An affordance that moved is both removed from its old place, and added to its new
+/// one.
+///
+/// Places, affordances and flows that were added or removed have the matching [`Status`]. An
+/// affordance whose description, kind, attributes or connections changed is [`Status::Changed`],
+/// as is any place with a change in it, and any flow whose description or steps changed.
+#[must_use]
+pub fn overlay(old: &Breadboard, new: &Breadboard) -> Overlay {
+    let diff = diff(old, new);
+
+    let renames = renames(&diff);
+
+    let mut overlay = Overlay {
+        breadboard: new.clone(),
+        ..Overlay::default()
+    };
+
+    for change in &diff.changes {
+        match change {
+            Change::PlaceAdded { place } | Change::ComponentAdded { component: place } => {
+                overlay.set_place(place, Status::Added);
+            }
+            Change::PlaceRemoved { place } => {
+                if let Some(place) = old.place(place) {
+                    overlay.breadboard.places.push(place.clone());
+                }
+                overlay.set_place(place, Status::Removed);
+            }
+            Change::ComponentRemoved { component } => {
+                if let Some(component) = old.component(component) {
+                    overlay.breadboard.components.push(component.clone());
+                }
+                overlay.set_place(component, Status::Removed);
+            }
+            Change::AffordanceAdded { place, affordance } => {
+                overlay.set_affordance(place, affordance, Status::Added);
+            }
+            Change::AffordanceRemoved { place, affordance }
+            | Change::AffordanceMoved {
+                affordance,
+                from: place,
+                ..
+            } => {
+                let old_place = renames.get(place.as_str()).copied().unwrap_or(place);
+                restore_affordance(old, old_place, &mut overlay.breadboard, place, affordance);
+                overlay.set_affordance(place, affordance, Status::Removed);
+
+                if let Change::AffordanceMoved { to, .. } = change {
+                    overlay.set_affordance(to, affordance, Status::Added);
+                }
+            }
+            Change::ConnectionAdded {
+                place, affordance, ..
+            }
+            | Change::ConnectionRemoved {
+                place, affordance, ..
+            }
+            | Change::ConnectionRetargeted {
+                place, affordance, ..
+            }
+            | Change::ConnectionKindChanged {
+                place, affordance, ..
+            }
+            | Change::ConnectionDescriptionChanged {
+                place, affordance, ..
+            }
+            | Change::DescriptionChanged {
+                place,
+                affordance: Some(affordance),
+                ..
+            }
+            | Change::AttributesChanged {
+                place,
+                affordance: Some(affordance),
+                ..
+            }
+            | Change::KindChanged {
+                place, affordance, ..
+            } => overlay.change_affordance(place, affordance),
+            Change::PlaceRenamed { to: place, .. }
+            | Change::ComponentRenamed { to: place, .. }
+            | Change::IncludeAdded { place, .. }
+            | Change::IncludeRemoved { place, .. }
+            | Change::DescriptionChanged {
+                place,
+                affordance: None,
+                ..
+            }
+            | Change::AttributesChanged {
+                place,
+                affordance: None,
+                ..
+            }
+            | Change::PositionChanged { place, .. }
+            | Change::SketchChanged { place, .. } => overlay.change_place(place),
+            Change::FlowAdded { flow } => overlay.set_flow(flow, Status::Added),
+            Change::FlowRemoved { flow } => {
+                if let Some(flow) = old.flow(flow) {
+                    overlay.breadboard.flows.push(flow.clone());
+                }
+                overlay.set_flow(flow, Status::Removed);
+            }
+            Change::FlowDescriptionChanged { flow, .. } | Change::FlowStepsChanged { flow, .. } => {
+                overlay.change_flow(flow);
+            }
+        }
+    }
+
+    overlay
+}
+
+/// Returns the old names of renamed places and components, by their new names.
+fn renames(diff: &Diff) -> HashMap<&str, &str> {
+    diff.changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::PlaceRenamed { from, to } | Change::ComponentRenamed { from, to } => {
+                Some((to.as_str(), from.as_str()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Adds the affordance of the old place to the end of the place in the overlay.
+fn restore_affordance(
+    old: &Breadboard,
+    old_place: &str,
+    overlay: &mut Breadboard,
+    place: &str,
+    affordance: &str,
+) {
+    let Some(affordance) = container(old, old_place).and_then(|old| {
+        old.items.iter().find_map(|item| match item {
+            Item::Affordance(a) if a.name == affordance => Some(a),
+            _ => None,
+        })
+    }) else {
+        return;
+    };
+
+    let place = overlay
+        .places
+        .iter_mut()
+        .find(|p| p.name == place)
+        .or_else(|| {
+            overlay
+                .components
+                .iter_mut()
+                .map(|c| &mut **c)
+                .find(|c| c.name == place)
+        });
+
+    if let Some(place) = place {
+        place.items.push(Item::Affordance(affordance.clone()));
+    }
+}
+
+fn container<'a>(breadboard: &'a Breadboard, name: &str) -> Option<&'a Place> {
+    breadboard
+        .place(name)
+        .or_else(|| breadboard.component(name).map(|c| &**c))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn affordances(place: &Place) -> Vec<&str> {
+        place
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Affordance(a) => Some(a.name.as_str()),
+                Item::Reference(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_overlay() {
+        let old = bnb_parser::parse(indoc! {"
+            place Home
+              Sign In -> Login
+              Help -> Support
+              Logo
+
+            place Login
+              Username
+              Password
+              Submit -> Home
+
+            place Support
+              Contact
+        "})
+        .unwrap();
+
+        let new = bnb_parser::parse(indoc! {"
+            place Home
+              Sign In -> Auth
+              Help -> Home
+              Register
+
+            place Auth
+              Username
+              Password
+              Submit -> Home
+              Logo
+
+            place Registration
+              Email
+        "})
+        .unwrap();
+
+        let overlay = overlay(&old, &new);
+
+        let places: Vec<_> = overlay
+            .breadboard
+            .places
+            .iter()
+            .map(|place| (place.name.as_str(), affordances(place)))
+            .collect();
+
+        assert_eq!(
+            places,
+            vec![
+                ("Home", vec!["Sign In", "Help", "Register", "Logo"]),
+                ("Auth", vec!["Username", "Password", "Submit", "Logo"]),
+                ("Registration", vec!["Email"]),
+                ("Support", vec!["Contact"]),
+            ]
+        );
+
+        assert_eq!(overlay.place("Home"), Some(Status::Changed));
+        assert_eq!(overlay.place("Auth"), Some(Status::Changed));
+        assert_eq!(overlay.place("Registration"), Some(Status::Added));
+        assert_eq!(overlay.place("Support"), Some(Status::Removed));
+
+        assert_eq!(overlay.affordance("Home", "Sign In"), None);
+        assert_eq!(overlay.affordance("Home", "Help"), Some(Status::Changed));
+        assert_eq!(overlay.affordance("Home", "Register"), Some(Status::Added));
+        assert_eq!(overlay.affordance("Home", "Logo"), Some(Status::Removed));
+        assert_eq!(overlay.affordance("Auth", "Logo"), Some(Status::Added));
+        assert_eq!(overlay.affordance("Auth", "Username"), None);
+    }
+
+    #[test]
+    fn test_overlay_renamed() {
+        let old = bnb_parser::parse("place Login\n  Username\n  Password\n  Cancel\n").unwrap();
+        let new = bnb_parser::parse("place Auth\n  Username\n  Password\n").unwrap();
+
+        let overlay = overlay(&old, &new);
+
+        assert_eq!(
+            affordances(&overlay.breadboard.places[0]),
+            vec!["Username", "Password", "Cancel"]
+        );
+        assert_eq!(overlay.affordance("Auth", "Cancel"), Some(Status::Removed));
+        assert_eq!(overlay.place("Auth"), Some(Status::Changed));
+    }
+
+    #[test]
+    fn test_overlay_flows() {
+        let old = bnb_parser::parse(indoc! {"
+            place Home
+              Go -> Home
+
+            flow Stay
+              Home: Go
+
+            flow Leave
+              Home: Go
+        "})
+        .unwrap();
+        let new = bnb_parser::parse(indoc! {"
+            place Home
+              Go -> Home
+
+            /// Stay at home.
+            flow Stay
+              Home: Go
+
+            flow Return
+              Home: Go
+        "})
+        .unwrap();
+
+        let overlay = overlay(&old, &new);
+
+        let flows: Vec<_> = overlay
+            .breadboard
+            .flows
+            .iter()
+            .map(|flow| flow.name.as_str())
+            .collect();
+        assert_eq!(flows, ["Stay", "Return", "Leave"]);
+
+        assert_eq!(overlay.flow("Stay"), Some(Status::Changed));
+        assert_eq!(overlay.flow("Return"), Some(Status::Added));
+        assert_eq!(overlay.flow("Leave"), Some(Status::Removed));
+        assert_eq!(overlay.place("Home"), None);
+        assert!(!overlay.is_empty());
+    }
+
+    #[test]
+    fn test_overlay_unchanged() {
+        let source = "place Home\n  Go -> Away\n\nplace Away\n";
+        let breadboard = bnb_parser::parse(source).unwrap();
+
+        let overlay = overlay(&breadboard, &breadboard);
+
+        assert!(overlay.is_empty());
+        assert_eq!(overlay.breadboard, breadboard);
+    }
+}
@@ -0,0 +1,378 @@
+//! Paths from an entry place that together follow every connection, e.g. for test plans.
+
+use crate::{Edge, Error, Graph};
+
+/// A set of paths from an entry place, which together follow every connection reachable from it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cover<'a> {
+    /// The paths, each starting at the entry place.
+    pub paths: Vec<Vec<&'a Edge>>,
+
+    /// The connections that cannot be reached from the entry place, and are not part of any path,
+    /// in the order they are defined in.
+    pub unreachable: Vec<&'a Edge>,
+}
+
+/// A step of the closed walk through the graph, from which the paths are cut.
+#[derive(Debug, Clone, Copy)]
+enum Walk {
+    /// Follow the edge.
+    Edge(usize),
+
+    /// Go back to the entry place, and start a new path.
+    Restart,
+}
+
+impl Graph {
+    /// Returns a minimal set of paths from the entry place, which together follow every connection
+    /// reachable from it at least once.
+    ///
+    /// This is the [route inspection problem][route], with the option to start over at the entry
+    /// place from any place: first the number of paths is minimized, then the number of connections
+    /// that have to be followed more than once. A graph in which every place can reach the entry
+    /// place is covered by a single path, which ends at the entry place.
+    ///
+    /// [route]: https://en.wikipedia.org/wiki/Chinese_postman_problem
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry place does not exist.
+    pub fn cover(&self, entry: &str) -> Result<Cover<'_>, Error> {
+        let start = self.node(entry)?;
+        let nodes = self.places.len();
+
+        let mut reachable = vec![false; nodes];
+        for node in self.visit(start) {
+            reachable[node] = true;
+        }
+
+        let (required, unreachable): (Vec<usize>, Vec<usize>) =
+            (0..self.edges.len()).partition(|&edge| reachable[self.ends[edge].0]);
+
+        // Following every required edge once leaves some places with more incoming than outgoing
+        // edges, which need additional outgoing ones, and the other way around. The cheapest way
+        // to balance them is a minimum cost flow from the former to the latter.
+        let mut balance = vec![0_i64; nodes];
+        for &edge in &required {
+            let (from, to) = self.ends[edge];
+            balance[from] -= 1;
+            balance[to] += 1;
+        }
+
+        let unlimited = i64::try_from(required.len()).unwrap_or(i64::MAX);
+        let restart_cost = unlimited
+            .saturating_mul(i64::try_from(nodes).unwrap_or(i64::MAX))
+            .saturating_add(1);
+        let (source, sink) = (nodes, nodes + 1);
+        let mut flow = Flow::new(nodes + 2);
+
+        let mut repeats = vec![];
+        for &edge in &required {
+            let (from, to) = self.ends[edge];
+            if !repeats
+                .iter()
+                .any(|&(other, _)| self.ends[other] == (from, to))
+            {
+                repeats.push((edge, flow.add(from, to, unlimited, 1)));
+            }
+        }
+
+        // Starting over is always possible, but costs more than any number of repeated edges.
+        let restarts: Vec<_> = (0..nodes)
+            .filter(|&node| reachable[node] && node != start)
+            .map(|node| (node, flow.add(node, start, unlimited, restart_cost)))
+            .collect();
+
+        for (node, &balance) in balance.iter().enumerate() {
+            match balance {
+                1.. => {
+                    flow.add(source, node, balance, 0);
+                }
+                ..=-1 => {
+                    flow.add(node, sink, -balance, 0);
+                }
+                0 => {}
+            }
+        }
+
+        flow.run(source, sink);
+
+        // Every place is balanced now, so a closed walk from the entry place follows all edges.
+        let mut outgoing: Vec<Vec<(Walk, usize)>> = vec![vec![]; nodes];
+        for &edge in &required {
+            let (from, to) = self.ends[edge];
+            outgoing[from].push((Walk::Edge(edge), to));
+        }
+        for (edge, arc) in repeats {
+            let (from, to) = self.ends[edge];
+            for _ in 0..flow.flow(arc) {
+                outgoing[from].push((Walk::Edge(edge), to));
+            }
+        }
+        for (node, arc) in restarts {
+            for _ in 0..flow.flow(arc) {
+                outgoing[node].push((Walk::Restart, start));
+            }
+        }
+
+        let mut paths = vec![];
+        let mut path = vec![];
+        for walk in eulerian_circuit(&outgoing, start) {
+            match walk {
+                Walk::Edge(edge) => path.push(&self.edges[edge]),
+                Walk::Restart => paths.push(std::mem::take(&mut path)),
+            }
+        }
+        if !path.is_empty() {
+            paths.push(path);
+        }
+
+        Ok(Cover {
+            paths,
+            unreachable: unreachable
+                .into_iter()
+                .map(|edge| &self.edges[edge])
+                .collect(),
+        })
+    }
+}
+
+/// Returns a closed walk from the start node that takes every outgoing step exactly once, using
+/// Hierholzer's algorithm.
+///
+/// Every node must have as many incoming as outgoing steps, and be reachable from the start node.
+fn eulerian_circuit(outgoing: &[Vec<(Walk, usize)>], start: usize) -> Vec<Walk> {
+    let mut next = vec![0; outgoing.len()];
+    let mut stack = vec![(start, None)];
+    let mut circuit = vec![];
+
+    while let Some(&(node, _)) = stack.last() {
+        if let Some(&(walk, to)) = outgoing[node].get(next[node]) {
+            next[node] += 1;
+            stack.push((to, Some(walk)));
+        } else if let Some((_, Some(walk))) = stack.pop() {
+            circuit.push(walk);
+        }
+    }
+
+    circuit.reverse();
+    circuit
+}
+
+/// A flow network, solved with the successive shortest paths algorithm.
+///
+/// Every arc is stored next to its residual arc, at the index with the lowest bit flipped.
+struct Flow {
+    targets: Vec<usize>,
+    capacities: Vec<i64>,
+    costs: Vec<i64>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl Flow {
+    fn new(nodes: usize) -> Self {
+        Self {
+            targets: vec![],
+            capacities: vec![],
+            costs: vec![],
+            outgoing: vec![vec![]; nodes],
+        }
+    }
+
+    /// Adds an arc, and returns its index.
+    fn add(&mut self, from: usize, to: usize, capacity: i64, cost: i64) -> usize {
+        let arc = self.targets.len();
+
+        self.targets.extend([to, from]);
+        self.capacities.extend([capacity, 0]);
+        self.costs.extend([cost, -cost]);
+        self.outgoing[from].push(arc);
+        self.outgoing[to].push(arc + 1);
+
+        arc
+    }
+
+    /// Returns the flow through the arc.
+    fn flow(&self, arc: usize) -> i64 {
+        self.capacities[arc ^ 1]
+    }
+
+    /// Sends as much flow as possible from the source to the sink, at the minimum cost.
+    fn run(&mut self, source: usize, sink: usize) {
+        loop {
+            // Bellman-Ford, since residual arcs have negative costs.
+            let mut distances = vec![i64::MAX; self.outgoing.len()];
+            let mut via = vec![None; self.outgoing.len()];
+            distances[source] = 0;
+
+            let mut changed = true;
+            while changed {
+                changed = false;
+
+                for node in 0..self.outgoing.len() {
+                    if distances[node] == i64::MAX {
+                        continue;
+                    }
+
+                    for &arc in &self.outgoing[node] {
+                        let to = self.targets[arc];
+                        let distance = distances[node] + self.costs[arc];
+
+                        if self.capacities[arc] > 0 && distance < distances[to] {
+                            distances[to] = distance;
+                            via[to] = Some(arc);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            if via[sink].is_none() {
+                return;
+            }
+
+            let mut path = vec![];
+            let mut node = sink;
+            while let Some(arc) = via[node] {
+                path.push(arc);
+                node = self.targets[arc ^ 1];
+            }
+
+            let amount = path
+                .iter()
+                .map(|&arc| self.capacities[arc])
+                .min()
+                .unwrap_or_default();
+            for arc in path {
+                self.capacities[arc] -= amount;
+                self.capacities[arc ^ 1] += amount;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn cover(input: &str, entry: &str) -> Vec<Vec<String>> {
+        let breadboard = bnb_parser::parse(input).unwrap();
+        let graph = Graph::new(&breadboard);
+
+        graph
+            .cover(entry)
+            .unwrap()
+            .paths
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(|edge| format!("{} -({})-> {}", edge.from, edge.affordance, edge.to))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cover_cycle() {
+        let paths = cover(
+            "place A\n  Next -> B\n\nplace B\n  Next -> C\n  Back -> A\n\nplace C\n  Next -> A\n",
+            "A",
+        );
+
+        assert_eq!(
+            paths,
+            vec![vec![
+                "A -(Next)-> B",
+                "B -(Next)-> C",
+                "C -(Next)-> A",
+                "A -(Next)-> B",
+                "B -(Back)-> A",
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_cover_dead_ends() {
+        let input = indoc! {"
+            place Home
+              Sign In -> Login
+              Help -> Support
+              Nowhere -> Missing
+
+            place Login
+              Submit -> (success) Home
+                     -> (failure) Login
+              Forgot -> Reset
+
+            place Reset
+              include Footer
+
+            place Support
+
+            place Orphan
+              Go -> Home
+
+            component Footer
+              Back -> Login
+        "};
+
+        let paths = cover(input, "Home");
+        assert_eq!(
+            paths,
+            vec![vec![
+                "Home -(Sign In)-> Login",
+                "Login -(Submit)-> Login",
+                "Login -(Forgot)-> Reset",
+                "Reset -(Back)-> Login",
+                "Login -(Submit)-> Home",
+                "Home -(Help)-> Support",
+            ]]
+        );
+
+        let breadboard = bnb_parser::parse(input).unwrap();
+        let graph = Graph::new(&breadboard);
+        let cover = graph.cover("Home").unwrap();
+
+        let descriptions: Vec<_> = cover.paths[0]
+            .iter()
+            .map(|edge| edge.description.as_deref())
+            .collect();
+        assert_eq!(
+            descriptions,
+            [None, Some("failure"), None, None, Some("success"), None]
+        );
+
+        let unreachable: Vec<_> = cover.unreachable.iter().map(|e| &e.affordance).collect();
+        assert_eq!(unreachable, ["Go"]);
+    }
+
+    #[test]
+    fn test_cover_multiple_paths() {
+        let paths = cover(
+            "place Home\n  Shop -> Cart\n  Help -> Support\n\nplace Cart\n  Pay -> Receipt\n\n\
+             place Support\n\nplace Receipt\n",
+            "Home",
+        );
+
+        assert_eq!(
+            paths,
+            vec![
+                vec!["Home -(Shop)-> Cart", "Cart -(Pay)-> Receipt"],
+                vec!["Home -(Help)-> Support"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cover_without_connections() {
+        assert!(cover("place Home\n\nplace Away\n  Back -> Home\n", "Home").is_empty());
+
+        let breadboard = bnb_parser::parse("place Home\n").unwrap();
+        assert_eq!(
+            Graph::new(&breadboard).cover("Away"),
+            Err(Error::UnknownPlace("Away".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! # Bread'n'Butter Graph
+//!
+//! **A buttery smooth graph experience.**
+//!
+//! The `bnb_graph` crate treats a [`Breadboard`] as a directed graph, with its places as nodes and
+//! the connections of their affordances as edges, and answers questions about its navigation
+//! structure:
+//!
+//! - Which places can be reached from an entry place, and which cannot.
+//! - Which places are dead ends, without any outgoing connections.
+//! - What the shortest path between two places is.
+//! - Which places form cycles, as strongly connected components.
+//! - How many places lead to, and can be reached from, each place.
+//! - Which paths from an entry place follow every connection, e.g. to plan tests.
+//!
+//! Affordances included from components are part of every place that includes them. Components
+//! are not nodes of the graph themselves, and connections to unknown places are ignored (these are
+//! reported by [validation](bnb_ast::validate)).
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_graph::Graph;
+//!
+//! let breadboard = bnb_parser::parse(
+//!     "place Home\n  Sign In -> Login\n\nplace Login\n  Submit -> Home\n\nplace Help\n",
+//! )
+//! .unwrap();
+//!
+//! let graph = Graph::new(&breadboard);
+//!
+//! assert_eq!(graph.unreachable("Home").unwrap(), vec!["Help"]);
+//! assert_eq!(graph.dead_ends(), vec!["Help"]);
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+mod cover;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bnb_ast::{Breadboard, ConnectionKind};
+
+pub use cover::Cover;
+
+/// A directed graph of the places of a breadboard, and the connections between them.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    /// The names of the places, in the order they are defined in.
+    places: Vec<String>,
+
+    /// The node of each place, by name.
+    nodes: HashMap<String, usize>,
+
+    /// The edges of the graph, in the order they are defined in.
+    edges: Vec<Edge>,
+
+    /// The source and target node of each edge.
+    ends: Vec<(usize, usize)>,
+
+    /// The outgoing edges of each node.
+    outgoing: Vec<Vec<usize>>,
+
+    /// The incoming edges of each node.
+    incoming: Vec<Vec<usize>>,
+}
+
+/// A connection from an affordance in one place to another place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    /// The place the connection starts from.
+    pub from: String,
+
+    /// The affordance the connection belongs to.
+    pub affordance: String,
+
+    /// The place the connection leads to.
+    pub to: String,
+
+    /// The kind of the connection.
+    pub kind: ConnectionKind,
+
+    /// The description of the connection, e.g. the outcome that leads to the place.
+    pub description: Option<String>,
+}
+
+/// The number of distinct places connected to a place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Degree {
+    /// The number of places with a connection to this place.
+    pub fan_in: usize,
+
+    /// The number of places this place has a connection to.
+    pub fan_out: usize,
+}
+
+impl Graph {
+    /// Builds the graph of the given breadboard.
+    ///
+    /// If a place is defined more than once, all its definitions are merged into a single node.
+    #[must_use]
+    pub fn new(breadboard: &Breadboard) -> Self {
+        let mut graph = Self::default();
+
+        for place in &breadboard.places {
+            if !graph.nodes.contains_key(&place.name) {
+                graph.nodes.insert(place.name.clone(), graph.places.len());
+                graph.places.push(place.name.clone());
+                graph.outgoing.push(vec![]);
+                graph.incoming.push(vec![]);
+            }
+        }
+
+        for place in &breadboard.places {
+            let from = graph.nodes[&place.name];
+
+            for affordance in breadboard.affordances(place) {
+                for connection in affordance.connections {
+                    let Some(&to) = graph.nodes.get(&connection.target_place) else {
+                        continue;
+                    };
+
+                    let edge = graph.edges.len();
+                    graph.outgoing[from].push(edge);
+                    graph.incoming[to].push(edge);
+                    graph.ends.push((from, to));
+                    graph.edges.push(Edge {
+                        from: place.name.clone(),
+                        affordance: affordance.name.clone(),
+                        to: connection.target_place,
+                        kind: connection.kind,
+                        description: connection.description,
+                    });
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Returns the names of all places, in the order they are defined in.
+    pub fn places(&self) -> impl Iterator<Item = &str> {
+        self.places.iter().map(String::as_str)
+    }
+
+    /// Returns all edges, in the order they are defined in.
+    #[must_use]
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Returns the places that can be reached from the entry place, including the entry place
+    /// itself, ordered by their distance from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry place does not exist.
+    pub fn reachable(&self, entry: &str) -> Result<Vec<&str>, Error> {
+        let visited = self.visit(self.node(entry)?);
+
+        Ok(visited.into_iter().map(|node| self.name(node)).collect())
+    }
+
+    /// Returns the places that cannot be reached from the entry place, in the order they are
+    /// defined in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry place does not exist.
+    pub fn unreachable(&self, entry: &str) -> Result<Vec<&str>, Error> {
+        let visited: HashSet<_> = self.visit(self.node(entry)?).into_iter().collect();
+
+        Ok((0..self.places.len())
+            .filter(|node| !visited.contains(node))
+            .map(|node| self.name(node))
+            .collect())
+    }
+
+    /// Returns the places without any outgoing connections, in the order they are defined in.
+    #[must_use]
+    pub fn dead_ends(&self) -> Vec<&str> {
+        (0..self.places.len())
+            .filter(|&node| self.outgoing[node].is_empty())
+            .map(|node| self.name(node))
+            .collect()
+    }
+
+    /// Returns the edges of a shortest path between two places, or `None` if there is no path.
+    ///
+    /// The path between a place and itself is empty. If there are multiple shortest paths, the
+    /// one following the earliest defined connections is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either of the places does not exist.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Vec<&Edge>>, Error> {
+        let from = self.node(from)?;
+        let to = self.node(to)?;
+
+        // The edge each node was first reached through.
+        let mut via: Vec<Option<usize>> = vec![None; self.places.len()];
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                break;
+            }
+
+            for &edge in &self.outgoing[node] {
+                let (_, next) = self.ends[edge];
+                if next != from && via[next].is_none() {
+                    via[next] = Some(edge);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if from != to && via[to].is_none() {
+            return Ok(None);
+        }
+
+        let mut path = vec![];
+        let mut node = to;
+        while let Some(edge) = via[node] {
+            path.push(&self.edges[edge]);
+            node = self.ends[edge].0;
+        }
+        path.reverse();
+
+        Ok(Some(path))
+    }
+
+    /// Returns the strongly connected components of the graph.
+    ///
+    /// Each component is a set of places that can all reach each other. Places that are not part
+    /// of any cycle form a component on their own. The places of each component, and the
+    /// components themselves, are ordered by the definition order of their (first) place.
+    #[must_use]
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&str>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            next: 0,
+            indices: vec![None; self.places.len()],
+            lowlinks: vec![0; self.places.len()],
+            on_stack: vec![false; self.places.len()],
+            stack: vec![],
+            components: vec![],
+        };
+
+        for node in 0..self.places.len() {
+            if tarjan.indices[node].is_none() {
+                tarjan.visit(node);
+            }
+        }
+
+        let mut components = tarjan.components;
+        for component in &mut components {
+            component.sort_unstable();
+        }
+        components.sort_unstable_by_key(|component| component[0]);
+
+        components
+            .into_iter()
+            .map(|component| component.into_iter().map(|node| self.name(node)).collect())
+            .collect()
+    }
+
+    /// Returns the fan-in and fan-out of the given place.
+    ///
+    /// Multiple connections between the same two places are counted once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the place does not exist.
+    pub fn degree(&self, place: &str) -> Result<Degree, Error> {
+        let node = self.node(place)?;
+
+        let sources: HashSet<_> = self.incoming[node]
+            .iter()
+            .map(|&edge| self.ends[edge].0)
+            .collect();
+        let targets: HashSet<_> = self.outgoing[node]
+            .iter()
+            .map(|&edge| self.ends[edge].1)
+            .collect();
+
+        Ok(Degree {
+            fan_in: sources.len(),
+            fan_out: targets.len(),
+        })
+    }
+
+    fn node(&self, place: &str) -> Result<usize, Error> {
+        self.nodes
+            .get(place)
+            .copied()
+            .ok_or_else(|| Error::UnknownPlace(place.to_owned()))
+    }
+
+    fn name(&self, node: usize) -> &str {
+        &self.places[node]
+    }
+
+    /// Returns the nodes reachable from the given node, in breadth-first order.
+    fn visit(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut i = 0;
+
+        while let Some(&node) = visited.get(i) {
+            for &edge in &self.outgoing[node] {
+                let (_, next) = self.ends[edge];
+                if seen.insert(next) {
+                    visited.push(next);
+                }
+            }
+
+            i += 1;
+        }
+
+        visited
+    }
+}
+
+/// The state of Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    graph: &'a Graph,
+    next: usize,
+    indices: Vec<Option<usize>>,
+    lowlinks: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.next);
+        self.lowlinks[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let graph = self.graph;
+        for &edge in &graph.outgoing[node] {
+            let (_, next) = graph.ends[edge];
+
+            match self.indices[next] {
+                None => {
+                    self.visit(next);
+                    self.lowlinks[node] = self.lowlinks[node].min(self.lowlinks[next]);
+                }
+                Some(index) if self.on_stack[next] => {
+                    self.lowlinks[node] = self.lowlinks[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.lowlinks[node]) != self.indices[node] {
+            return;
+        }
+
+        let mut component = vec![];
+        while let Some(member) = self.stack.pop() {
+            self.on_stack[member] = false;
+            component.push(member);
+
+            if member == node {
+                break;
+            }
+        }
+
+        self.components.push(component);
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The place does not exist in the breadboard.
+    #[error("unknown place `{0}`")]
+    UnknownPlace(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn graph() -> Graph {
+        let breadboard = bnb_parser::parse(indoc! {"
+            place Home
+              Sign In -> Login
+              Help -> Support
+              Nowhere -> Missing
+
+            place Login
+              Submit -> Home
+              Cancel -> Home
+              Forgot -> Reset
+
+            place Reset
+              include Footer
+
+            place Support
+
+            place Orphan
+              Go -> Home
+
+            component Footer
+              Back -> Login
+        "})
+        .unwrap();
+
+        Graph::new(&breadboard)
+    }
+
+    #[test]
+    fn test_reachable() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.reachable("Home").unwrap(),
+            vec!["Home", "Login", "Support", "Reset"]
+        );
+        assert_eq!(graph.reachable("Support").unwrap(), vec!["Support"]);
+        assert_eq!(graph.unreachable("Home").unwrap(), vec!["Orphan"]);
+        assert_eq!(
+            graph.unreachable("Support").unwrap(),
+            vec!["Home", "Login", "Reset", "Orphan"]
+        );
+        assert_eq!(
+            graph.reachable("Missing"),
+            Err(Error::UnknownPlace("Missing".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_dead_ends() {
+        assert_eq!(graph().dead_ends(), vec!["Support"]);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let graph = graph();
+
+        let path = |from, to| {
+            graph.shortest_path(from, to).unwrap().map(|path| {
+                path.into_iter()
+                    .map(|edge| format!("{} -({})-> {}", edge.from, edge.affordance, edge.to))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            path("Orphan", "Reset"),
+            Some(vec![
+                "Orphan -(Go)-> Home".to_owned(),
+                "Home -(Sign In)-> Login".to_owned(),
+                "Login -(Forgot)-> Reset".to_owned(),
+            ])
+        );
+        assert_eq!(
+            path("Reset", "Home"),
+            Some(vec![
+                "Reset -(Back)-> Login".to_owned(),
+                "Login -(Submit)-> Home".to_owned(),
+            ])
+        );
+        assert_eq!(path("Home", "Home"), Some(vec![]));
+        assert_eq!(path("Home", "Orphan"), None);
+        assert_eq!(path("Support", "Home"), None);
+        assert!(graph.shortest_path("Home", "Missing").is_err());
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        assert_eq!(
+            graph().strongly_connected_components(),
+            vec![
+                vec!["Home", "Login", "Reset"],
+                vec!["Support"],
+                vec!["Orphan"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_degree() {
+        let graph = graph();
+
+        let degree = |place| graph.degree(place).unwrap();
+
+        assert_eq!(
+            degree("Home"),
+            Degree {
+                fan_in: 2,
+                fan_out: 2
+            }
+        );
+        assert_eq!(
+            degree("Login"),
+            Degree {
+                fan_in: 2,
+                fan_out: 2
+            }
+        );
+        assert_eq!(
+            degree("Orphan"),
+            Degree {
+                fan_in: 0,
+                fan_out: 1
+            }
+        );
+        assert_eq!(graph.edges().len(), 7);
+    }
+
+    #[test]
+    fn test_duplicate_places() {
+        let breadboard =
+            bnb_parser::parse("place Home\n  Go -> Away\nplace Away\nplace Home\n  Back -> Away")
+                .unwrap();
+        let graph = Graph::new(&breadboard);
+
+        assert_eq!(graph.places().collect::<Vec<_>>(), vec!["Home", "Away"]);
+        assert_eq!(graph.dead_ends(), vec!["Away"]);
+        assert_eq!(graph.degree("Away").unwrap().fan_in, 1);
+    }
+}
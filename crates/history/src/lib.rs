@@ -0,0 +1,399 @@
+//! # Bread'n'Butter History
+//!
+//! **A buttery smooth history experience.**
+//!
+//! The `bnb_history` crate reads the earlier revisions of a breadboard file from its git
+//! repository, and turns them into a timeline of structural changes, with one [`Entry`] per
+//! commit. The changes of each commit are computed with [`bnb_diff`].
+//!
+//! Revisions are read through a [`Repository`]. The [`Git`] repository runs the local `git`
+//! executable, and never accesses the network.
+//!
+//! Each revision is parsed on its own with [`bnb_parser::parse`], which means that the imports of
+//! a revision are not resolved.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use bnb_history::{revisions, timeline, Git};
+//!
+//! let revisions = revisions("app.bnb", &Git).unwrap();
+//!
+//! for entry in timeline(&revisions) {
+//!     println!("{} {}", entry.commit.id, entry.commit.summary);
+//!     print!("{}", entry.diff);
+//! }
+//! ```
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use bnb_ast::Breadboard;
+use bnb_diff::Diff;
+use serde::Serialize;
+
+/// Access to the revisions of a file, used by [`revisions`].
+pub trait Repository {
+    /// Returns the commits that changed the file at the given path, newest first, following the
+    /// file across renames.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history of the file cannot be read.
+    fn log(&self, path: &Path) -> io::Result<Vec<Commit>>;
+
+    /// Returns the contents of the file as of the given commit.
+    ///
+    /// The `path` is the [`Commit::path`] of the file in that commit, and `file` is the path
+    /// originally passed to [`log`](Self::log).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist in the commit, or cannot be read.
+    fn show(&self, commit: &str, path: &Path, file: &Path) -> io::Result<String>;
+}
+
+/// The [`Repository`] of the local `git` executable.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Git;
+
+impl Repository for Git {
+    fn log(&self, path: &Path) -> io::Result<Vec<Commit>> {
+        let (dir, file) = split(path)?;
+
+        // Commits that delete the file have no revision to show. With `--follow`, the names of
+        // the file are listed relative to the root of the repository. With `-z`, they are not
+        // quoted, so they can be passed to `git show` as is.
+        let output = run(Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "log",
+                "--follow",
+                "--diff-filter=ACMRT",
+                "--name-only",
+                "-z",
+                "--format=%x1e%H%x1f%s",
+                "--",
+            ])
+            .arg(file))?;
+
+        Ok(parse_log(&output))
+    }
+
+    fn show(&self, commit: &str, path: &Path, file: &Path) -> io::Result<String> {
+        let (dir, _) = split(file)?;
+
+        run(Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .arg("show")
+            .arg(format!("{commit}:{}", path.to_string_lossy())))
+    }
+}
+
+/// Splits the path into the directory to run `git` in, and the name of the file.
+fn split(path: &Path) -> io::Result<(&Path, &std::ffi::OsStr)> {
+    let file = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file"))?;
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    Ok((dir, file))
+}
+
+/// Runs the command, and returns its standard output.
+fn run(command: &mut Command) -> io::Result<String> {
+    let output = command.output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+
+    String::from_utf8(output.stdout)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Parses the output of `git log --name-only -z --format=%x1e%H%x1f%s`.
+///
+/// Each record is the commit, followed by a NUL, and the names of the file, each preceded by a
+/// newline and followed by a NUL.
+fn parse_log(output: &str) -> Vec<Commit> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.split('\0');
+            let (id, summary) = fields.next()?.split_once('\x1f')?;
+            let path = fields
+                .map(|field| field.strip_prefix('\n').unwrap_or(field))
+                .find(|path| !path.is_empty())?;
+
+            Some(Commit {
+                id: id.to_owned(),
+                summary: summary.to_owned(),
+                path: PathBuf::from(path),
+            })
+        })
+        .collect()
+}
+
+/// A commit that changed a breadboard file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Commit {
+    /// The full hash of the commit.
+    pub id: String,
+
+    /// The first line of the commit message.
+    pub summary: String,
+
+    /// The path of the file in the commit, relative to the root of the repository.
+    ///
+    /// This differs from the current path of the file, if the file was renamed since.
+    pub path: PathBuf,
+}
+
+/// The breadboard of a file, as of a commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub commit: Commit,
+
+    /// The breadboard, or the error if the file did not parse in this commit.
+    pub breadboard: Result<Breadboard, bnb_parser::Error>,
+}
+
+/// The structural changes a commit made to a breadboard.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Entry {
+    pub commit: Commit,
+
+    /// The changes, compared to the previous revision that parsed.
+    pub diff: Diff,
+}
+
+/// Reads all revisions of the breadboard file at the given path, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the history of the file, or any of its revisions, cannot be read. A
+/// revision that does not parse is not an error, see [`Revision::breadboard`].
+pub fn revisions(
+    path: impl AsRef<Path>,
+    repository: &dyn Repository,
+) -> Result<Vec<Revision>, Error> {
+    let path = path.as_ref();
+
+    let mut commits = repository.log(path).map_err(|source| Error::Log {
+        path: path.to_owned(),
+        source,
+    })?;
+    commits.reverse();
+
+    commits
+        .into_iter()
+        .map(|commit| {
+            let input = repository
+                .show(&commit.id, &commit.path, path)
+                .map_err(|source| Error::Show {
+                    commit: commit.id.clone(),
+                    path: commit.path.clone(),
+                    source,
+                })?;
+
+            Ok(Revision {
+                breadboard: bnb_parser::parse(&input),
+                commit,
+            })
+        })
+        .collect()
+}
+
+/// Returns the timeline of structural changes of the given revisions, oldest first.
+///
+/// Each revision is compared to the previous revision that parsed, and the first one to an empty
+/// breadboard. Revisions that do not parse are left out of the timeline.
+#[must_use]
+pub fn timeline(revisions: &[Revision]) -> Vec<Entry> {
+    let mut previous = &Breadboard::default();
+    let mut entries = vec![];
+
+    for revision in revisions {
+        let Ok(breadboard) = &revision.breadboard else {
+            continue;
+        };
+
+        entries.push(Entry {
+            commit: revision.commit.clone(),
+            diff: bnb_diff::diff(previous, breadboard),
+        });
+        previous = breadboard;
+    }
+
+    entries
+}
+
+/// An error that occurred while reading the revisions of a breadboard with [`revisions`].
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("failed to read the history of {}: {source}", path.display())]
+    Log { path: PathBuf, source: io::Error },
+
+    #[error("failed to read {} at {commit}: {source}", path.display())]
+    Show {
+        commit: String,
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    /// A repository with a single file, with its path and contents by commit, oldest first.
+    struct Memory(Vec<(&'static str, &'static str, &'static str)>);
+
+    impl Repository for Memory {
+        fn log(&self, _: &Path) -> io::Result<Vec<Commit>> {
+            Ok(self
+                .0
+                .iter()
+                .rev()
+                .map(|(id, path, _)| Commit {
+                    id: (*id).to_owned(),
+                    summary: format!("Commit {id}"),
+                    path: PathBuf::from(path),
+                })
+                .collect())
+        }
+
+        fn show(&self, commit: &str, path: &Path, _: &Path) -> io::Result<String> {
+            let contents: HashMap<_, _> = self
+                .0
+                .iter()
+                .map(|(id, path, contents)| ((*id, Path::new(path)), *contents))
+                .collect();
+
+            contents
+                .get(&(commit, path))
+                .map(|contents| (*contents).to_owned())
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        // The file was renamed from `old.bnb` in commit `c`.
+        let repository = Memory(vec![
+            ("a", "old.bnb", "place Home\n"),
+            (
+                "b",
+                "old.bnb",
+                "place Home\n  Sign In -> Login\nplace Login\n",
+            ),
+            ("c", "app.bnb", "place Home\n  Sign In -> \"Login"),
+            (
+                "d",
+                "app.bnb",
+                "place Home\n  Sign In -> Auth\nplace Auth\n",
+            ),
+        ]);
+
+        let revisions = revisions("app.bnb", &repository).unwrap();
+        assert_eq!(revisions.len(), 4);
+        assert!(revisions[2].breadboard.is_err());
+
+        let timeline: Vec<_> = timeline(&revisions)
+            .into_iter()
+            .map(|entry| format!("{}\n{}", entry.commit.summary, entry.diff))
+            .collect();
+
+        assert_eq!(
+            timeline,
+            vec![
+                "Commit a\n+ place `Home`\n",
+                indoc! {"
+                    Commit b
+                    + affordance `Sign In` in `Home`
+                    + place `Login`
+                "},
+                "Commit d\n~ place `Login` renamed to `Auth`\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_revisions_error() {
+        struct Broken;
+        impl Repository for Broken {
+            fn log(&self, _: &Path) -> io::Result<Vec<Commit>> {
+                Err(io::Error::other("not a git repository"))
+            }
+
+            fn show(&self, _: &str, _: &Path, _: &Path) -> io::Result<String> {
+                unreachable!()
+            }
+        }
+
+        let repository = Memory(vec![("a", "app.bnb", "place Home\n")]);
+
+        assert!(revisions("app.bnb", &repository).is_ok());
+        assert_eq!(
+            revisions("app.bnb", &Broken).unwrap_err().to_string(),
+            "failed to read the history of app.bnb: not a git repository"
+        );
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let output = "\x1eabc123\x1fRename: home \x1f page\0\ndesigns/app.bnb\0\
+                      \x1edef456\x1fAdd login\0\napp.bnb\0\
+                      \x1e789abc\x1fAdd café\0\ncafé\n\"menu\".bnb\0";
+
+        assert_eq!(
+            parse_log(output),
+            vec![
+                Commit {
+                    id: "abc123".to_owned(),
+                    summary: "Rename: home \x1f page".to_owned(),
+                    path: PathBuf::from("designs/app.bnb"),
+                },
+                Commit {
+                    id: "def456".to_owned(),
+                    summary: "Add login".to_owned(),
+                    path: PathBuf::from("app.bnb"),
+                },
+                Commit {
+                    id: "789abc".to_owned(),
+                    summary: "Add café".to_owned(),
+                    path: PathBuf::from("café\n\"menu\".bnb"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split(Path::new("app.bnb")).unwrap(),
+            (Path::new("."), "app.bnb".as_ref())
+        );
+        assert_eq!(
+            split(Path::new("designs/app.bnb")).unwrap(),
+            (Path::new("designs"), "app.bnb".as_ref())
+        );
+        assert!(split(Path::new("/")).is_err());
+    }
+}
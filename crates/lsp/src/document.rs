@@ -0,0 +1,120 @@
+//! Open text documents, and conversion between byte offsets and LSP positions.
+//!
+//! Internally, all locations are byte offsets into the document text. The protocol counts
+//! characters in UTF-16 code units, so locations are converted at the edges.
+
+use std::ops::Range;
+
+use lsp_types::Position;
+
+use crate::index::Index;
+
+/// A text document opened in the editor.
+pub(crate) struct Document {
+    /// The full text of the document.
+    pub text: String,
+
+    /// The symbols defined and referenced in the document.
+    pub index: Index,
+
+    /// The version of the document, as reported by the editor.
+    pub version: i32,
+
+    /// The byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl Document {
+    pub(crate) fn new(text: String, version: i32) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+
+        Self {
+            index: Index::new(&text),
+            text,
+            version,
+            line_starts,
+        }
+    }
+
+    /// Converts a byte offset into a protocol position.
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+
+        Position {
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+            character: utf16_len(&self.text[start..offset]),
+        }
+    }
+
+    /// Converts a protocol position into a byte offset.
+    ///
+    /// Positions past the end of a line are clamped to the end of that line.
+    pub(crate) fn offset(&self, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+
+        let mut character = 0;
+        for (offset, c) in self.text[start..].char_indices() {
+            if c == '\n' || character >= position.character {
+                return start + offset;
+            }
+
+            character += utf16_len(c.encode_utf8(&mut [0; 4]));
+        }
+
+        self.text.len()
+    }
+
+    /// Converts a byte range into a protocol range.
+    pub(crate) fn range(&self, range: &Range<usize>) -> lsp_types::Range {
+        lsp_types::Range {
+            start: self.position(range.start),
+            end: self.position(range.end),
+        }
+    }
+
+    /// Returns the byte offset of the end of the line containing the given offset.
+    pub(crate) fn line_end(&self, offset: usize) -> usize {
+        self.text[offset..]
+            .find('\n')
+            .map_or(self.text.len(), |end| offset + end)
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    u32::try_from(text.encode_utf16().count()).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_offset() {
+        let document = Document::new("place Café\n  Naïve 🍞 -> Home\n".to_owned(), 0);
+
+        let test_cases = vec![
+            (0, Position::new(0, 0)),
+            (6, Position::new(0, 6)),
+            (12, Position::new(1, 0)),
+            // `ï` is two bytes, but one UTF-16 code unit.
+            (20, Position::new(1, 7)),
+            // `🍞` is four bytes, and two UTF-16 code units.
+            (25, Position::new(1, 10)),
+            (document.text.len(), Position::new(2, 0)),
+        ];
+
+        for (offset, position) in test_cases {
+            assert_eq!(document.position(offset), position, "{offset}");
+            assert_eq!(document.offset(position), offset, "{position:?}");
+        }
+
+        assert_eq!(document.offset(Position::new(0, 100)), 11);
+        assert_eq!(document.offset(Position::new(100, 0)), document.text.len());
+    }
+}
@@ -0,0 +1,704 @@
+//! The language features, implemented on top of a [`Document`].
+//!
+//! Handlers take byte offsets into the document, and leave converting request positions to the
+//! server loop in `server.rs`.
+
+use std::{
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use bnb_ast::Violation;
+use bnb_parser::{FileSystem, NameKind};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, DocumentSymbol,
+    MarkupContent, MarkupKind, SymbolKind, TextEdit,
+};
+
+use crate::{
+    document::Document,
+    index::{Index, Kind, Symbol, Target},
+};
+
+/// The name of the source reported with diagnostics.
+const SOURCE: &str = "bnb";
+
+/// Keywords offered as completions at the start of a line.
+const KEYWORDS: &[&str] = &[
+    "import",
+    "place",
+    "component",
+    "flow",
+    "include",
+    "position",
+    "sketch",
+];
+
+/// Parses and validates the document, returning all problems found.
+///
+/// If the document is stored at `path`, its imports are read from `fs` and validated along with
+/// it, but only problems within the document itself are reported.
+pub(crate) fn diagnostics(
+    document: &Document,
+    path: Option<&Path>,
+    fs: &dyn FileSystem,
+) -> Vec<Diagnostic> {
+    let breadboard = match bnb_parser::parse_located(&document.text) {
+        Ok(breadboard) => breadboard,
+        Err(error) => {
+            let end = document.line_end(error.offset);
+
+            // Most errors are only detected at the end of the line, so highlight the whole line.
+            let start = if error.offset == end {
+                let line_start = document.text[..end]
+                    .rfind('\n')
+                    .map_or(0, |start| start + 1);
+                let line = &document.text[line_start..end];
+                line_start + (line.len() - line.trim_start().len())
+            } else {
+                error.offset
+            };
+
+            return vec![diagnostic(document, &(start..end), error.to_string())];
+        }
+    };
+
+    let (breadboard, imported) = match path {
+        Some(path) if !breadboard.imports.is_empty() => {
+            let fs = Overlay { path, document, fs };
+
+            match bnb_parser::load(path, &fs) {
+                Ok(breadboard) => (breadboard, true),
+                Err(error) => {
+                    let range = import_range(document);
+                    return vec![diagnostic(document, &range, error.to_string())];
+                }
+            }
+        }
+        _ => (breadboard, false),
+    };
+
+    breadboard
+        .validate()
+        .iter()
+        .filter_map(|violation| {
+            let range = violation_range(&document.index, violation);
+
+            // Problems of imported places are reported in their own files.
+            let range = if imported {
+                range?
+            } else {
+                range.unwrap_or(0..0)
+            };
+            Some(diagnostic(document, &range, violation.to_string()))
+        })
+        .collect()
+}
+
+/// A [`FileSystem`] reading the document from memory, instead of its (unsaved) file.
+struct Overlay<'a> {
+    path: &'a Path,
+    document: &'a Document,
+    fs: &'a dyn FileSystem,
+}
+
+impl FileSystem for Overlay<'_> {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        if path == self.path {
+            Ok(self.document.text.clone())
+        } else {
+            self.fs.read_to_string(path)
+        }
+    }
+}
+
+/// Returns the range of the first `import` line, where problems of imported files are reported.
+fn import_range(document: &Document) -> Range<usize> {
+    let mut offset = 0;
+    for line in document.text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("import") {
+            let start = offset + line.len() - trimmed.len();
+            return start..offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+
+    0..0
+}
+
+/// Returns the path of a `file:` URI, or `None` for any other scheme.
+pub(crate) fn file_path(uri: &lsp_types::Uri) -> Option<PathBuf> {
+    if uri.scheme()?.as_str() != "file" {
+        return None;
+    }
+
+    let path = uri.path().as_estr().decode().into_string().ok()?;
+    Some(PathBuf::from(path.into_owned()))
+}
+
+fn diagnostic(document: &Document, range: &Range<usize>, message: String) -> Diagnostic {
+    Diagnostic {
+        range: document.range(range),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some(SOURCE.to_owned()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+/// Finds the most specific source range for a violation, falling back to the definition of the
+/// place or component it belongs to.
+fn violation_range(index: &Index, violation: &Violation) -> Option<Range<usize>> {
+    let definition = |name: &str| {
+        index
+            .definition(Kind::Place, name)
+            .or_else(|| index.definition(Kind::Component, name))
+    };
+
+    // Finds a reference to `name` within the given place or component.
+    let reference = |place: &str, kind: Kind, name: &str| {
+        let place = definition(place)?;
+        index
+            .references
+            .iter()
+            .find(|r| r.kind == kind && r.name == name && place.full_range.contains(&r.range.start))
+            .map(|r| r.range.clone())
+    };
+
+    // Finds a reference to `name` in a flow step, i.e. outside of any place or component.
+    let step = |kind: Kind, name: &str, place: Option<&str>| {
+        index
+            .references
+            .iter()
+            .find(|r| {
+                r.kind == kind
+                    && r.name == name
+                    && r.place.as_deref() == place
+                    && !index
+                        .definitions
+                        .iter()
+                        .any(|definition| definition.full_range.contains(&r.range.start))
+            })
+            .map(|r| r.range.clone())
+    };
+
+    let affordance = |place: &str, name: &str| {
+        definition(place)?
+            .children
+            .iter()
+            .find(|affordance| affordance.name == name)
+            .map(|affordance| affordance.range.clone())
+    };
+
+    let (owner, range) = match violation {
+        // Point at the second definition, the first one is considered the original.
+        Violation::DuplicatePlace { place: name }
+        | Violation::DuplicateComponent { component: name } => (
+            name,
+            index
+                .definitions
+                .iter()
+                .filter(|symbol| &symbol.name == name)
+                .nth(1)
+                .map(|symbol| symbol.range.clone()),
+        ),
+        Violation::UnknownConnectionTarget { place, target, .. }
+        | Violation::ModalWithoutReturn { place, target, .. }
+        | Violation::UnknownPositionPlace { place, target } => {
+            (place, reference(place, Kind::Place, target))
+        }
+        Violation::UnknownConnectionAffordance {
+            place,
+            target_affordance,
+            ..
+        } => (place, reference(place, Kind::Affordance, target_affordance)),
+        Violation::UnknownComponent { place, component } => {
+            (place, reference(place, Kind::Component, component))
+        }
+        Violation::SketchAffordanceWithoutConnections {
+            place,
+            affordance: name,
+        }
+        | Violation::UnknownAffordanceKind {
+            place,
+            affordance: name,
+            ..
+        } => (place, affordance(place, name)),
+        Violation::UnknownFlowPlace { place, .. } => (place, step(Kind::Place, place, None)),
+        Violation::UnknownFlowAffordance {
+            place, affordance, ..
+        }
+        | Violation::DisconnectedFlowStep {
+            place, affordance, ..
+        } => (place, step(Kind::Affordance, affordance, Some(place))),
+        Violation::RecursiveInclude { component: place }
+        | Violation::UnknownSketchAffordance { place, .. }
+        | Violation::DuplicateFlow { flow: place } => (place, None),
+    };
+
+    range.or_else(|| definition(owner).map(|symbol| symbol.range.clone()))
+}
+
+/// Returns the range of the definition of the symbol at the given offset.
+pub(crate) fn definition(document: &Document, offset: usize) -> Option<Range<usize>> {
+    let index = &document.index;
+
+    match index.at(offset)? {
+        Target::Definition(symbol) => Some(symbol.range.clone()),
+        Target::Reference(reference) => index.resolve(reference).map(|symbol| symbol.range.clone()),
+    }
+}
+
+/// Returns the description of the symbol at the given offset, and the range of that symbol.
+pub(crate) fn hover(document: &Document, offset: usize) -> Option<(MarkupContent, Range<usize>)> {
+    let index = &document.index;
+    let target = index.at(offset)?;
+
+    let symbol = match target {
+        Target::Definition(symbol) => symbol,
+        Target::Reference(reference) => index.resolve(reference)?,
+    };
+
+    let keyword = match symbol.kind {
+        Kind::Place => "place",
+        Kind::Component => "component",
+        Kind::Affordance => "affordance",
+    };
+
+    let mut value = format!("```bnb\n{keyword} {}\n```", symbol.name);
+    if !symbol.description.is_empty() {
+        value.push_str("\n\n");
+        value.push_str(&symbol.description.join("\n"));
+    }
+
+    let content = MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    };
+
+    Some((content, target.range().clone()))
+}
+
+/// Returns the completions for the given offset.
+///
+/// Place names are offered after `->` and in `position` coordinates, the affordances of a place
+/// after `-> Place.`, component names after `include`, and keywords at the start of a line.
+pub(crate) fn completion(document: &Document, offset: usize) -> Vec<CompletionItem> {
+    let line_start = document.text[..offset]
+        .rfind('\n')
+        .map_or(0, |start| start + 1);
+    let line = document.text[line_start..offset].trim_start();
+    let item = line.trim_start_matches(|c: char| c == '>' || c.is_whitespace());
+
+    let names = |kind: Kind, name_kind: NameKind| {
+        document
+            .index
+            .definitions
+            .iter()
+            .filter(move |symbol| symbol.kind == kind)
+            .map(move |symbol| completion_item(symbol, name_kind))
+    };
+
+    // The text after the last arrow of the line, if any.
+    let target = bnb_parser::ARROWS
+        .iter()
+        .filter_map(|(arrow, _)| line.rfind(arrow).map(|start| start + arrow.len()))
+        .max()
+        .map(|start| line[start..].trim_start());
+
+    if let Some((place, _)) = target.and_then(|target| target.split_once('.')) {
+        document
+            .index
+            .affordances(place.trim_matches('"'))
+            .into_iter()
+            .map(|symbol| completion_item(symbol, NameKind::TargetAffordance))
+            .collect()
+    } else if target.is_some() {
+        names(Kind::Place, NameKind::Target).collect()
+    } else if line.starts_with("position ") {
+        names(Kind::Place, NameKind::Coordinate).collect()
+    } else if item.starts_with("include ") {
+        names(Kind::Component, NameKind::Reference).collect()
+    } else if !line.contains(char::is_whitespace) {
+        KEYWORDS
+            .iter()
+            .map(|keyword| CompletionItem {
+                label: (*keyword).to_owned(),
+                kind: Some(CompletionItemKind::KEYWORD),
+                ..CompletionItem::default()
+            })
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+fn completion_item(symbol: &Symbol, name_kind: NameKind) -> CompletionItem {
+    let insert_text = bnb_parser::print_name_as(&symbol.name, name_kind);
+
+    CompletionItem {
+        label: symbol.name.clone(),
+        kind: Some(match symbol.kind {
+            Kind::Component => CompletionItemKind::MODULE,
+            Kind::Place | Kind::Affordance => CompletionItemKind::CLASS,
+        }),
+        detail: symbol.description.first().cloned(),
+        insert_text: (insert_text != symbol.name).then_some(insert_text),
+        ..CompletionItem::default()
+    }
+}
+
+/// Returns the outline of the document: its places and components, with their affordances.
+pub(crate) fn document_symbols(document: &Document) -> Vec<DocumentSymbol> {
+    document
+        .index
+        .definitions
+        .iter()
+        .map(|symbol| document_symbol(document, symbol))
+        .collect()
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated` must still be initialized.
+fn document_symbol(document: &Document, symbol: &Symbol) -> DocumentSymbol {
+    let (kind, detail) = match symbol.kind {
+        Kind::Place => (SymbolKind::CLASS, "place"),
+        Kind::Component => (SymbolKind::MODULE, "component"),
+        Kind::Affordance => (SymbolKind::FIELD, "affordance"),
+    };
+
+    let children = symbol
+        .children
+        .iter()
+        .map(|child| document_symbol(document, child))
+        .collect::<Vec<_>>();
+
+    DocumentSymbol {
+        name: if symbol.name.is_empty() {
+            format!("<unnamed {detail}>")
+        } else {
+            symbol.name.clone()
+        },
+        detail: Some(detail.to_owned()),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: document.range(&symbol.full_range),
+        selection_range: document.range(&symbol.range),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// Returns the range of the place, component or affordance at the given offset, if it can be
+/// renamed.
+pub(crate) fn prepare_rename(document: &Document, offset: usize) -> Option<Range<usize>> {
+    refactor_symbol(&document.index, offset)?;
+
+    document
+        .index
+        .at(offset)
+        .map(|target| target.range().clone())
+}
+
+/// Renames the place, component or affordance at the given offset, including all references to
+/// it.
+///
+/// Returns `Ok(None)` if there is nothing to rename at the given offset.
+pub(crate) fn rename(
+    document: &Document,
+    offset: usize,
+    new_name: &str,
+) -> Result<Option<Vec<TextEdit>>, bnb_refactor::Error> {
+    let Some(symbol) = refactor_symbol(&document.index, offset) else {
+        return Ok(None);
+    };
+
+    let edits = bnb_refactor::rename_source(&document.text, &symbol, new_name)?
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: document.range(&edit.range),
+            new_text: edit.new_text,
+        })
+        .collect();
+
+    Ok(Some(edits))
+}
+
+/// Returns the symbol to refactor for the definition or reference at the given offset.
+fn refactor_symbol(index: &Index, offset: usize) -> Option<bnb_refactor::Symbol> {
+    let target = index.at(offset)?;
+    let name = target.name().to_owned();
+
+    let symbol = match target.kind() {
+        Kind::Place => bnb_refactor::Symbol::Place(name),
+        Kind::Component => bnb_refactor::Symbol::Component(name),
+        Kind::Affordance => {
+            let affordance = match target {
+                Target::Definition(affordance) => affordance,
+                Target::Reference(reference) => index.resolve(reference)?,
+            };
+
+            let parent = index.definitions.iter().find(|definition| {
+                definition
+                    .children
+                    .iter()
+                    .any(|child| std::ptr::eq(child, affordance))
+            })?;
+
+            bnb_refactor::Symbol::Affordance {
+                place: parent.name.clone(),
+                affordance: name,
+            }
+        }
+    };
+
+    Some(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const TEXT: &str = indoc! {"
+        /// The start.
+        place Home
+          include Header
+          Sign Up -> Away
+
+        place Away
+          Back -> Home -> Nowhere
+          position < Home + 10, 0
+
+        component Header
+          Logo
+    "};
+
+    fn offset_of(needle: &str, nth: usize) -> usize {
+        TEXT.match_indices(needle).nth(nth).unwrap().0
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let document = Document::new(TEXT.to_owned(), 0);
+        let found = diagnostics(&document, None, &bnb_parser::OsFileSystem);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].message,
+            "affordance `Back` in `Away` connects to unknown place `Nowhere`"
+        );
+        assert_eq!(
+            found[0].range,
+            document.range(&(offset_of("Nowhere", 0)..offset_of("Nowhere", 0) + 7))
+        );
+
+        let document = Document::new("place Home\n  Go -> \"Away\n".to_owned(), 0);
+        let found = diagnostics(&document, None, &bnb_parser::OsFileSystem);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, "unterminated quoted string");
+        assert_eq!(
+            found[0].range,
+            lsp_types::Range::new(
+                lsp_types::Position::new(1, 2),
+                lsp_types::Position::new(1, 13)
+            )
+        );
+    }
+
+    #[test]
+    fn test_flow_diagnostics() {
+        let text = "place Home\n  Go -> Home\n  Stay\n\nflow Loop\n  Home: Stay\n  Home: Gone\n";
+        let document = Document::new(text.to_owned(), 0);
+        let found = diagnostics(&document, None, &bnb_parser::OsFileSystem);
+        let range = |needle: &str| {
+            let start = text.rfind(needle).unwrap();
+            document.range(&(start..start + needle.len()))
+        };
+
+        let found: Vec<_> = found
+            .iter()
+            .map(|diagnostic| (diagnostic.message.as_str(), diagnostic.range))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "flow `Loop` continues to `Home`, but affordance `Stay` in `Home` does not \
+                     connect to it",
+                    range("Stay")
+                ),
+                (
+                    "flow `Loop` uses unknown affordance `Gone` in `Home`",
+                    range("Gone")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_with_imports() {
+        let text = "import \"shared.bnb\"\n\nplace Home\n  include Header\n  Go -> Away\n";
+        let document = Document::new(text.to_owned(), 0);
+        let path = Path::new("app/main.bnb");
+        let mut files = std::collections::HashMap::from([(
+            PathBuf::from("app/shared.bnb"),
+            "place Away\n  Back -> Nowhere\n\ncomponent Header\n  Logo\n".to_owned(),
+        )]);
+
+        // Problems of the imported file are not reported in the document.
+        assert_eq!(diagnostics(&document, Some(path), &files), vec![]);
+        assert_eq!(diagnostics(&document, None, &files).len(), 2);
+
+        files.insert(PathBuf::from("app/shared.bnb"), "place Home\n".to_owned());
+        let found = diagnostics(&document, Some(path), &files);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].message,
+            "place `Home` is defined in both app/main.bnb and app/shared.bnb"
+        );
+        assert_eq!(found[0].range, document.range(&(0..19)));
+    }
+
+    #[test]
+    fn test_definition() {
+        let document = Document::new(TEXT.to_owned(), 0);
+
+        let away = offset_of("Away", 1);
+        assert_eq!(
+            definition(&document, offset_of("Away", 0) + 1),
+            Some(away..away + 4)
+        );
+
+        let header = offset_of("Header", 1);
+        assert_eq!(
+            definition(&document, offset_of("Header", 0)),
+            Some(header..header + 6)
+        );
+
+        assert_eq!(definition(&document, offset_of("Nowhere", 0)), None);
+    }
+
+    #[test]
+    fn test_hover() {
+        let document = Document::new(TEXT.to_owned(), 0);
+        let (content, range) = hover(&document, offset_of("Home", 1)).unwrap();
+
+        assert_eq!(content.value, "```bnb\nplace Home\n```\n\nThe start.");
+        assert_eq!(range, offset_of("Home", 1)..offset_of("Home", 1) + 4);
+    }
+
+    #[test]
+    fn test_completion() {
+        let labels = |text: &str| {
+            let text = format!("{TEXT}{text}");
+            let document = Document::new(text.clone(), 0);
+            completion(&document, text.len())
+                .into_iter()
+                .map(|item| item.label)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(labels("  Go -> "), vec!["Home", "Away"]);
+        assert_eq!(labels("  Go -> Away ~> "), vec!["Home", "Away"]);
+        assert_eq!(labels("  position ^ "), vec!["Home", "Away"]);
+        assert_eq!(labels("  > include "), vec!["Header"]);
+        // The line being typed is itself an affordance of `Header`, which `Home` includes.
+        assert_eq!(labels("  Go -> Home."), vec!["Sign Up", "Logo", "Go"]);
+        assert_eq!(labels("comp"), KEYWORDS);
+        assert!(labels("fl").iter().any(|label| label == "flow"));
+        assert!(labels("  Some affordance").is_empty());
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let document = Document::new(TEXT.to_owned(), 0);
+        let symbols = document_symbols(&document);
+
+        let names: Vec<_> = symbols
+            .iter()
+            .map(|symbol| {
+                let children = symbol.children.iter().flatten();
+                (
+                    symbol.name.as_str(),
+                    children.map(|c| c.name.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("Home", vec!["Sign Up"]),
+                ("Away", vec!["Back"]),
+                ("Header", vec!["Logo"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let document = Document::new(TEXT.to_owned(), 0);
+        let new_texts = |offset, new_name| {
+            rename(&document, offset, new_name)
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .map(|edit| edit.new_text)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            new_texts(offset_of("Home", 2), "Home, Sweet -> Home"),
+            vec![
+                "Home, Sweet -> Home",
+                "\"Home, Sweet -> Home\"",
+                "\"Home, Sweet -> Home\"",
+            ]
+        );
+        assert_eq!(
+            new_texts(offset_of("Sign Up", 0), "Register"),
+            vec!["Register"]
+        );
+
+        assert_eq!(
+            prepare_rename(&document, offset_of("Logo", 0)),
+            Some(offset_of("Logo", 0)..offset_of("Logo", 0) + 4)
+        );
+        assert_eq!(prepare_rename(&document, offset_of("position", 0)), None);
+        assert_eq!(
+            rename(&document, offset_of("Away", 1), "Home"),
+            Err(bnb_refactor::Error::AlreadyExists(
+                bnb_refactor::Symbol::Place("Home".to_owned())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_target_affordance() {
+        let text = "place Home\n  include Header\n  Go -> Home.Logo -> Home.Nothing\n\ncomponent Header\n  Logo\n";
+        let document = Document::new(text.to_owned(), 0);
+        let logo = text.rfind("Logo").unwrap();
+
+        assert_eq!(
+            definition(&document, text.find("Logo").unwrap()),
+            Some(logo..logo + 4)
+        );
+
+        let found = diagnostics(&document, None, &bnb_parser::OsFileSystem);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].range,
+            document.range(&(text.find("Nothing").unwrap()..text.find("Nothing").unwrap() + 7))
+        );
+
+        let edits = rename(&document, text.find("Logo").unwrap(), "Brand")
+            .unwrap()
+            .unwrap();
+        assert_eq!(edits.len(), 2);
+    }
+}
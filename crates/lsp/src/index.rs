@@ -0,0 +1,508 @@
+//! A lightweight index of the symbols in a document.
+//!
+//! The AST produced by the parser does not retain source locations, so the index is built from the
+//! [`tokenize`] token stream instead, to find where places, components and affordances are defined,
+//! and where they are referenced. Tokenizing never fails, so incomplete lines, as they appear while
+//! typing, are indexed as far as possible.
+
+use std::ops::Range;
+
+use bnb_parser::{tokenize, NameKind, Token, TokenKind};
+
+/// The kind of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Place,
+    Component,
+    Affordance,
+}
+
+/// A definition of a place, component or affordance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Symbol {
+    pub kind: Kind,
+    pub name: String,
+
+    /// The byte range of the name, including any quotes.
+    pub range: Range<usize>,
+
+    /// The byte range of the entire definition, from its keyword to its last line.
+    pub full_range: Range<usize>,
+
+    /// The `///` description lines of the definition.
+    pub description: Vec<String>,
+
+    /// The affordances defined in a place or component.
+    pub children: Vec<Symbol>,
+}
+
+/// A reference to a place (from a connection or position), a component (from an `include`), or
+/// an affordance (from a connection target).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Reference {
+    pub kind: Kind,
+    pub name: String,
+
+    /// The place of a referenced affordance.
+    pub place: Option<String>,
+
+    /// The byte range of the name, including any quotes.
+    pub range: Range<usize>,
+}
+
+/// The symbol found at a given offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Target<'a> {
+    Definition(&'a Symbol),
+    Reference(&'a Reference),
+}
+
+impl Target<'_> {
+    pub(crate) fn kind(self) -> Kind {
+        match self {
+            Self::Definition(symbol) => symbol.kind,
+            Self::Reference(reference) => reference.kind,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Self::Definition(symbol) => &symbol.name,
+            Self::Reference(reference) => &reference.name,
+        }
+    }
+
+    pub(crate) fn range(&self) -> &Range<usize> {
+        match self {
+            Self::Definition(symbol) => &symbol.range,
+            Self::Reference(reference) => &reference.range,
+        }
+    }
+}
+
+/// All symbols defined and referenced in a document.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Index {
+    /// The places and components, in source order.
+    pub definitions: Vec<Symbol>,
+
+    /// All references to places and components, in source order.
+    pub references: Vec<Reference>,
+}
+
+impl Index {
+    pub(crate) fn new(text: &str) -> Self {
+        let mut scanner = Scanner::default();
+
+        for token in tokenize(text) {
+            scanner.token(text, &token);
+        }
+
+        scanner.finish()
+    }
+
+    /// Returns the definition of the place or component with the given name.
+    pub(crate) fn definition(&self, kind: Kind, name: &str) -> Option<&Symbol> {
+        self.definitions
+            .iter()
+            .find(|symbol| symbol.kind == kind && symbol.name == name)
+    }
+
+    /// Returns the affordances of a place, including those of the components it directly
+    /// includes.
+    pub(crate) fn affordances(&self, place: &str) -> Vec<&Symbol> {
+        let Some(place) = self.definition(Kind::Place, place) else {
+            return vec![];
+        };
+
+        let components = self
+            .references
+            .iter()
+            .filter(|reference| {
+                reference.kind == Kind::Component
+                    && place.full_range.contains(&reference.range.start)
+            })
+            .filter_map(|reference| self.definition(Kind::Component, &reference.name));
+
+        std::iter::once(place)
+            .chain(components)
+            .flat_map(|symbol| &symbol.children)
+            .collect()
+    }
+
+    /// Returns the definition of the affordance with the given name in a place.
+    pub(crate) fn affordance(&self, place: &str, name: &str) -> Option<&Symbol> {
+        self.affordances(place)
+            .into_iter()
+            .find(|symbol| symbol.name == name)
+    }
+
+    /// Returns the definition a reference refers to.
+    pub(crate) fn resolve(&self, reference: &Reference) -> Option<&Symbol> {
+        match &reference.place {
+            Some(place) => self.affordance(place, &reference.name),
+            None => self.definition(reference.kind, &reference.name),
+        }
+    }
+
+    /// Returns the symbol at the given offset, if any.
+    pub(crate) fn at(&self, offset: usize) -> Option<Target<'_>> {
+        let contains = |range: &Range<usize>| range.start <= offset && offset <= range.end;
+
+        let definitions = self
+            .definitions
+            .iter()
+            .flat_map(|symbol| std::iter::once(symbol).chain(&symbol.children));
+
+        definitions
+            .filter(|symbol| contains(&symbol.range))
+            .map(Target::Definition)
+            .chain(
+                self.references
+                    .iter()
+                    .filter(|reference| contains(&reference.range))
+                    .map(Target::Reference),
+            )
+            .next()
+    }
+}
+
+#[derive(Default)]
+struct Scanner {
+    index: Index,
+
+    /// The description lines preceding the current token.
+    description: Vec<String>,
+
+    /// The start of the `>` level markers preceding the current token.
+    level_start: Option<usize>,
+
+    /// The end offset of the last token with content.
+    content_end: usize,
+
+    /// The place targeted by the current connection, or visited by the current flow step.
+    target: Option<String>,
+
+    /// Whether the last place or component is still open, i.e. not followed by a flow.
+    open: bool,
+}
+
+impl Scanner {
+    fn token(&mut self, text: &str, token: &Token) {
+        let span = token.span.clone();
+
+        match token.kind {
+            TokenKind::Description => {
+                let description = &token.text(text)[3..];
+                let description = description.strip_prefix(' ').unwrap_or(description);
+                self.description.push(description.to_owned());
+            }
+            TokenKind::Keyword => {
+                self.level_start = None;
+                self.keyword(token.text(text), span);
+            }
+            TokenKind::Level => {
+                self.level_start.get_or_insert(span.start);
+            }
+            TokenKind::Arrow | TokenKind::ConnectionLabel | TokenKind::Annotation => {
+                self.extend_affordance(span.end);
+            }
+            TokenKind::AreaBracket => {
+                self.description.clear();
+                self.content_end = span.end;
+            }
+            TokenKind::Name(kind) | TokenKind::QuotedString(kind) => {
+                let name = token.name(text).unwrap_or_default().into_owned();
+                self.name(kind, name, span);
+            }
+            TokenKind::Comment
+            | TokenKind::Pivot
+            | TokenKind::Integer
+            | TokenKind::Separator
+            | TokenKind::SketchPath
+            | TokenKind::ImportPath
+            | TokenKind::AttributeKey
+            | TokenKind::AttributeValue => {}
+        }
+    }
+
+    fn name(&mut self, kind: NameKind, name: String, span: Range<usize>) {
+        match kind {
+            NameKind::Place | NameKind::Component => {
+                if let Some(symbol) = self.index.definitions.last_mut() {
+                    symbol.name = name;
+                    symbol.range = span.clone();
+                }
+            }
+            NameKind::Affordance => {
+                let full_range = self.level_start.take().unwrap_or(span.start)..span.end;
+
+                if let Some(parent) = self.index.definitions.last_mut() {
+                    parent.children.push(Symbol {
+                        kind: Kind::Affordance,
+                        name,
+                        range: span.clone(),
+                        full_range,
+                        description: std::mem::take(&mut self.description),
+                        children: vec![],
+                    });
+                }
+            }
+            NameKind::Reference => self.reference(Kind::Component, name, None, &span),
+            NameKind::Target => {
+                self.extend_affordance(span.end);
+                self.target = Some(name.clone());
+                self.reference(Kind::Place, name, None, &span);
+            }
+            NameKind::TargetAffordance => {
+                self.extend_affordance(span.end);
+                let place = self.target.clone();
+                self.reference(Kind::Affordance, name, place, &span);
+            }
+            NameKind::Coordinate => self.reference(Kind::Place, name, None, &span),
+            NameKind::StepPlace => {
+                self.target = Some(name.clone());
+                self.reference(Kind::Place, name, None, &span);
+            }
+            NameKind::StepAffordance => {
+                let place = self.target.clone();
+                self.reference(Kind::Affordance, name, place, &span);
+            }
+            NameKind::Area | NameKind::Flow => {}
+        }
+
+        self.content_end = span.end;
+    }
+
+    fn keyword(&mut self, keyword: &str, span: Range<usize>) {
+        let description = std::mem::take(&mut self.description);
+
+        let kind = match keyword {
+            "place" => Kind::Place,
+            "component" => Kind::Component,
+            "flow" => {
+                self.close();
+                self.content_end = span.end;
+                return;
+            }
+            _ => {
+                self.content_end = span.end;
+                return;
+            }
+        };
+
+        self.close();
+        self.open = true;
+        self.content_end = span.end;
+        self.index.definitions.push(Symbol {
+            kind,
+            name: String::new(),
+            range: span.end..span.end,
+            full_range: span,
+            description,
+            children: vec![],
+        });
+    }
+
+    fn reference(&mut self, kind: Kind, name: String, place: Option<String>, span: &Range<usize>) {
+        if name.is_empty() {
+            return;
+        }
+
+        self.index.references.push(Reference {
+            kind,
+            name,
+            place,
+            range: span.clone(),
+        });
+    }
+
+    /// Extends the last affordance to include its connections.
+    fn extend_affordance(&mut self, end: usize) {
+        let affordance = self
+            .index
+            .definitions
+            .last_mut()
+            .and_then(|symbol| symbol.children.last_mut());
+
+        if let Some(affordance) = affordance {
+            affordance.full_range.end = end;
+        }
+
+        self.content_end = end;
+    }
+
+    /// Closes the current definition, extending it to its last token with content.
+    fn close(&mut self) {
+        if !std::mem::take(&mut self.open) {
+            return;
+        }
+
+        if let Some(symbol) = self.index.definitions.last_mut() {
+            symbol.full_range.end = symbol.full_range.end.max(self.content_end);
+        }
+    }
+
+    fn finish(mut self) -> Index {
+        self.close();
+        self.index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_index() {
+        let text = indoc! {r#"
+            /// The start.
+            place Home
+              include Header
+              /// Go away.
+              Sign Up -> (done) Away -> ("a )") "Far -> Away"
+              > Nested -> Home.Logo
+
+              position < Away - 10, ^ "Far -> Away" + 2
+              sketch home.png
+                [0,0 10,10] Sign Up
+
+            component Header
+              Logo
+        "#};
+
+        let index = Index::new(text);
+        let slice = |range: &Range<usize>| &text[range.clone()];
+
+        let names: Vec<_> = index
+            .definitions
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.kind,
+                    slice(&symbol.range),
+                    symbol.description.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Kind::Place, "Home", vec!["The start.".to_owned()]),
+                (Kind::Component, "Header", vec![]),
+            ]
+        );
+
+        let home = &index.definitions[0];
+        assert!(slice(&home.full_range).starts_with("place Home"));
+        assert!(slice(&home.full_range).ends_with("Sign Up"));
+
+        let affordances: Vec<_> = home
+            .children
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.description.clone()))
+            .collect();
+        assert_eq!(
+            affordances,
+            vec![("Sign Up", vec!["Go away.".to_owned()]), ("Nested", vec![]),]
+        );
+
+        let references: Vec<_> = index
+            .references
+            .iter()
+            .map(|reference| {
+                (
+                    reference.kind,
+                    reference.name.as_str(),
+                    slice(&reference.range),
+                )
+            })
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                (Kind::Component, "Header", "Header"),
+                (Kind::Place, "Away", "Away"),
+                (Kind::Place, "Far -> Away", r#""Far -> Away""#),
+                (Kind::Place, "Home", "Home"),
+                (Kind::Affordance, "Logo", "Logo"),
+                (Kind::Place, "Away", "Away"),
+                (Kind::Place, "Far -> Away", r#""Far -> Away""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flows() {
+        let text = "place Home\n  Go -> Home\n\nflow Loop\n  Home: Go\n  Home: Go\n\nplace Away\n";
+        let index = Index::new(text);
+
+        let home = &index.definitions[0];
+        assert_eq!(&text[home.full_range.clone()], "place Home\n  Go -> Home");
+        assert_eq!(index.definitions[1].name, "Away");
+
+        let steps: Vec<_> = index.references[1..]
+            .iter()
+            .map(|reference| {
+                (
+                    reference.kind,
+                    reference.name.as_str(),
+                    reference.place.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (Kind::Place, "Home", None),
+                (Kind::Affordance, "Go", Some("Home")),
+                (Kind::Place, "Home", None),
+                (Kind::Affordance, "Go", Some("Home")),
+            ]
+        );
+        assert_eq!(
+            index
+                .resolve(&index.references[2])
+                .map(|symbol| symbol.range.clone()),
+            Some(13..15)
+        );
+    }
+
+    #[test]
+    fn test_index_incomplete() {
+        let index = Index::new("place Home\n  Sign Up -> \n  position ^\nplace");
+
+        assert_eq!(index.definitions.len(), 2);
+        assert_eq!(index.definitions[0].children[0].name, "Sign Up");
+        assert!(index.references.is_empty());
+    }
+
+    #[test]
+    fn test_affordance() {
+        let index = Index::new(
+            "place Home
+  include Header
+  Go -> Home.Logo
+
+component Header
+  Logo",
+        );
+
+        assert_eq!(index.references[2].place.as_deref(), Some("Home"));
+        assert_eq!(
+            index
+                .resolve(&index.references[2])
+                .map(|symbol| symbol.range.clone()),
+            Some(66..70)
+        );
+        assert_eq!(
+            index
+                .affordance("Home", "Go")
+                .map(|symbol| symbol.name.as_str()),
+            Some("Go")
+        );
+        assert_eq!(index.affordance("Home", "Nothing"), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! # Bread'n'Butter Language Server
+//!
+//! **Breadboards, with all the trimmings of a modern editor.**
+//!
+//! The `bnb-lsp` binary implements the [Language Server Protocol][lsp] for `.bnb` files. It
+//! communicates over stdin and stdout, and can be used with any editor that supports LSP.
+//!
+//! ## Features
+//!
+//! - Diagnostics for parse errors and validation problems, as you type.
+//! - Go to definition of places (from `->` connections, `position` coordinates and flow steps),
+//!   affordances (from `-> Place.Affordance` targets and flow steps) and components (from `include`
+//!   lines).
+//! - Hover, showing the description of places, components and affordances.
+//! - Completion of keywords, place names, component names and the affordances of a target place.
+//! - Document symbols, outlining places and components with their affordances.
+//! - Rename of places, components and affordances, updating all references in the document.
+//!
+//! [lsp]: https://microsoft.github.io/language-server-protocol/
+
+mod document;
+mod handlers;
+mod index;
+mod server;
+
+use lsp_server::Connection;
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = serde_json::to_value(server::capabilities())?;
+    connection.initialize(capabilities)?;
+
+    server::Server::new(connection).run()?;
+    io_threads.join()?;
+
+    Ok(())
+}
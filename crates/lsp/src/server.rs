@@ -0,0 +1,287 @@
+//! The main loop, dispatching protocol messages to the [`handlers`].
+
+use std::collections::HashMap;
+
+use lsp_server::{Connection, ErrorCode, ExtractError, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{
+        Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, PrepareRenameRequest,
+        Rename, Request as _,
+    },
+    CompletionOptions, CompletionResponse, DocumentSymbolResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverProviderCapability, Location, OneOf,
+    PrepareRenameResponse, PublishDiagnosticsParams, RenameOptions, ServerCapabilities,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+    WorkspaceEdit,
+};
+
+use crate::{document::Document, handlers};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Returns the features supported by the server.
+pub(crate) fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![">".to_owned(), " ".to_owned()]),
+            ..CompletionOptions::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+        })),
+        ..ServerCapabilities::default()
+    }
+}
+
+pub(crate) struct Server {
+    connection: Connection,
+
+    /// The documents currently opened in the editor.
+    documents: HashMap<Uri, Document>,
+}
+
+impl Server {
+    pub(crate) fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Handles messages until the editor shuts down the server.
+    pub(crate) fn run(mut self) -> Result<(), Error> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    self.request(request)?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Result<(), Error> {
+        match request.method.as_str() {
+            GotoDefinition::METHOD => self.respond::<GotoDefinition>(request, Self::definition),
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, Self::hover),
+            Completion::METHOD => self.respond::<Completion>(request, Self::completion),
+            DocumentSymbolRequest::METHOD => {
+                self.respond::<DocumentSymbolRequest>(request, |server, params| {
+                    let document = server.documents.get(&params.text_document.uri)?;
+                    let symbols = handlers::document_symbols(document);
+
+                    Some(DocumentSymbolResponse::Nested(symbols))
+                })
+            }
+            PrepareRenameRequest::METHOD => {
+                self.respond::<PrepareRenameRequest>(request, Self::prepare_rename)
+            }
+            Rename::METHOD => {
+                self.try_respond::<Rename, bnb_refactor::Error>(request, |server, params| {
+                    let Some((uri, document, offset)) =
+                        server.locate(&params.text_document_position)
+                    else {
+                        return Ok(None);
+                    };
+
+                    let edits = handlers::rename(document, offset, &params.new_name)?;
+
+                    Ok(edits.map(|edits| WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), edits)])),
+                        ..WorkspaceEdit::default()
+                    }))
+                })
+            }
+            _ => {
+                let response = Response::new_err(
+                    request.id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request `{}`", request.method),
+                );
+
+                Ok(self.connection.sender.send(response.into())?)
+            }
+        }
+    }
+
+    /// Extracts the parameters of the request, and sends back the result of the handler.
+    fn respond<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, &R::Params) -> R::Result,
+    ) -> Result<(), Error> {
+        self.try_respond::<R, _>(request, |server, params| {
+            Ok::<_, std::convert::Infallible>(handler(server, params))
+        })
+    }
+
+    /// Like [`Self::respond`], but reports errors returned by the handler as a failed request.
+    fn try_respond<R: lsp_types::request::Request, E: std::fmt::Display>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, &R::Params) -> Result<R::Result, E>,
+    ) -> Result<(), Error> {
+        let id = request.id.clone();
+        let response = match request.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => match handler(self, &params) {
+                Ok(result) => Response::new_ok(id, result),
+                Err(error) => {
+                    Response::new_err(id, ErrorCode::RequestFailed as i32, error.to_string())
+                }
+            },
+            Err(ExtractError::JsonError { method, error }) => Response::new_err(
+                id,
+                ErrorCode::InvalidParams as i32,
+                format!("invalid parameters for `{method}`: {error}"),
+            ),
+            Err(ExtractError::MethodMismatch(request)) => {
+                unreachable!("dispatched on method `{}`", request.method)
+            }
+        };
+
+        Ok(self.connection.sender.send(response.into())?)
+    }
+
+    fn notification(&mut self, notification: Notification) -> Result<(), Error> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = params::<DidOpenTextDocument>(notification) else {
+                    return Ok(());
+                };
+                let document = params.text_document;
+
+                self.update(document.uri, Document::new(document.text, document.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = params::<DidChangeTextDocument>(notification) else {
+                    return Ok(());
+                };
+
+                // With full synchronization, the last change contains the entire document.
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(());
+                };
+
+                let document = Document::new(change.text, params.text_document.version);
+                self.update(params.text_document.uri, document)
+            }
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = params::<DidCloseTextDocument>(notification) else {
+                    return Ok(());
+                };
+                let uri = params.text_document.uri;
+
+                self.documents.remove(&uri);
+                self.publish(uri, vec![], None)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Stores the new contents of a document, and publishes its diagnostics.
+    fn update(&mut self, uri: Uri, document: Document) -> Result<(), Error> {
+        let path = handlers::file_path(&uri);
+        let diagnostics =
+            handlers::diagnostics(&document, path.as_deref(), &bnb_parser::OsFileSystem);
+        let version = document.version;
+
+        self.documents.insert(uri.clone(), document);
+        self.publish(uri, diagnostics, Some(version))
+    }
+
+    fn publish(
+        &self,
+        uri: Uri,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<(), Error> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+
+        Ok(self.connection.sender.send(notification.into())?)
+    }
+
+    /// Returns the document and byte offset of a position in a request.
+    fn locate<'a>(
+        &'a self,
+        position: &'a TextDocumentPositionParams,
+    ) -> Option<(&'a Uri, &'a Document, usize)> {
+        let uri = &position.text_document.uri;
+        let document = self.documents.get(uri)?;
+
+        Some((uri, document, document.offset(position.position)))
+    }
+
+    fn definition(&self, params: &GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let (uri, document, offset) = self.locate(&params.text_document_position_params)?;
+        let range = handlers::definition(document, offset)?;
+
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri: uri.clone(),
+            range: document.range(&range),
+        }))
+    }
+
+    fn hover(&self, params: &lsp_types::HoverParams) -> Option<Hover> {
+        let (_, document, offset) = self.locate(&params.text_document_position_params)?;
+        let (content, range) = handlers::hover(document, offset)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(content),
+            range: Some(document.range(&range)),
+        })
+    }
+
+    fn completion(&self, params: &lsp_types::CompletionParams) -> Option<CompletionResponse> {
+        let (_, document, offset) = self.locate(&params.text_document_position)?;
+        let items = handlers::completion(document, offset);
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn prepare_rename(&self, params: &TextDocumentPositionParams) -> Option<PrepareRenameResponse> {
+        let (_, document, offset) = self.locate(params)?;
+        let range = handlers::prepare_rename(document, offset)?;
+
+        Some(PrepareRenameResponse::Range(document.range(&range)))
+    }
+}
+
+/// Extracts the parameters of a notification.
+///
+/// Notifications have no response to carry an error, so malformed parameters are logged to stderr
+/// and the notification is dropped, instead of stopping the server.
+fn params<N: lsp_types::notification::Notification>(
+    notification: Notification,
+) -> Option<N::Params> {
+    match notification.extract(N::METHOD) {
+        Ok(params) => Some(params),
+        Err(ExtractError::JsonError { method, error }) => {
+            eprintln!("bnb-lsp: invalid parameters for `{method}`: {error}");
+            None
+        }
+        Err(ExtractError::MethodMismatch(notification)) => {
+            unreachable!("dispatched on method `{}`", notification.method)
+        }
+    }
+}
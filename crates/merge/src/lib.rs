@@ -0,0 +1,899 @@
+//! # Bread'n'Butter Merge
+//!
+//! **A buttery smooth merging experience.**
+//!
+//! The `bnb_merge` crate merges two versions of a [`Breadboard`] that were both derived from a
+//! common base version, at the design level instead of line by line. Independent changes to
+//! different places, components, affordances and flows are combined automatically, while changes
+//! that cannot be combined are reported as [`Conflict`]s.
+//!
+//! Places, components and flows are matched by name, as are the affordances and includes within a
+//! place. For nodes that exist in all versions, every field (such as the description, the kind or
+//! the connections of an affordance) is merged on its own: a field that changed on only one side
+//! takes that side's value, and a field that changed differently on both sides is a conflict.
+//! Nodes that were added on both sides are merged in the same way, but without a base to fall
+//! back to.
+//!
+//! Items are also checked against the nesting of their side: an item nested (with `>`) under an
+//! affordance or include that the other side removed keeps its parent, as a conflict.
+//!
+//! The merged breadboard is always complete. For each conflict, it contains *our* version of the
+//! field, and a node that was changed on one side and removed on the other is kept. Their version
+//! of a node with a conflicting field is kept with the conflict.
+//!
+//! The `bnb-merge` binary wraps [`merge`] as a git merge driver.
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_merge::merge;
+//! use bnb_parser::parse;
+//!
+//! let base = parse("place Home\n  Sign In -> Login\n\nplace Login\n").unwrap();
+//! let ours = parse("place Home\n  Sign In -> Login\n  Help\n\nplace Login\n").unwrap();
+//! let theirs = parse("place Home\n  Sign In -> Login\n\nplace Login\n  Submit\n").unwrap();
+//!
+//! let merge = merge(&base, &ours, &theirs);
+//!
+//! assert!(merge.is_clean());
+//! assert_eq!(
+//!     bnb_parser::print(&merge.breadboard),
+//!     "place Home\n  Sign In -> Login\n  Help\n\nplace Login\n  Submit\n"
+//! );
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+use std::fmt;
+
+use bnb_ast::{Affordance, Attributes, Breadboard, Component, Flow, Item, Place, Reference};
+use bnb_parser::print;
+
+/// The result of a three-way [`merge`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    /// The merged breadboard, with our version of any conflicting changes.
+    pub breadboard: Breadboard,
+
+    /// The changes that could not be merged, in the order of our breadboard.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    /// Returns `true` if all changes were merged without conflicts.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A change that could not be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The node the conflicting changes were made to.
+    pub node: Node,
+
+    /// How the changes conflict.
+    pub kind: Kind,
+
+    /// Their version of the node, in the DSL, if the merged breadboard has our version of it.
+    ///
+    /// Places and components are printed without their affordances and includes, which are
+    /// merged on their own.
+    pub theirs: Option<String>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.node, self.kind)
+    }
+}
+
+/// A node of a breadboard, identified by its name (and the name of its place).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    /// A place, or one of its properties, such as its description.
+    Place { place: String },
+
+    /// A component, or one of its properties, such as its description.
+    Component { component: String },
+
+    /// An affordance in a place or component.
+    Affordance { place: String, affordance: String },
+
+    /// An include of a component in a place or component.
+    Include { place: String, component: String },
+
+    /// A flow, or one of its properties, such as its steps.
+    Flow { flow: String },
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Place { place } => write!(f, "place `{place}`"),
+            Self::Component { component } => write!(f, "component `{component}`"),
+            Self::Affordance { place, affordance } => {
+                write!(f, "affordance `{affordance}` in `{place}`")
+            }
+            Self::Include { place, component } => {
+                write!(f, "include of `{component}` in `{place}`")
+            }
+            Self::Flow { flow } => write!(f, "flow `{flow}`"),
+        }
+    }
+}
+
+/// The kind of a [`Conflict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// The field of the node was changed differently on both sides, or the node was added on
+    /// both sides with different values for the field.
+    ///
+    /// Attributes are merged one by one, with `@key` as the name of the field.
+    Changed { field: String },
+
+    /// The node was changed on one side, and removed on the other.
+    ///
+    /// Adding an item nested under an affordance or include changes that parent.
+    Removed,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Changed { field } => write!(f, "{field} changed on both sides"),
+            Self::Removed => f.write_str("changed on one side, removed on the other"),
+        }
+    }
+}
+
+/// Merges the changes made in `ours` and `theirs`, both derived from `base`.
+///
+/// See the [crate documentation](crate) for how changes are merged.
+#[must_use]
+pub fn merge(base: &Breadboard, ours: &Breadboard, theirs: &Breadboard) -> Merge {
+    let mut merger = Merger::default();
+
+    let breadboard = Breadboard {
+        imports: merger.list(
+            &base.imports,
+            &ours.imports,
+            &theirs.imports,
+            |path| path.to_string_lossy().into_owned(),
+            |_| unreachable!("imports are equal to their key"),
+            |_, _, ours, _| ours.clone(),
+        ),
+        places: merger.list(
+            &base.places,
+            &ours.places,
+            &theirs.places,
+            |place| place.name.clone(),
+            |place| Node::Place {
+                place: place.name.clone(),
+            },
+            |merger, base, ours, theirs| {
+                let node = Node::Place {
+                    place: ours.name.clone(),
+                };
+                merger.place(&node, base, ours, theirs)
+            },
+        ),
+        components: merger.list(
+            &base.components,
+            &ours.components,
+            &theirs.components,
+            |component| component.name.clone(),
+            |component| Node::Component {
+                component: component.name.clone(),
+            },
+            |merger, base, ours, theirs| {
+                let node = Node::Component {
+                    component: ours.name.clone(),
+                };
+                Component::new(merger.place(&node, base.map(|c| &**c), ours, theirs))
+            },
+        ),
+        flows: merger.list(
+            &base.flows,
+            &ours.flows,
+            &theirs.flows,
+            |flow| flow.name.clone(),
+            |flow| Node::Flow {
+                flow: flow.name.clone(),
+            },
+            Merger::flow,
+        ),
+    };
+
+    Merge {
+        breadboard,
+        conflicts: merger.conflicts,
+    }
+}
+
+/// Collects the conflicts while merging.
+#[derive(Default)]
+struct Merger {
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+    /// Merges lists of nodes, matched by their key.
+    ///
+    /// The merged list is in the order of our list, with the nodes added by them inserted after
+    /// the node that precedes them in their list. Nodes that exist on both sides are merged with
+    /// `merge`.
+    fn list<T: Clone + PartialEq, K: PartialEq>(
+        &mut self,
+        base: &[T],
+        ours: &[T],
+        theirs: &[T],
+        key: impl Fn(&T) -> K,
+        node: impl Fn(&T) -> Node,
+        merge: impl Fn(&mut Self, Option<&T>, &T, &T) -> T,
+    ) -> Vec<T> {
+        let mut merged = vec![];
+        for our in ours {
+            let k = key(our);
+
+            match (find(base, &key, &k), find(theirs, &key, &k)) {
+                (base, Some(their)) => merged.push(merge(self, base, our, their)),
+                (Some(base), None) => {
+                    // Removed by them.
+                    if our != base {
+                        self.conflict(node(our), Kind::Removed);
+                        merged.push(our.clone());
+                    }
+                }
+                (None, None) => merged.push(our.clone()),
+            }
+        }
+
+        // The key of the last of their nodes that is in the merged list.
+        let mut previous: Option<K> = None;
+        for their in theirs {
+            let k = key(their);
+
+            if find(ours, &key, &k).is_none() {
+                let added = match find(base, &key, &k) {
+                    None => true,
+                    Some(base) if their != base => {
+                        // Removed by us.
+                        self.conflict(node(their), Kind::Removed);
+                        true
+                    }
+                    Some(_) => false,
+                };
+
+                if added {
+                    let index = previous
+                        .as_ref()
+                        .and_then(|p| merged.iter().position(|v| key(v) == *p))
+                        .map_or(0, |index| index + 1);
+
+                    merged.insert(index, their.clone());
+                }
+            }
+
+            if merged.iter().any(|v| key(v) == k) {
+                previous = Some(k);
+            }
+        }
+
+        merged
+    }
+
+    /// Merges the field of a node.
+    ///
+    /// Without a base, the field must be the same on both sides.
+    fn field<T: Clone + PartialEq>(
+        &mut self,
+        node: &Node,
+        field: &str,
+        base: Option<&T>,
+        ours: &T,
+        theirs: &T,
+    ) -> T {
+        if ours == theirs || base == Some(theirs) {
+            return ours.clone();
+        }
+
+        if base == Some(ours) {
+            return theirs.clone();
+        }
+
+        self.conflict(
+            node.clone(),
+            Kind::Changed {
+                field: field.to_owned(),
+            },
+        );
+
+        ours.clone()
+    }
+
+    /// Merges the attributes of a node, one by one.
+    ///
+    /// Attributes keep our order, followed by the attributes only added by them.
+    fn attributes(
+        &mut self,
+        node: &Node,
+        base: Option<&Attributes>,
+        ours: &Attributes,
+        theirs: &Attributes,
+    ) -> Attributes {
+        let mut keys = vec![];
+        for key in ours
+            .keys()
+            .chain(theirs.keys())
+            .chain(base.into_iter().flat_map(Attributes::keys))
+        {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let value = self.field(
+                    node,
+                    &format!("@{key}"),
+                    base.map(|base| base.get(key)).as_ref(),
+                    &ours.get(key),
+                    &theirs.get(key),
+                );
+
+                value.map(|value| (key.clone(), value.clone()))
+            })
+            .collect()
+    }
+
+    fn place(&mut self, node: &Node, base: Option<&Place>, ours: &Place, theirs: &Place) -> Place {
+        let name = &ours.name;
+        let since = self.conflicts.len();
+        let base_items = base.map_or(&[][..], |b| &b.items);
+
+        let mut place = Place {
+            name: name.clone(),
+            description: self.field(
+                node,
+                "description",
+                base.map(|b| &b.description),
+                &ours.description,
+                &theirs.description,
+            ),
+            attributes: self.attributes(
+                node,
+                base.map(|b| &b.attributes),
+                &ours.attributes,
+                &theirs.attributes,
+            ),
+            items: self.list(
+                base_items,
+                &ours.items,
+                &theirs.items,
+                item_key,
+                |item| item_node(name, item),
+                |merger, base, ours, theirs| merger.item(name, base, ours, theirs),
+            ),
+            position: self.field(
+                node,
+                "position",
+                base.map(|b| &b.position),
+                &ours.position,
+                &theirs.position,
+            ),
+            sketch: self.field(
+                node,
+                "sketch",
+                base.map(|b| &b.sketch),
+                &ours.sketch,
+                &theirs.sketch,
+            ),
+            origin: ours.origin.clone(),
+        };
+
+        self.keep_parents(
+            name,
+            base_items,
+            &ours.items,
+            &theirs.items,
+            &mut place.items,
+        );
+        self.keep_parents(
+            name,
+            base_items,
+            &theirs.items,
+            &ours.items,
+            &mut place.items,
+        );
+
+        self.theirs(since, node, || {
+            let theirs = Place {
+                items: vec![],
+                ..theirs.clone()
+            };
+
+            print(&match node {
+                Node::Component { .. } => Breadboard {
+                    components: vec![Component::new(theirs)],
+                    ..Breadboard::default()
+                },
+                _ => Breadboard {
+                    places: vec![theirs],
+                    ..Breadboard::default()
+                },
+            })
+        });
+
+        place
+    }
+
+    /// Keeps the parents of the items added by one side, that the other side removed.
+    ///
+    /// An item is nested under the closest preceding item with a lower level, so without its
+    /// parent, it would end up nested under another item, or not at all.
+    fn keep_parents(
+        &mut self,
+        place: &str,
+        base: &[Item],
+        added: &[Item],
+        removed: &[Item],
+        merged: &mut Vec<Item>,
+    ) {
+        for (index, item) in added.iter().enumerate() {
+            if find(base, item_key, &item_key(item)).is_some() {
+                continue;
+            }
+
+            let mut child = index;
+            while let Some(parent) = parent(added, child) {
+                let key = item_key(&added[parent]);
+                let is_removed =
+                    find(base, item_key, &key).is_some() && find(removed, item_key, &key).is_none();
+
+                if !is_removed || merged.iter().any(|v| item_key(v) == key) {
+                    break;
+                }
+
+                self.conflict(item_node(place, &added[parent]), Kind::Removed);
+
+                let child_key = item_key(&added[child]);
+                let position = merged
+                    .iter()
+                    .position(|v| item_key(v) == child_key)
+                    .unwrap_or(merged.len());
+                merged.insert(position, added[parent].clone());
+
+                child = parent;
+            }
+        }
+    }
+
+    fn item(&mut self, place: &str, base: Option<&Item>, ours: &Item, theirs: &Item) -> Item {
+        let node = item_node(place, ours);
+        let since = self.conflicts.len();
+
+        let item = match (ours, theirs) {
+            (Item::Affordance(ours), Item::Affordance(theirs)) => {
+                let base = base.and_then(|base| match base {
+                    Item::Affordance(base) => Some(base),
+                    Item::Reference(_) => None,
+                });
+
+                Item::Affordance(self.affordance(&node, base, ours, theirs))
+            }
+            (Item::Reference(ours), Item::Reference(theirs)) => {
+                let base = base.and_then(|base| match base {
+                    Item::Reference(base) => Some(base),
+                    Item::Affordance(_) => None,
+                });
+
+                Item::Reference(Reference {
+                    name: ours.name.clone(),
+                    level: self.field(
+                        &node,
+                        "level",
+                        base.map(|b| &b.level),
+                        &ours.level,
+                        &theirs.level,
+                    ),
+                })
+            }
+            _ => unreachable!("items are matched by kind"),
+        };
+
+        // Items are printed within a place, without the line and the indentation of the place.
+        self.theirs(since, &node, || {
+            print(&Breadboard {
+                places: vec![Place {
+                    name: place.to_owned(),
+                    description: vec![],
+                    attributes: Attributes::default(),
+                    items: vec![theirs.clone()],
+                    position: None,
+                    sketch: None,
+                    origin: None,
+                }],
+                ..Breadboard::default()
+            })
+            .lines()
+            .skip(1)
+            .flat_map(|line| [line.strip_prefix("  ").unwrap_or(line), "\n"])
+            .collect()
+        });
+
+        item
+    }
+
+    fn affordance(
+        &mut self,
+        node: &Node,
+        base: Option<&Affordance>,
+        ours: &Affordance,
+        theirs: &Affordance,
+    ) -> Affordance {
+        Affordance {
+            name: ours.name.clone(),
+            description: self.field(
+                node,
+                "description",
+                base.map(|b| &b.description),
+                &ours.description,
+                &theirs.description,
+            ),
+            kind: self.field(
+                node,
+                "kind",
+                base.map(|b| &b.kind),
+                &ours.kind,
+                &theirs.kind,
+            ),
+            attributes: self.attributes(
+                node,
+                base.map(|b| &b.attributes),
+                &ours.attributes,
+                &theirs.attributes,
+            ),
+            connections: self.field(
+                node,
+                "connections",
+                base.map(|b| &b.connections),
+                &ours.connections,
+                &theirs.connections,
+            ),
+            level: self.field(
+                node,
+                "level",
+                base.map(|b| &b.level),
+                &ours.level,
+                &theirs.level,
+            ),
+        }
+    }
+
+    fn flow(&mut self, base: Option<&Flow>, ours: &Flow, theirs: &Flow) -> Flow {
+        let node = Node::Flow {
+            flow: ours.name.clone(),
+        };
+        let since = self.conflicts.len();
+
+        let flow = Flow {
+            name: ours.name.clone(),
+            description: self.field(
+                &node,
+                "description",
+                base.map(|b| &b.description),
+                &ours.description,
+                &theirs.description,
+            ),
+            steps: self.field(
+                &node,
+                "steps",
+                base.map(|b| &b.steps),
+                &ours.steps,
+                &theirs.steps,
+            ),
+        };
+
+        self.theirs(since, &node, || {
+            print(&Breadboard {
+                flows: vec![theirs.clone()],
+                ..Breadboard::default()
+            })
+        });
+
+        flow
+    }
+
+    fn conflict(&mut self, node: Node, kind: Kind) {
+        self.conflicts.push(Conflict {
+            node,
+            kind,
+            theirs: None,
+        });
+    }
+
+    /// Adds their version of the node to the conflicting fields of the node, reported since the
+    /// given number of conflicts.
+    fn theirs(&mut self, since: usize, node: &Node, print: impl FnOnce() -> String) {
+        let mut conflicts = self.conflicts[since..]
+            .iter_mut()
+            .filter(|c| c.node == *node && matches!(c.kind, Kind::Changed { .. }))
+            .peekable();
+
+        if conflicts.peek().is_none() {
+            return;
+        }
+
+        let theirs = print();
+        for conflict in conflicts {
+            conflict.theirs = Some(theirs.clone());
+        }
+    }
+}
+
+/// Returns the node of the list with the given key.
+fn find<'a, T, K: PartialEq>(list: &'a [T], key: impl Fn(&T) -> K, k: &K) -> Option<&'a T> {
+    list.iter().find(|v| key(v) == *k)
+}
+
+/// The key of an item, which keeps affordances and includes with the same name apart.
+#[derive(PartialEq)]
+enum ItemKey {
+    Affordance(String),
+    Include(String),
+}
+
+fn item_key(item: &Item) -> ItemKey {
+    match item {
+        Item::Affordance(affordance) => ItemKey::Affordance(affordance.name.clone()),
+        Item::Reference(reference) => ItemKey::Include(reference.name.clone()),
+    }
+}
+
+fn item_level(item: &Item) -> usize {
+    match item {
+        Item::Affordance(affordance) => affordance.level,
+        Item::Reference(reference) => reference.level,
+    }
+}
+
+/// Returns the index of the item that the item at the given index is nested under, if any.
+fn parent(items: &[Item], index: usize) -> Option<usize> {
+    let level = item_level(&items[index]);
+
+    items[..index]
+        .iter()
+        .rposition(|item| item_level(item) < level)
+}
+
+fn item_node(place: &str, item: &Item) -> Node {
+    match item {
+        Item::Affordance(affordance) => Node::Affordance {
+            place: place.to_owned(),
+            affordance: affordance.name.clone(),
+        },
+        Item::Reference(reference) => Node::Include {
+            place: place.to_owned(),
+            component: reference.name.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn merge_sources(base: &str, ours: &str, theirs: &str) -> (String, Vec<String>) {
+        let parse = |source| bnb_parser::parse(source).unwrap();
+        let merge = merge(&parse(base), &parse(ours), &parse(theirs));
+
+        (
+            bnb_parser::print(&merge.breadboard),
+            merge.conflicts.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn test_merge_independent_changes() {
+        let base = indoc! {"
+            place Home
+              Sign In -> Login
+              Help
+
+            place Login
+              Username
+              Password
+        "};
+
+        let ours = indoc! {"
+            place Home
+              Sign In -> Login
+              > Remember Me
+              Help -> Support
+
+            place Login
+              Username
+              Password
+
+            place Support
+        "};
+
+        let theirs = indoc! {"
+            place Home
+              Sign In -> Login
+              Help
+
+            place Login
+              Username
+              Password
+              Submit -> Home
+
+            place Register
+              Email
+        "};
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert!(conflicts.is_empty(), "{conflicts:?}");
+        assert_eq!(
+            merged,
+            indoc! {"
+                place Home
+                  Sign In -> Login
+                  > Remember Me
+                  Help -> Support
+
+                place Login
+                  Username
+                  Password
+                  Submit -> Home
+
+                place Register
+                  Email
+
+                place Support
+            "}
+        );
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = indoc! {"
+            place Home
+              Sign In -> Login
+              Help
+
+            place Login
+              Username
+        "};
+
+        let ours = indoc! {"
+            place Home
+              Sign In -> Auth
+              Help
+
+            place Login
+              Username
+              Password
+        "};
+
+        let theirs = indoc! {"
+            place Home
+              Sign In -> Registration
+              Help -> Support
+        "};
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert_eq!(
+            conflicts,
+            vec![
+                "affordance `Sign In` in `Home`: connections changed on both sides",
+                "place `Login`: changed on one side, removed on the other",
+            ]
+        );
+        assert_eq!(
+            merged,
+            indoc! {"
+                place Home
+                  Sign In -> Auth
+                  Help -> Support
+
+                place Login
+                  Username
+                  Password
+            "}
+        );
+    }
+
+    #[test]
+    fn test_merge_added_on_both_sides() {
+        let base = "place Home\n";
+        let ours = "place Home\n\nplace Help\n  Contact [link owner=support]\n";
+        let theirs = "place Home\n\nplace Help\n  Contact [link owner=docs]\n  FAQ\n";
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert_eq!(
+            conflicts,
+            vec!["affordance `Contact` in `Help`: @owner changed on both sides"]
+        );
+        assert_eq!(
+            merged,
+            "place Home\n\nplace Help\n  Contact [link owner=support]\n  FAQ\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_affordance_named_like_include() {
+        let base = "place Home\n\ncomponent Header\n";
+        let ours = "place Home\n  \"include Header\"\n\ncomponent Header\n";
+        let theirs = "place Home\n  include Header\n\ncomponent Header\n";
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged,
+            "place Home\n  include Header\n  \"include Header\"\n\ncomponent Header\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_nested_under_removed() {
+        let base = "place A\n  X\n  Y\n";
+        let ours = "place A\n  Y\n";
+        let theirs = "place A\n  X\n  > Z\n  Y\n";
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert_eq!(
+            conflicts,
+            vec!["affordance `X` in `A`: changed on one side, removed on the other"]
+        );
+        assert_eq!(merged, theirs);
+
+        // Removing the parent on their side instead.
+        let (merged, conflicts) = merge_sources(base, theirs, ours);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(merged, theirs);
+    }
+
+    #[test]
+    fn test_merge_conflict_keeps_theirs() {
+        let base = "place Home\n  Go -> A\n\nplace A\n\nflow Tour\n  Home: Go -> A\n";
+        let ours = "place Home\n  Go -> B\n\nplace A\n\nflow Tour\n  Home: Go -> B\n";
+        let theirs = "place Home\n  Go -> C\n  > Help\n\nplace A\n\nflow Tour\n  Home: Go -> C\n";
+
+        let parse = |source| bnb_parser::parse(source).unwrap();
+        let merge = merge(&parse(base), &parse(ours), &parse(theirs));
+        let theirs: Vec<_> = merge
+            .conflicts
+            .iter()
+            .map(|c| c.theirs.as_deref())
+            .collect();
+
+        assert_eq!(
+            theirs,
+            vec![Some("Go -> C\n"), Some("flow Tour\n  Home: Go -> C\n")]
+        );
+    }
+
+    #[test]
+    fn test_merge_removed_on_both_sides() {
+        let base = "place Home\n  Help\n\nplace Help\n";
+        let ours = "place Home\n";
+        let theirs = "place Home\n";
+
+        let (merged, conflicts) = merge_sources(base, ours, theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(merged, "place Home\n");
+    }
+}
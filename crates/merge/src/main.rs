@@ -0,0 +1,207 @@
+//! # Bread'n'Butter Merge Driver
+//!
+//! **Breadboards, without the merge conflicts.**
+//!
+//! The `bnb-merge` binary is a [git merge driver][driver] for `.bnb` files, which merges them
+//! structurally with [`bnb_merge::merge`]. To use it, register the driver, and assign it to the
+//! breadboard files of a repository:
+//!
+//! ```sh
+//! git config merge.bnb.name "Bread'n'Butter breadboard"
+//! git config merge.bnb.driver "bnb-merge %O %A %B"
+//! echo "*.bnb merge=bnb" >> .gitattributes
+//! ```
+//!
+//! The merged breadboard is written to our version of the file (`%A`), in the canonical layout of
+//! `bnb fmt`. If the merge has conflicts, our version of each conflicting change is written, the
+//! conflicts are listed in comments at the top of the file, along with their version of each
+//! conflicting node, and the driver exits with status 1, so git marks the file as conflicted.
+//!
+//! If any of the versions does not parse, or contains regular `//` comments (which the canonical
+//! layout does not preserve), the driver falls back to the line-based merge of `git merge-file`.
+//!
+//! [driver]: https://git-scm.com/docs/gitattributes#_defining_a_custom_merge_driver
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+use bnb_ast::Breadboard;
+
+fn main() -> ExitCode {
+    let args: Vec<PathBuf> = std::env::args_os().skip(1).map(PathBuf::from).collect();
+    let [base, ours, theirs] = args.as_slice() else {
+        eprintln!("usage: bnb-merge BASE OURS THEIRS");
+        return ExitCode::from(2);
+    };
+
+    match run(base, ours, theirs) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("bnb-merge: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Merges the files, and writes the result to our file.
+///
+/// Returns `true` if the files merged without conflicts.
+fn run(base: &Path, ours: &Path, theirs: &Path) -> io::Result<bool> {
+    let inputs = [
+        fs::read_to_string(base)?,
+        fs::read_to_string(ours)?,
+        fs::read_to_string(theirs)?,
+    ];
+
+    if inputs.iter().any(|input| bnb_parser::has_comments(input)) {
+        eprintln!("bnb-merge: falling back to a line-based merge, to keep the `//` comments");
+        return merge_file(base, ours, theirs);
+    }
+
+    let [Ok(base_breadboard), Ok(our_breadboard), Ok(their_breadboard)] =
+        inputs.each_ref().map(|input| parse(input))
+    else {
+        eprintln!("bnb-merge: falling back to a line-based merge");
+        return merge_file(base, ours, theirs);
+    };
+
+    let merge = bnb_merge::merge(&base_breadboard, &our_breadboard, &their_breadboard);
+
+    let mut output = String::new();
+    if !merge.is_clean() {
+        output.push_str("// Merge conflicts, resolved with our version of each change:\n");
+
+        for conflict in &merge.conflicts {
+            eprintln!("bnb-merge: conflict in {conflict}");
+            output.push_str(&format!("// - {conflict}\n"));
+
+            if let Some(theirs) = &conflict.theirs {
+                output.push_str("//   theirs:\n");
+                for line in theirs.lines().filter(|line| !line.is_empty()) {
+                    output.push_str(&format!("//     {line}\n"));
+                }
+            }
+        }
+
+        output.push('\n');
+    }
+
+    output.push_str(&bnb_parser::print(&merge.breadboard));
+    fs::write(ours, output)?;
+
+    Ok(merge.is_clean())
+}
+
+fn parse(input: &str) -> Result<Breadboard, bnb_parser::Error> {
+    bnb_parser::parse(input).inspect_err(|error| eprintln!("bnb-merge: {error}"))
+}
+
+/// Merges the files line by line, with conflict markers, using `git merge-file`.
+fn merge_file(base: &Path, ours: &Path, theirs: &Path) -> io::Result<bool> {
+    let status = Command::new("git")
+        .arg("merge-file")
+        .args([ours, base, theirs])
+        .status()?;
+
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    /// Writes the versions to a temporary directory, merges them, and returns our merged file.
+    fn merge(name: &str, base: &str, ours: &str, theirs: &str) -> (bool, String) {
+        let dir = std::env::temp_dir().join(format!("bnb-merge-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let paths = ["base.bnb", "ours.bnb", "theirs.bnb"].map(|file| dir.join(file));
+        for (path, contents) in paths.iter().zip([base, ours, theirs]) {
+            fs::write(path, contents).unwrap();
+        }
+
+        let clean = run(&paths[0], &paths[1], &paths[2]).unwrap();
+        let merged = fs::read_to_string(&paths[1]).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        (clean, merged)
+    }
+
+    #[test]
+    fn test_run() {
+        let (clean, merged) = merge(
+            "structural",
+            "place Home\n  Sign In -> Login\n\nplace Login\n",
+            "place Home\n  Sign In -> Login\n  Help -> Login\n\nplace Login\n",
+            "place Home\n  Sign In -> Login\n\nplace Login\n  Back -> Home\n",
+        );
+
+        assert!(clean);
+        assert_eq!(
+            merged,
+            indoc! {"
+                place Home
+                  Sign In -> Login
+                  Help -> Login
+
+                place Login
+                  Back -> Home
+            "}
+        );
+    }
+
+    #[test]
+    fn test_run_conflicts() {
+        let (clean, merged) = merge(
+            "conflicts",
+            "place Home\n  Go -> A\n\nplace A\n",
+            "place Home\n  Go -> B\n\nplace A\n",
+            "place Home\n  Go -> C\n\nplace A\n",
+        );
+
+        assert!(!clean);
+        assert_eq!(
+            merged,
+            indoc! {"
+                // Merge conflicts, resolved with our version of each change:
+                // - affordance `Go` in `Home`: connections changed on both sides
+                //   theirs:
+                //     Go -> C
+
+                place Home
+                  Go -> B
+
+                place A
+            "}
+        );
+    }
+
+    #[test]
+    fn test_run_keeps_comments() {
+        let (clean, merged) = merge(
+            "comments",
+            "place Home\n  Sign In -> Login\n\nplace Login\n",
+            "// The start.\nplace Home\n  Sign In -> Login\n\nplace Login\n",
+            "place Home\n  Sign In -> Login\n\nplace Login\n  Back -> Home\n",
+        );
+
+        assert!(clean);
+        assert_eq!(
+            merged,
+            indoc! {"
+                // The start.
+                place Home
+                  Sign In -> Login
+
+                place Login
+                  Back -> Home
+            "}
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Escape sequences of quoted strings.
+//!
+//! Quoted strings support the following escape sequences:
+//!
+//! - `\"`: A double quote.
+//! - `\\`: A backslash.
+//! - `\n`: A newline.
+//! - `\u{...}`: A Unicode scalar value of one to six hexadecimal digits, e.g. `\u{1F35E}`.
+//!
+//! Any other backslash sequence is an error.
+
+use crate::Error;
+
+/// Returns the contents of a quoted string (without the surrounding quotes) with all escape
+/// sequences resolved.
+pub(crate) fn unescape(text: &str) -> Result<String, Error> {
+    let mut out = String::with_capacity(text.len());
+
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let start = chars.as_str();
+        let invalid = |chars: &std::str::Chars<'_>| {
+            let len = start.len() - chars.as_str().len();
+            Error::InvalidEscape(format!("\\{}", &start[..len]))
+        };
+
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('n') => out.push('\n'),
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err(invalid(&chars));
+                }
+
+                let digits = chars.as_str();
+                let len = digits.find('}').ok_or_else(|| invalid(&chars))?;
+                let c = (1..=6)
+                    .contains(&len)
+                    .then(|| u32::from_str_radix(&digits[..len], 16).ok())
+                    .flatten()
+                    .and_then(char::from_u32);
+
+                chars = digits[len + 1..].chars();
+                out.push(c.ok_or_else(|| invalid(&chars))?);
+            }
+            _ => return Err(invalid(&chars)),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Returns the name as a quoted string, escaping quotes, backslashes and control characters.
+pub(crate) fn quote(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 2);
+
+    out.push('"');
+    for c in name.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if needs_escape(c) => out.push_str(&format!("\\u{{{:x}}}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+/// Returns `true` if the character cannot be written as-is, and therefore requires the name to be
+/// quoted.
+///
+/// Tabs are allowed, as they do not affect how a line is parsed.
+pub(crate) fn needs_escape(c: char) -> bool {
+    c.is_control() && c != '\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        #[rustfmt::skip]
+        let test_cases = vec![
+            (r"plain", Ok("plain")),
+            (r#"say \"hi\""#, Ok("say \"hi\"")),
+            (r"back\\slash", Ok("back\\slash")),
+            (r"new\nline", Ok("new\nline")),
+            (r"\u{1F35E} and \u{e9}", Ok("🍞 and é")),
+            (r"\t", Err(Error::InvalidEscape(r"\t".to_owned()))),
+            (r"trailing \", Err(Error::InvalidEscape(r"\".to_owned()))),
+            (r"\u12", Err(Error::InvalidEscape(r"\u1".to_owned()))),
+            (r"\u{}", Err(Error::InvalidEscape(r"\u{}".to_owned()))),
+            (r"\u{1234567}", Err(Error::InvalidEscape(r"\u{1234567}".to_owned()))),
+            (r"\u{D800}", Err(Error::InvalidEscape(r"\u{D800}".to_owned()))),
+            (r"\u{12", Err(Error::InvalidEscape(r"\u{".to_owned()))),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(unescape(input), expected.map(ToOwned::to_owned), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_quote() {
+        let test_cases = vec![
+            "plain",
+            "say \"hi\"",
+            "back\\slash",
+            "new\nline",
+            "bell\u{7}",
+            "-> , + -",
+        ];
+
+        for name in test_cases {
+            let quoted = quote(name);
+
+            assert!(!quoted.contains('\n'), "{quoted}");
+            assert_eq!(unescape(&quoted[1..quoted.len() - 1]).as_deref(), Ok(name));
+        }
+    }
+}
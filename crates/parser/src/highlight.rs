@@ -0,0 +1,139 @@
+//! Rendering of syntax highlighted DSL source, based on the [`tokenize`] token stream.
+
+use crate::lexer::{tokenize, NameKind, TokenKind};
+
+/// Renders DSL source with ANSI escape codes, for display in a terminal.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::highlight_ansi;
+///
+/// let output = highlight_ansi("place Home");
+///
+/// assert_eq!(output, "\x1b[1;35mplace\x1b[0m \x1b[1;34mHome\x1b[0m");
+/// ```
+#[must_use]
+pub fn highlight_ansi(input: &str) -> String {
+    render(input, |kind, text, out| match ansi_style(kind) {
+        Some(style) => out.push_str(&format!("\x1b[{style}m{text}\x1b[0m")),
+        None => out.push_str(text),
+    })
+}
+
+/// Renders DSL source as an HTML `<pre>` block.
+///
+/// Each token is wrapped in a `<span>` with a `bnb-` prefixed class named after its
+/// [`TokenKind::as_str`], e.g. `bnb-keyword`, so that the colors can be chosen with CSS.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::highlight_html;
+///
+/// let output = highlight_html("place Home\n  > Back -> Home");
+///
+/// assert_eq!(
+///     output,
+///     concat!(
+///         r#"<pre class="bnb"><code><span class="bnb-keyword">place</span> "#,
+///         r#"<span class="bnb-place">Home</span>"#,
+///         "\n  ",
+///         r#"<span class="bnb-level">&gt;</span> <span class="bnb-affordance">Back</span> "#,
+///         r#"<span class="bnb-arrow">-&gt;</span> <span class="bnb-target">Home</span>"#,
+///         "</code></pre>",
+///     )
+/// );
+/// ```
+#[must_use]
+pub fn highlight_html(input: &str) -> String {
+    let mut out = String::from(r#"<pre class="bnb"><code>"#);
+
+    out.push_str(&render(input, |kind, text, out| match kind {
+        Some(kind) => out.push_str(&format!(
+            r#"<span class="bnb-{}">{}</span>"#,
+            kind.as_str(),
+            escape_html(text)
+        )),
+        None => out.push_str(&escape_html(text)),
+    }));
+
+    out.push_str("</code></pre>");
+    out
+}
+
+/// Renders the input by passing every token, and the text between tokens, to `write`.
+fn render(input: &str, mut write: impl FnMut(Option<TokenKind>, &str, &mut String)) -> String {
+    let mut out = String::with_capacity(input.len());
+
+    let mut offset = 0;
+    for token in tokenize(input) {
+        if offset < token.span.start {
+            write(None, &input[offset..token.span.start], &mut out);
+        }
+
+        write(Some(token.kind), token.text(input), &mut out);
+        offset = token.span.end;
+    }
+
+    if offset < input.len() {
+        write(None, &input[offset..], &mut out);
+    }
+
+    out
+}
+
+/// Returns the SGR parameters used to render a token in a terminal.
+fn ansi_style(kind: Option<TokenKind>) -> Option<&'static str> {
+    let style = match kind? {
+        TokenKind::Keyword => "1;35",
+        TokenKind::Name(NameKind::Place | NameKind::Component | NameKind::Flow) => "1;34",
+        TokenKind::Name(
+            NameKind::Reference
+            | NameKind::Target
+            | NameKind::TargetAffordance
+            | NameKind::Coordinate
+            | NameKind::StepPlace,
+        ) => "34",
+        TokenKind::Name(NameKind::Affordance | NameKind::Area | NameKind::StepAffordance)
+        | TokenKind::Separator => return None,
+        TokenKind::QuotedString(_) => "32",
+        TokenKind::Level | TokenKind::Arrow | TokenKind::Pivot | TokenKind::AreaBracket => "36",
+        TokenKind::ConnectionLabel | TokenKind::Integer | TokenKind::AttributeValue => "33",
+        TokenKind::Annotation | TokenKind::AttributeKey => "35",
+        TokenKind::SketchPath | TokenKind::ImportPath => "4",
+        TokenKind::Comment => "2",
+        TokenKind::Description => "3;32",
+    };
+
+    Some(style)
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        let input = "/// <Start>\nplace Home\n  \"Go\" -> (x) Away\n";
+
+        insta::assert_snapshot!(highlight_html(input));
+        insta::assert_snapshot!(highlight_ansi(input).replace('\x1b', "\\e"));
+    }
+}
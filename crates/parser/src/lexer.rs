@@ -0,0 +1,723 @@
+//! Tokenization of DSL source into classified tokens, for syntax highlighting and editor tooling.
+//!
+//! Unlike [`parse`](crate::parse), the tokenizer never fails: incomplete or invalid lines, as they
+//! appear while typing, are tokenized as far as possible, and anything that cannot be classified
+//! is left out of the token stream.
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::{escape::unescape, ARROWS};
+
+/// A classified piece of DSL source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The classification of the token.
+    pub kind: TokenKind,
+
+    /// The byte range of the token in the input.
+    pub span: Range<usize>,
+}
+
+impl Token {
+    /// Returns the source text of the token.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the token was not produced by tokenizing `input`.
+    #[must_use]
+    pub fn text<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.clone()]
+    }
+
+    /// Returns the name held by a [`TokenKind::Name`] or [`TokenKind::QuotedString`] token, with
+    /// any surrounding quotes removed and escape sequences resolved.
+    ///
+    /// Quoted strings with invalid escape sequences are returned as written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bnb_parser::tokenize;
+    ///
+    /// let input = r#"place Home
+    ///   "Sign \"Up\"" -> Away"#;
+    /// let names: Vec<_> = tokenize(input)
+    ///     .iter()
+    ///     .filter_map(|token| token.name(input))
+    ///     .collect();
+    ///
+    /// assert_eq!(names, vec!["Home", "Sign \"Up\"", "Away"]);
+    /// ```
+    #[must_use]
+    pub fn name<'a>(&self, input: &'a str) -> Option<Cow<'a, str>> {
+        let text = self.text(input);
+
+        match self.kind {
+            TokenKind::Name(_) => Some(Cow::Borrowed(text)),
+            TokenKind::QuotedString(_) => {
+                let text = text.strip_prefix('"').unwrap_or(text);
+                let text = text.strip_suffix('"').unwrap_or(text);
+
+                Some(unescape(text).map_or(Cow::Borrowed(text), Cow::Owned))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The classification of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// One of `import`, `place`, `component`, `flow`, `include`, `position` or `sketch`.
+    Keyword,
+
+    /// An unquoted name.
+    Name(NameKind),
+
+    /// A name written as a quoted string, e.g. `"Sign Up -> Now"`. The span includes the quotes.
+    QuotedString(NameKind),
+
+    /// A `>` marking the nesting level of an affordance or `include`.
+    Level,
+
+    /// The arrow of a connection, one of [`ARROWS`](crate::ARROWS).
+    Arrow,
+
+    /// The `(label)` of a connection, including the parentheses.
+    ConnectionLabel,
+
+    /// The `[kind key=value]` annotation of an affordance, including the brackets.
+    Annotation,
+
+    /// The `@key` of a place or component attribute.
+    AttributeKey,
+
+    /// The value of a place or component attribute, following its key.
+    AttributeValue,
+
+    /// One of the `^`, `>`, `_` or `<` pivots of a position coordinate.
+    Pivot,
+
+    /// A (signed) integer of a position coordinate or sketch area.
+    Integer,
+
+    /// The `,` between position coordinates or sketch area coordinates, the `.` between the
+    /// place and affordance of a connection target, or the `:` between the place and affordance
+    /// of a flow step.
+    Separator,
+
+    /// The path of a `sketch`.
+    SketchPath,
+
+    /// The path of an `import`, usually a quoted string including the quotes.
+    ImportPath,
+
+    /// The `[` or `]` of a sketch area.
+    AreaBracket,
+
+    /// A `//` comment.
+    Comment,
+
+    /// A `///` description.
+    Description,
+}
+
+impl TokenKind {
+    /// Returns a short, kebab-case name of the kind, e.g. to be used as a CSS class.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Name(kind) => kind.as_str(),
+            Self::QuotedString(_) => "string",
+            Self::Level => "level",
+            Self::Arrow => "arrow",
+            Self::ConnectionLabel => "label",
+            Self::Annotation => "annotation",
+            Self::AttributeKey => "attribute",
+            Self::AttributeValue => "value",
+            Self::Pivot => "pivot",
+            Self::Integer => "integer",
+            Self::Separator => "separator",
+            Self::SketchPath => "path",
+            Self::ImportPath => "import",
+            Self::AreaBracket => "bracket",
+            Self::Comment => "comment",
+            Self::Description => "description",
+        }
+    }
+}
+
+/// What a [`TokenKind::Name`] or [`TokenKind::QuotedString`] names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameKind {
+    /// The name of a place, after `place`.
+    Place,
+
+    /// The name of a component, after `component`.
+    Component,
+
+    /// The name of an affordance.
+    Affordance,
+
+    /// The component referenced by an `include`.
+    Reference,
+
+    /// The place targeted by a connection.
+    Target,
+
+    /// The affordance targeted by a connection, after the `.` following the target place.
+    TargetAffordance,
+
+    /// The place a `position` coordinate is relative to.
+    Coordinate,
+
+    /// The affordance referenced by a sketch area.
+    Area,
+
+    /// The name of a flow, after `flow`.
+    Flow,
+
+    /// The place of a flow step, before the `:`.
+    StepPlace,
+
+    /// The affordance of a flow step, after the `:`.
+    StepAffordance,
+}
+
+impl NameKind {
+    /// Returns a short, kebab-case name of the kind.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Place => "place",
+            Self::Component => "component",
+            Self::Affordance => "affordance",
+            Self::Reference => "reference",
+            Self::Target => "target",
+            Self::TargetAffordance => "target-affordance",
+            Self::Coordinate => "coordinate",
+            Self::Area => "area",
+            Self::Flow => "flow",
+            Self::StepPlace => "step-place",
+            Self::StepAffordance => "step-affordance",
+        }
+    }
+}
+
+/// Splits DSL source into classified [`Token`]s, in source order.
+///
+/// Whitespace is not part of any token.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::{tokenize, NameKind, TokenKind};
+///
+/// let input = "place Home\n  Sign Up -> Registration";
+/// let tokens: Vec<_> = tokenize(input)
+///     .into_iter()
+///     .map(|token| (token.kind, &input[token.span]))
+///     .collect();
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         (TokenKind::Keyword, "place"),
+///         (TokenKind::Name(NameKind::Place), "Home"),
+///         (TokenKind::Name(NameKind::Affordance), "Sign Up"),
+///         (TokenKind::Arrow, "->"),
+///         (TokenKind::Name(NameKind::Target), "Registration"),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut lexer = Lexer::default();
+
+    let mut offset = 0;
+    for raw in input.split_inclusive('\n') {
+        let line = raw.trim_end_matches(['\n', '\r']);
+        lexer.line(offset, line);
+        offset += raw.len();
+    }
+
+    lexer.tokens
+}
+
+#[derive(Default)]
+struct Lexer {
+    tokens: Vec<Token>,
+
+    /// Whether the current line is part of a `sketch` block.
+    in_sketch: bool,
+
+    /// Whether the current line is a step of a `flow` block.
+    in_flow: bool,
+}
+
+impl Lexer {
+    fn push(&mut self, kind: TokenKind, span: Range<usize>) {
+        if !span.is_empty() {
+            self.tokens.push(Token { kind, span });
+        }
+    }
+
+    fn line(&mut self, start: usize, line: &str) {
+        let trimmed = line.trim_start();
+        let start = start + line.len() - trimmed.len();
+
+        if trimmed.starts_with("///") {
+            return self.push(TokenKind::Description, start..start + trimmed.len());
+        }
+
+        if trimmed.starts_with("//") {
+            return self.push(TokenKind::Comment, start..start + trimmed.len());
+        }
+
+        if trimmed.starts_with('@') {
+            return self.attribute(start, trimmed);
+        }
+
+        let word = &trimmed[..trimmed.find(char::is_whitespace).unwrap_or(trimmed.len())];
+        let rest = trimmed[word.len()..].trim_start();
+        let rest_start = start + trimmed.len() - rest.len();
+
+        let name = match word {
+            "place" => NameKind::Place,
+            "component" => NameKind::Component,
+            "flow" => NameKind::Flow,
+            "position" => {
+                self.push(TokenKind::Keyword, start..start + word.len());
+                return self.position(rest_start, rest);
+            }
+            "import" => {
+                self.in_sketch = false;
+                self.in_flow = false;
+                self.push(TokenKind::Keyword, start..start + word.len());
+                return self.push(TokenKind::ImportPath, rest_start..rest_start + rest.len());
+            }
+            "sketch" => {
+                self.in_sketch = true;
+                self.push(TokenKind::Keyword, start..start + word.len());
+                return self.push(TokenKind::SketchPath, rest_start..rest_start + rest.len());
+            }
+            _ if self.in_sketch && trimmed.starts_with('[') => return self.area(start, trimmed),
+            _ if self.in_flow => return self.step(start, trimmed),
+            _ => return self.item(start, trimmed),
+        };
+
+        self.in_sketch = false;
+        self.in_flow = name == NameKind::Flow;
+        self.push(TokenKind::Keyword, start..start + word.len());
+        self.name(rest, rest_start, name, &[]);
+    }
+
+    /// Tokenizes a `Place: Affordance` step of a flow.
+    fn step(&mut self, start: usize, line: &str) {
+        let end = self.name(line, start, NameKind::StepPlace, &[":"]);
+        let end = skip_whitespace(line, end);
+
+        if line[end..].starts_with(':') {
+            self.push(TokenKind::Separator, start + end..start + end + 1);
+
+            let offset = skip_whitespace(line, end + 1);
+            self.name(
+                &line[offset..],
+                start + offset,
+                NameKind::StepAffordance,
+                &[],
+            );
+        }
+    }
+
+    /// Tokenizes an `include` or an affordance, with its connections.
+    fn item(&mut self, start: usize, line: &str) {
+        let mut offset = 0;
+        if line.starts_with('>') {
+            for (level, c) in line.char_indices() {
+                match c {
+                    '>' => self.push(TokenKind::Level, start + level..start + level + 1),
+                    c if c.is_whitespace() => {}
+                    _ => break,
+                }
+
+                offset = level + c.len_utf8();
+            }
+        }
+
+        let item = &line[offset..];
+        let start = start + offset;
+
+        if let Some(rest) = item.strip_prefix("include") {
+            if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+                let name = rest.trim_start();
+                let name_start = start + item.len() - name.len();
+
+                self.push(TokenKind::Keyword, start..start + "include".len());
+                self.name(name, name_start, NameKind::Reference, &[]);
+                return;
+            }
+        }
+
+        let arrows = ARROWS.map(|(arrow, _)| arrow);
+        let affordance_end = [arrows.as_slice(), &["["]].concat();
+        let target_end = [arrows.as_slice(), &["."]].concat();
+        let arrow_at = |offset: usize| {
+            arrows
+                .iter()
+                .find(|arrow| item[offset..].starts_with(*arrow))
+        };
+
+        // A line starting with an arrow continues the connections of the previous affordance.
+        let mut end = 0;
+        if arrow_at(0).is_none() {
+            end = self.name(item, start, NameKind::Affordance, &affordance_end);
+            end = skip_whitespace(item, end);
+
+            if item[end..].starts_with('[') {
+                let annotation_end = annotation_end(item, end + 1);
+                self.push(TokenKind::Annotation, start + end..start + annotation_end);
+                end = annotation_end;
+            }
+        }
+
+        loop {
+            end = skip_whitespace(item, end);
+            let Some(arrow) = arrow_at(end) else {
+                break;
+            };
+
+            self.push(TokenKind::Arrow, start + end..start + end + arrow.len());
+            end = skip_whitespace(item, end + arrow.len());
+
+            if item[end..].starts_with('(') {
+                let label_end = connection_label_end(item, end + 1);
+                self.push(TokenKind::ConnectionLabel, start + end..start + label_end);
+                end = skip_whitespace(item, label_end);
+            }
+
+            end += self.name(&item[end..], start + end, NameKind::Target, &target_end);
+
+            if item[end..].starts_with('.') {
+                self.push(TokenKind::Separator, start + end..start + end + 1);
+                end = skip_whitespace(item, end + 1);
+                end += self.name(
+                    &item[end..],
+                    start + end,
+                    NameKind::TargetAffordance,
+                    &arrows,
+                );
+            }
+        }
+    }
+
+    /// Tokenizes a place or component attribute, e.g. `@owner payments`.
+    fn attribute(&mut self, start: usize, line: &str) {
+        let key_end = line.find(char::is_whitespace).unwrap_or(line.len());
+        self.push(TokenKind::AttributeKey, start..start + key_end);
+
+        let value = line[key_end..].trim();
+        let value_start = start + skip_whitespace(line, key_end);
+        self.push(
+            TokenKind::AttributeValue,
+            value_start..value_start + value.len(),
+        );
+    }
+
+    /// Tokenizes the coordinates of a `position`.
+    fn position(&mut self, start: usize, coordinates: &str) {
+        let mut offset = 0;
+
+        loop {
+            offset = skip_whitespace(coordinates, offset);
+            if coordinates[offset..].starts_with(['^', '>', '_', '<']) {
+                self.push(TokenKind::Pivot, start + offset..start + offset + 1);
+                offset = skip_whitespace(coordinates, offset + 1);
+            }
+
+            let rest = &coordinates[offset..];
+            if !rest.starts_with(|c: char| c == '+' || c == '-' || c == ',' || c.is_ascii_digit()) {
+                offset += self.name(rest, start + offset, NameKind::Coordinate, &["+", "-", ","]);
+                offset = skip_whitespace(coordinates, offset);
+            }
+
+            if let Some(end) = integer(coordinates, offset) {
+                self.push(TokenKind::Integer, start + offset..start + end);
+                offset = skip_whitespace(coordinates, end);
+            }
+
+            if !coordinates[offset..].starts_with(',') {
+                break;
+            }
+
+            self.push(TokenKind::Separator, start + offset..start + offset + 1);
+            offset += 1;
+        }
+    }
+
+    /// Tokenizes a sketch area, e.g. `[50,20 110,40] Sign Up`.
+    fn area(&mut self, start: usize, line: &str) {
+        self.push(TokenKind::AreaBracket, start..start + 1);
+
+        let mut offset = 1;
+        loop {
+            offset = skip_whitespace(line, offset);
+
+            if let Some(end) = integer(line, offset) {
+                self.push(TokenKind::Integer, start + offset..start + end);
+                offset = end;
+            } else if line[offset..].starts_with(',') {
+                self.push(TokenKind::Separator, start + offset..start + offset + 1);
+                offset += 1;
+            } else if line[offset..].starts_with(']') {
+                self.push(TokenKind::AreaBracket, start + offset..start + offset + 1);
+                offset += 1;
+                break;
+            } else {
+                return;
+            }
+        }
+
+        let offset = skip_whitespace(line, offset);
+        self.name(&line[offset..], start + offset, NameKind::Area, &[]);
+    }
+
+    /// Tokenizes a (potentially quoted) name at the start of the text, which ends at any of the
+    /// given delimiters.
+    ///
+    /// Returns the byte offset in the text at which the name ends.
+    fn name(&mut self, text: &str, start: usize, kind: NameKind, delimiters: &[&str]) -> usize {
+        if let Some(quoted) = text.strip_prefix('"') {
+            let end = closing_quote(quoted).map_or(text.len(), |close| close + 2);
+            self.push(TokenKind::QuotedString(kind), start..start + end);
+
+            return end;
+        }
+
+        let end = delimiters
+            .iter()
+            .filter_map(|delimiter| text.find(delimiter))
+            .min()
+            .unwrap_or(text.len());
+
+        let name = text[..end].trim_end();
+        self.push(TokenKind::Name(kind), start..start + name.len());
+
+        end
+    }
+}
+
+/// Returns the byte offset of the first unescaped quote in the text.
+fn closing_quote(text: &str) -> Option<usize> {
+    let mut escape = false;
+    for (offset, c) in text.char_indices() {
+        if c == '"' && !escape {
+            return Some(offset);
+        }
+        escape = c == '\\' && !escape;
+    }
+
+    None
+}
+
+/// Returns the offset after the connection label starting at the given offset (just after the
+/// opening parenthesis).
+fn connection_label_end(text: &str, offset: usize) -> usize {
+    let mut end = offset;
+    if let Some(quoted) = text[offset..].strip_prefix('"') {
+        end += closing_quote(quoted).map_or(quoted.len() + 1, |end| end + 2);
+    }
+
+    text[end..]
+        .find(')')
+        .map_or(text.len(), |close| end + close + 1)
+}
+
+/// Returns the offset after the affordance annotation starting at the given offset (just after the
+/// opening bracket).
+fn annotation_end(text: &str, offset: usize) -> usize {
+    let mut end = offset;
+    while let Some(c) = text[end..].chars().next() {
+        match c {
+            ']' => return end + 1,
+            '"' => {
+                let quoted = &text[end + 1..];
+                end += closing_quote(quoted).map_or(quoted.len() + 1, |close| close + 2);
+            }
+            c => end += c.len_utf8(),
+        }
+    }
+
+    end
+}
+
+/// Returns the offset after the (signed) integer starting at the given offset, if any.
+fn integer(text: &str, offset: usize) -> Option<usize> {
+    let mut end = offset;
+    if text[end..].starts_with(['+', '-']) {
+        end = skip_whitespace(text, end + 1);
+    }
+
+    let digits = text[end..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len() - end);
+
+    (digits > 0).then_some(end + digits)
+}
+
+fn skip_whitespace(text: &str, offset: usize) -> usize {
+    let rest = &text[offset.min(text.len())..];
+    text.len() - rest.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(input)
+            .into_iter()
+            .map(|token| (token.kind, &input[token.span]))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        use NameKind as N;
+        use TokenKind as T;
+
+        let input = indoc! {r#"
+            import "shared/header.bnb"
+
+            /// The start.
+            place Home
+              @owner Team A
+              include Header
+              // Not a description.
+              Sign Up -> (done) Away
+                      -> ("a )") "Far -> Away"
+                      -> Away."Log In" ~> Terms
+              >> include Footer
+              > Nested [toggle label="a ]"] -> Away
+
+              position < Away - 10, ^ "Far -> Away" + 2
+              sketch home.png
+                [0,0 10, 10] Sign Up
+
+            component "Say \"Hi\""
+
+            flow Sign Up
+              Home: Sign Up
+              "Far: Away" : Back
+        "#};
+
+        assert_eq!(
+            tokens(input),
+            vec![
+                (T::Keyword, "import"),
+                (T::ImportPath, r#""shared/header.bnb""#),
+                (T::Description, "/// The start."),
+                (T::Keyword, "place"),
+                (T::Name(N::Place), "Home"),
+                (T::AttributeKey, "@owner"),
+                (T::AttributeValue, "Team A"),
+                (T::Keyword, "include"),
+                (T::Name(N::Reference), "Header"),
+                (T::Comment, "// Not a description."),
+                (T::Name(N::Affordance), "Sign Up"),
+                (T::Arrow, "->"),
+                (T::ConnectionLabel, "(done)"),
+                (T::Name(N::Target), "Away"),
+                (T::Arrow, "->"),
+                (T::ConnectionLabel, r#"("a )")"#),
+                (T::QuotedString(N::Target), r#""Far -> Away""#),
+                (T::Arrow, "->"),
+                (T::Name(N::Target), "Away"),
+                (T::Separator, "."),
+                (T::QuotedString(N::TargetAffordance), r#""Log In""#),
+                (T::Arrow, "~>"),
+                (T::Name(N::Target), "Terms"),
+                (T::Level, ">"),
+                (T::Level, ">"),
+                (T::Keyword, "include"),
+                (T::Name(N::Reference), "Footer"),
+                (T::Level, ">"),
+                (T::Name(N::Affordance), "Nested"),
+                (T::Annotation, r#"[toggle label="a ]"]"#),
+                (T::Arrow, "->"),
+                (T::Name(N::Target), "Away"),
+                (T::Keyword, "position"),
+                (T::Pivot, "<"),
+                (T::Name(N::Coordinate), "Away"),
+                (T::Integer, "- 10"),
+                (T::Separator, ","),
+                (T::Pivot, "^"),
+                (T::QuotedString(N::Coordinate), r#""Far -> Away""#),
+                (T::Integer, "+ 2"),
+                (T::Keyword, "sketch"),
+                (T::SketchPath, "home.png"),
+                (T::AreaBracket, "["),
+                (T::Integer, "0"),
+                (T::Separator, ","),
+                (T::Integer, "0"),
+                (T::Integer, "10"),
+                (T::Separator, ","),
+                (T::Integer, "10"),
+                (T::AreaBracket, "]"),
+                (T::Name(N::Area), "Sign Up"),
+                (T::Keyword, "component"),
+                (T::QuotedString(N::Component), r#""Say \"Hi\"""#),
+                (T::Keyword, "flow"),
+                (T::Name(N::Flow), "Sign Up"),
+                (T::Name(N::StepPlace), "Home"),
+                (T::Separator, ":"),
+                (T::Name(N::StepAffordance), "Sign Up"),
+                (T::QuotedString(N::StepPlace), r#""Far: Away""#),
+                (T::Separator, ":"),
+                (T::Name(N::StepAffordance), "Back"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_incomplete() {
+        use NameKind as N;
+        use TokenKind as T;
+
+        let test_cases = vec![
+            ("place", vec![(T::Keyword, "place")]),
+            (
+                "  Sign Up -> ",
+                vec![(T::Name(N::Affordance), "Sign Up"), (T::Arrow, "->")],
+            ),
+            (
+                r#"  "Sign Up -> ("#,
+                vec![(T::QuotedString(N::Affordance), r#""Sign Up -> ("#)],
+            ),
+            (
+                "  position 10, ^",
+                vec![
+                    (T::Keyword, "position"),
+                    (T::Integer, "10"),
+                    (T::Separator, ","),
+                    (T::Pivot, "^"),
+                ],
+            ),
+            ("  [0,0 10", vec![(T::Annotation, "[0,0 10")]),
+            (
+                "  Username [input",
+                vec![
+                    (T::Name(N::Affordance), "Username"),
+                    (T::Annotation, "[input"),
+                ],
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(tokens(input), expected, "{input:?}");
+        }
+    }
+}
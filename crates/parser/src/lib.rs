@@ -53,6 +53,55 @@
 //! After parsing, the [`Breadboard`] structure will contain [`Place`] and [`Component`] instances
 //! corresponding to these descriptions, which can then be used programmatically.
 //!
+//! ## Printing
+//!
+//! The inverse operation is available through [`print`], which turns a [`Breadboard`] back into
+//! its (canonically formatted) DSL representation.
+//!
+//! ## Imports
+//!
+//! A breadboard can be split across multiple files, by importing other files at the top level,
+//! e.g. `import "auth.bnb"`. [`parse`] only records the imported paths in
+//! [`Breadboard::imports`], use [`load`] to read a file from a [`FileSystem`] and merge all of its
+//! imports into a single breadboard.
+//!
+//! ## Connections
+//!
+//! An affordance connects to other places with one of the [`ARROWS`], each denoting a different
+//! [`ConnectionKind`]: `->` navigates, `<-` returns to a previous place, `~>` opens a modal, and
+//! `=>` submits data.
+//!
+//! ## Flows
+//!
+//! A `flow` block describes a named user journey through the breadboard, as one `Place:
+//! Affordance` step per line, e.g. `Registration: Sign Up`. Place names containing a `:` need to
+//! be quoted. Validation checks that every step follows a connection of the previous step.
+//!
+//! ## Attributes
+//!
+//! Places and components can hold key/value metadata, written as `@key value` lines directly
+//! after their name, e.g. `@owner payments` or `@status in-progress`. The value spans the rest of
+//! the line, and can be omitted for flag-like attributes.
+//!
+//! ## Annotations
+//!
+//! An affordance name can be followed by an annotation in square brackets, holding the kind of the
+//! affordance and any number of `key=value` attributes, e.g. `Username [input placeholder="Your
+//! name"]`. Both the kind and the attributes are optional, and values containing whitespace or
+//! `]` are written as quoted strings.
+//!
+//! ## Quoting
+//!
+//! Names can be written as double-quoted strings, e.g. `"Sign Up -> Now"`, to include characters
+//! that would otherwise be parsed as syntax. Quoted strings support the `\"`, `\\`, `\n` and
+//! `\u{...}` escape sequences, and [`print`] quotes and escapes names wherever needed.
+//!
+//! ## Highlighting
+//!
+//! For syntax highlighting and editor tooling, [`tokenize`] splits the source into classified
+//! [`Token`]s without failing on incomplete input. [`highlight_ansi`] and [`highlight_html`] render
+//! those tokens for terminals and web pages.
+//!
 //! ## Error Handling
 //!
 //! If parsing fails, a descriptive [`Error`] enum variant is returned.
@@ -64,11 +113,32 @@ use std::{
 };
 
 use bnb_ast::{
-    Affordance, Area, Breadboard, Component, Connection, Coordinate, Item, Pivot, Place, Position,
-    Reference, Sketch,
+    Affordance, Area, Attributes, Breadboard, Component, Connection, ConnectionKind, Coordinate,
+    Flow, Item, Pivot, Place, Position, Reference, Sketch, Step,
 };
 use tracing::instrument;
 
+mod escape;
+mod highlight;
+mod lexer;
+mod loader;
+mod printer;
+
+pub use highlight::{highlight_ansi, highlight_html};
+pub use lexer::{tokenize, NameKind, Token, TokenKind};
+pub use loader::{load, FileSystem, LoadError, OsFileSystem};
+pub use printer::{has_comments, print, print_name, print_name_as};
+
+/// The arrows that start a connection, and the [`ConnectionKind`] each of them denotes.
+///
+/// Unquoted affordance and target names end at any of these arrows.
+pub const ARROWS: [(&str, ConnectionKind); 4] = [
+    ("->", ConnectionKind::Navigate),
+    ("<-", ConnectionKind::Return),
+    ("~>", ConnectionKind::Modal),
+    ("=>", ConnectionKind::Data),
+];
+
 /// Parses a string input to create a [`Breadboard`] structure.
 ///
 /// # Errors
@@ -84,24 +154,164 @@ use tracing::instrument;
 /// let breadboard = parse(input).unwrap();
 /// ```
 ///
-#[instrument(skip_all)]
 pub fn parse(input: &str) -> Result<Breadboard, Error> {
-    let mut chars = input.trim().chars();
+    parse_located(input).map_err(|located| located.error)
+}
+
+/// Parses a string input to create a [`Breadboard`] structure, reporting the location of any
+/// error.
+///
+/// This is useful for tools such as editors, which need to point at the offending part of the
+/// input.
+///
+/// # Errors
+///
+/// Returns a [`LocatedError`] if parsing of the string fails to produce a valid AST.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::{parse_located, Error};
+///
+/// let input = "/// The start.\nbogus Home";
+/// let error = parse_located(input).unwrap_err();
+///
+/// assert_eq!(error.error, Error::UnexpectedToken("bogus".to_owned()));
+/// assert_eq!(&input[error.offset..], "bogus Home");
+/// ```
+#[instrument(skip_all)]
+pub fn parse_located(input: &str) -> Result<Breadboard, LocatedError> {
+    let trimmed = input.trim();
+    let start = input.len() - input.trim_start().len();
+
+    let mut chars = trimmed.chars();
+    parse_breadboard(&mut chars).map_err(|error| LocatedError {
+        error,
+        offset: start + trimmed.len() - chars.as_str().len(),
+    })
+}
+
+fn parse_breadboard(chars: &mut Chars<'_>) -> Result<Breadboard, Error> {
+    let mut imports = vec![];
     let mut places = vec![];
     let mut components = vec![];
+    let mut flows = vec![];
 
     loop {
-        let description = parse_comment(&mut chars);
-
-        match parse_word(&mut chars) {
-            "place" => places.push(parse_place(&mut chars, description)?),
-            "component" => components.push(parse_component(&mut chars, description)?),
+        let description = parse_comment(chars);
+
+        // Only consume the keyword if it is valid, so that errors point at the unexpected token.
+        let mut ch = chars.clone();
+        match parse_word(&mut ch) {
+            "place" => {
+                *chars = ch;
+                places.push(parse_place(chars, description)?);
+            }
+            "component" => {
+                *chars = ch;
+                components.push(parse_component(chars, description)?);
+            }
+            "import" => {
+                *chars = ch;
+                imports.push(parse_import(chars)?);
+            }
+            "flow" => {
+                *chars = ch;
+                flows.push(parse_flow(chars, description)?);
+            }
             "" => break,
             v => return Err(Error::UnexpectedToken(v.to_owned())),
         }
     }
 
-    Ok(Breadboard { places, components })
+    Ok(Breadboard {
+        imports,
+        places,
+        components,
+        flows,
+    })
+}
+
+/// Parses the path of an `import`, which is usually written as a quoted string.
+#[instrument(level = "debug", skip_all)]
+fn parse_import(chars: &mut Chars<'_>) -> Result<PathBuf, Error> {
+    parse_while(chars, |c| c.is_whitespace() && c != '\n');
+
+    let path = parse_name_line(chars)?;
+    if path.is_empty() {
+        return Err(Error::MissingImportPath);
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+#[instrument(skip_all)]
+fn parse_flow(chars: &mut Chars<'_>, description: Vec<String>) -> Result<Flow, Error> {
+    skip_whitespace(chars);
+
+    let name = parse_name_line(chars)?;
+    if name.is_empty() {
+        return Err(Error::MissingFlowName);
+    }
+
+    let mut steps = vec![];
+    loop {
+        skip_whitespace(chars);
+
+        // Leave any description of the next top-level item for `parse_breadboard`.
+        let mut ch = chars.clone();
+        drop(parse_comment(&mut ch));
+        if starts_with_keyword(ch.as_str()) {
+            break;
+        }
+
+        drop(parse_comment(chars));
+        steps.push(parse_step(chars)?);
+    }
+
+    Ok(Flow {
+        name,
+        description,
+        steps,
+    })
+}
+
+/// Parses a `Place: Affordance` flow step. Unquoted place names end at the first `:`.
+#[instrument(level = "debug", skip_all)]
+fn parse_step(chars: &mut Chars<'_>) -> Result<Step, Error> {
+    let place = if chars.clone().next() == Some('"') {
+        parse_quoted_string(chars)?
+    } else {
+        parse_until(chars, ":\n").trim().to_owned()
+    };
+
+    parse_while(chars, |c| c.is_whitespace() && c != '\n');
+    if place.is_empty() || chars.next() != Some(':') {
+        return Err(Error::InvalidFlowStep(place));
+    }
+
+    parse_while(chars, |c| c.is_whitespace() && c != '\n');
+    let affordance = parse_name_line(chars)?;
+    if affordance.is_empty() {
+        return Err(Error::MissingStepAffordance(place));
+    }
+
+    Ok(Step { place, affordance })
+}
+
+/// Returns `true` at the end of the input, or if the input starts with a top-level keyword.
+fn starts_with_keyword(str: &str) -> bool {
+    str.is_empty()
+        || ["place", "component", "import", "flow"]
+            .into_iter()
+            .any(|keyword| starts_with_word(str, keyword))
+}
+
+/// Returns `true` if the input starts with the word, as a whole word like [`parse_word`] parses
+/// it, so that e.g. `placeholder` does not start with `place`.
+fn starts_with_word(str: &str, word: &str) -> bool {
+    str.strip_prefix(word)
+        .is_some_and(|rest| rest.chars().next().is_none_or(char::is_whitespace))
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -143,7 +353,7 @@ fn parse_component(chars: &mut Chars<'_>, description: Vec<String>) -> Result<Co
 fn parse_place(chars: &mut Chars<'_>, description: Vec<String>) -> Result<Place, Error> {
     skip_whitespace(chars);
 
-    let name = parse_line(chars).to_owned();
+    let name = parse_name_line(chars)?;
     if name.is_empty() {
         return Err(Error::MissingPlaceName);
     }
@@ -151,12 +361,42 @@ fn parse_place(chars: &mut Chars<'_>, description: Vec<String>) -> Result<Place,
     Ok(Place {
         name,
         description,
+        attributes: parse_attributes(chars)?,
         items: parse_items(chars)?,
         position: parse_position(chars)?,
         sketch: parse_sketch(chars)?,
+        origin: None,
     })
 }
 
+/// Parses the `@key value` attribute lines directly following the name of a place or component.
+#[instrument(level = "debug", skip_all)]
+fn parse_attributes(chars: &mut Chars<'_>) -> Result<Attributes, Error> {
+    let mut attributes = Attributes::new();
+
+    loop {
+        skip_whitespace(chars);
+
+        if chars.clone().next() != Some('@') {
+            return Ok(attributes);
+        }
+        chars.next();
+
+        let key = parse_word(chars).to_owned();
+        if key.is_empty() {
+            return Err(Error::MissingAttributeKey);
+        }
+
+        parse_while(chars, |c| c.is_whitespace() && c != '\n');
+        let value = parse_name_line(chars)?;
+
+        if attributes.contains_key(&key) {
+            return Err(Error::DuplicateAttribute(key));
+        }
+        attributes.insert(key, value);
+    }
+}
+
 #[instrument(level = "debug", skip_all)]
 fn parse_position(chars: &mut Chars<'_>) -> Result<Option<Position>, Error> {
     skip_whitespace(chars);
@@ -246,7 +486,7 @@ fn parse_coordinate(chars: &mut Chars<'_>) -> Result<Option<Coordinate>, Error>
     // If not, we check if there's any valid "unquoted string" character (e.g. anything except `+`,
     // `-`, a newline, or a digit character), and take those as being an unquoted string.
     let place = (c == '"')
-        .then(|| parse_quoted_string(chars).map(ToOwned::to_owned))
+        .then(|| parse_quoted_string(chars))
         .transpose()?
         .or_else(|| {
             (c != '+' && c != '-' && c != '\n' && c != ',' && !c.is_ascii_digit())
@@ -304,7 +544,8 @@ fn parse_sketch(chars: &mut Chars<'_>) -> Result<Option<Sketch>, Error> {
     while chars.clone().next() == Some('[') {
         let mut area = parse_area(chars)?;
 
-        area.affordance = parse_line(chars).trim().to_owned();
+        parse_while(chars, |c| c.is_whitespace() && c != '\n');
+        area.affordance = parse_name_line(chars)?;
         if area.affordance.is_empty() {
             return Err(Error::SketchAreaMissingAffordance);
         }
@@ -421,7 +662,7 @@ fn parse_reference(chars: &mut Chars<'_>) -> Result<Option<Reference>, Error> {
     let _ = parse_word(chars);
     skip_whitespace(chars);
 
-    let name = parse_line(chars).to_owned();
+    let name = parse_name_line(chars)?;
     if name.is_empty() {
         return Err(Error::MissingComponentReference);
     }
@@ -436,11 +677,9 @@ fn parse_affordance(chars: &mut Chars<'_>) -> Result<Option<Affordance>, Error>
     let mut ch = chars.clone();
     drop(parse_comment(&mut ch));
     let str = ch.as_str();
-    if str.is_empty()
-        || str.starts_with("place")
-        || str.starts_with("component")
-        || str.starts_with("sketch")
-        || str.starts_with("position")
+    if starts_with_keyword(str)
+        || starts_with_word(str, "sketch")
+        || starts_with_word(str, "position")
     {
         return Ok(None);
     }
@@ -449,7 +688,7 @@ fn parse_affordance(chars: &mut Chars<'_>) -> Result<Option<Affordance>, Error>
 
     let level = parse_level(chars);
 
-    let name = parse_affordance_or_target_name(chars)?.to_owned();
+    let name = parse_affordance_or_target_name(chars)?;
 
     // If there is no name, it means we've reached the end of the board.
     //
@@ -459,36 +698,95 @@ fn parse_affordance(chars: &mut Chars<'_>) -> Result<Option<Affordance>, Error>
         return Ok(None);
     }
 
+    parse_while(chars, |c| c.is_whitespace() && c != '\n');
+    let (kind, attributes) = parse_annotation(chars)?;
+
     Ok(Some(Affordance {
         name,
         description,
+        kind,
+        attributes,
         connections: parse_connections(chars)?,
         level,
     }))
 }
 
+/// Parses the optional `[kind key=value ...]` annotation following an affordance name.
+#[instrument(level = "trace", skip_all)]
+fn parse_annotation(chars: &mut Chars<'_>) -> Result<(Option<String>, Attributes), Error> {
+    let mut kind = None;
+    let mut attributes = Attributes::new();
+
+    if chars.clone().next() != Some('[') {
+        return Ok((kind, attributes));
+    }
+    chars.next();
+
+    loop {
+        parse_while(chars, |c| c.is_whitespace() && c != '\n');
+
+        match chars.clone().next() {
+            Some(']') => {
+                chars.next();
+                return Ok((kind, attributes));
+            }
+            None | Some('\n') => return Err(Error::UnterminatedAnnotation),
+            _ => {}
+        }
+
+        let key = parse_while(chars, |c| !c.is_whitespace() && !"=]\"".contains(c)).to_owned();
+
+        // The kind is the only entry without a value, and must come first.
+        if chars.clone().next() != Some('=') {
+            if key.is_empty() || kind.is_some() || !attributes.is_empty() {
+                return Err(Error::InvalidAnnotation(key));
+            }
+
+            kind = Some(key);
+            continue;
+        }
+        chars.next();
+
+        if key.is_empty() {
+            return Err(Error::InvalidAnnotation("=".to_owned()));
+        }
+
+        let value = if chars.clone().next() == Some('"') {
+            parse_quoted_string(chars)?
+        } else {
+            parse_while(chars, |c| !c.is_whitespace() && c != ']').to_owned()
+        };
+
+        if attributes.contains_key(&key) {
+            return Err(Error::DuplicateAttribute(key));
+        }
+        attributes.insert(key, value);
+    }
+}
+
 #[instrument(level = "trace", skip_all)]
 fn parse_connections(chars: &mut Chars<'_>) -> Result<Vec<Connection>, Error> {
     let mut connections = vec![];
     while chars.clone().next().is_some() {
         skip_whitespace(chars);
 
-        if !chars.as_str().starts_with("->") {
+        let Some(kind) = parse_arrow(chars) else {
             break;
-        }
+        };
 
-        chars.next();
-        chars.next();
         skip_whitespace(chars);
 
         // description
         let description = (chars.clone().next() == Some('('))
             .then(|| parse_connection_description(chars))
             .transpose()?;
-        let target_place = parse_affordance_or_target_name(chars)?.to_owned();
+        parse_while(chars, |c| c.is_whitespace() && c != '\n');
+        let (target_place, target_affordance) = parse_connection_target(chars)?;
 
         connections.push(Connection {
             target_place,
+            target_affordance,
+            kind,
             description,
         });
     }
@@ -496,6 +794,20 @@ fn parse_connections(chars: &mut Chars<'_>) -> Result<Vec<Connection>, Error> {
     Ok(connections)
 }
 
+/// Parses the arrow that starts a connection, if any.
+#[instrument(level = "trace", skip_all)]
+fn parse_arrow(chars: &mut Chars<'_>) -> Option<ConnectionKind> {
+    let str = chars.as_str();
+    let (arrow, kind) = ARROWS.iter().find(|(arrow, _)| str.starts_with(arrow))?;
+
+    *chars = str[arrow.len()..].chars();
+    Some(*kind)
+}
+
+fn starts_with_arrow(str: &str) -> bool {
+    ARROWS.iter().any(|(arrow, _)| str.starts_with(arrow))
+}
+
 #[instrument(level = "trace", skip_all)]
 fn parse_level(chars: &mut Chars<'_>) -> usize {
     // Don't do any implicit trimming, the first character should be a "level" character.
@@ -509,23 +821,62 @@ fn parse_level(chars: &mut Chars<'_>) -> usize {
     str.matches('>').count()
 }
 
+/// Parses a (potentially quoted) affordance name. Unquoted names end at an arrow, or at the `[`
+/// of an annotation.
 #[instrument(level = "trace", skip_all)]
-fn parse_affordance_or_target_name<'a>(chars: &'a mut Chars<'_>) -> Result<&'a str, Error> {
+fn parse_affordance_or_target_name(chars: &mut Chars<'_>) -> Result<String, Error> {
     let str = chars.as_str();
 
     if let Some('"') = chars.clone().next() {
         return parse_quoted_string(chars);
     }
 
-    while chars.clone().next().is_some_and(|c| c != '\n') {
-        if chars.as_str().starts_with("->") {
+    while chars.clone().next().is_some_and(|c| c != '\n' && c != '[') {
+        if starts_with_arrow(chars.as_str()) {
             break;
         }
 
         chars.next();
     }
 
-    Ok(str[..str.len() - chars.as_str().len()].trim())
+    Ok(str[..str.len() - chars.as_str().len()].trim().to_owned())
+}
+
+/// Parses the target of a connection: a place, optionally followed by a `.` and an affordance
+/// within that place, e.g. `Registration.Password`.
+///
+/// Both parts can be quoted. An unquoted place name ends at the first `.`, so place names
+/// containing a `.` need to be quoted.
+#[instrument(level = "trace", skip_all)]
+fn parse_connection_target(chars: &mut Chars<'_>) -> Result<(String, Option<String>), Error> {
+    let place = if chars.clone().next() == Some('"') {
+        parse_quoted_string(chars)?
+    } else {
+        let str = chars.as_str();
+        while chars
+            .clone()
+            .next()
+            .is_some_and(|c| c != '\n' && c != '.' && !starts_with_arrow(chars.as_str()))
+        {
+            chars.next();
+        }
+
+        str[..str.len() - chars.as_str().len()].trim().to_owned()
+    };
+
+    if chars.clone().next() != Some('.') {
+        return Ok((place, None));
+    }
+
+    chars.next();
+    parse_while(chars, |c| c.is_whitespace() && c != '\n');
+
+    let affordance = parse_affordance_or_target_name(chars)?;
+    if affordance.is_empty() {
+        return Err(Error::MissingConnectionAffordance);
+    }
+
+    Ok((place, Some(affordance)))
 }
 
 #[instrument(level = "trace", skip_all)]
@@ -536,7 +887,7 @@ fn parse_connection_description(chars: &mut Chars<'_>) -> Result<String, Error>
 
     let start = chars.as_str();
     let desc = if let Some('"') = chars.clone().next() {
-        parse_quoted_string(chars)?.to_owned()
+        parse_quoted_string(chars)?
     } else {
         while chars.clone().next().is_some_and(|c| c != '\n' && c != ')') {
             chars.next();
@@ -553,8 +904,29 @@ fn parse_connection_description(chars: &mut Chars<'_>) -> Result<String, Error>
     Ok(desc)
 }
 
+/// Parses a name that spans the rest of the line, such as a place name, which is either written
+/// as-is (ignoring trailing whitespace) or as a quoted string.
 #[instrument(level = "trace", skip_all)]
-fn parse_quoted_string<'a>(chars: &'a mut Chars<'_>) -> Result<&'a str, Error> {
+fn parse_name_line(chars: &mut Chars<'_>) -> Result<String, Error> {
+    if chars.clone().next() != Some('"') {
+        return Ok(parse_line(chars).trim_end().to_owned());
+    }
+
+    let name = parse_quoted_string(chars)?;
+
+    // Nothing but whitespace may follow the closing quote.
+    let mut ch = chars.clone();
+    let rest = parse_line(&mut ch).trim();
+    if !rest.is_empty() {
+        return Err(Error::UnexpectedToken(rest.to_owned()));
+    }
+
+    Ok(name)
+}
+
+/// Parses a quoted string, resolving its escape sequences.
+#[instrument(level = "trace", skip_all)]
+fn parse_quoted_string(chars: &mut Chars<'_>) -> Result<String, Error> {
     match chars.next() {
         Some('"') => (),
         _ => return Err(Error::ExpectedQuotedString),
@@ -567,7 +939,7 @@ fn parse_quoted_string<'a>(chars: &'a mut Chars<'_>) -> Result<&'a str, Error> {
         if c == '"' && !escape {
             let end = chars.as_str();
             chars.next(); // Consume the closing quote
-            return Ok(&start[..start.len() - end.len()]);
+            return escape::unescape(&start[..start.len() - end.len()]);
         }
         escape = c == '\\' && !escape;
         chars.next();
@@ -627,6 +999,17 @@ fn skip_whitespace(chars: &mut Chars<'_>) {
     }
 }
 
+/// An [`Error`], along with the location in the input at which it occurred.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{error}")]
+pub struct LocatedError {
+    /// The parse error.
+    pub error: Error,
+
+    /// The byte offset into the input at which parsing stopped.
+    pub offset: usize,
+}
+
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
 pub enum Error {
     #[error("missing place name")]
@@ -635,6 +1018,18 @@ pub enum Error {
     #[error("missing component name")]
     MissingComponentName,
 
+    #[error("missing import path")]
+    MissingImportPath,
+
+    #[error("missing flow name")]
+    MissingFlowName,
+
+    #[error("invalid flow step, expected `Place: Affordance`: {0}")]
+    InvalidFlowStep(String),
+
+    #[error("missing affordance in flow step of `{0}`")]
+    MissingStepAffordance(String),
+
     #[error("missing component reference")]
     MissingComponentReference,
 
@@ -647,12 +1042,30 @@ pub enum Error {
     #[error("unterminated quoted string")]
     UnterminatedQuotedString,
 
+    #[error("invalid escape sequence: {0}")]
+    InvalidEscape(String),
+
     #[error("expected connection description")]
     ExpectedConnectionDescription,
 
     #[error("unterminated connection description")]
     UnterminatedConnectionDescription,
 
+    #[error("missing attribute key after `@`")]
+    MissingAttributeKey,
+
+    #[error("duplicate attribute: {0}")]
+    DuplicateAttribute(String),
+
+    #[error("unterminated affordance annotation")]
+    UnterminatedAnnotation,
+
+    #[error("invalid affordance annotation: {0}")]
+    InvalidAnnotation(String),
+
+    #[error("missing affordance after `.` in connection target")]
+    MissingConnectionAffordance,
+
     #[error("invalid sketch path: {0}")]
     InvalidSketchPath(String),
 
@@ -752,6 +1165,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_imports() {
+        let input = indoc! {r#"
+            import "auth.bnb"
+            import shared/header.bnb
+
+            place Home
+              Sign In -> Login
+            import "late.bnb"
+        "#};
+
+        let breadboard = parse(input).unwrap();
+
+        assert_eq!(
+            breadboard.imports,
+            vec![
+                PathBuf::from("auth.bnb"),
+                PathBuf::from("shared/header.bnb"),
+                PathBuf::from("late.bnb"),
+            ]
+        );
+        assert_eq!(breadboard.places[0].items.len(), 1);
+
+        assert_eq!(parse("import\n"), Err(Error::MissingImportPath));
+        assert_eq!(
+            parse("import \"a.bnb\" b"),
+            Err(Error::UnexpectedToken("b".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_parse_flows() {
+        let input = indoc! {r#"
+            place Registration
+              Sign Up -> Support
+              placeholder
+
+            /// New users who fail to sign up.
+            flow Sign Up Fails
+              Registration: Sign Up
+              // Not a step.
+              "Support: Help" : Try Again
+              placeholder: Open
+
+            /// The next flow.
+            flow Empty
+            place Support
+        "#};
+
+        let breadboard = parse(input).unwrap();
+
+        assert_eq!(
+            breadboard.flows,
+            vec![
+                Flow {
+                    name: "Sign Up Fails".to_owned(),
+                    description: vec!["New users who fail to sign up.".to_owned()],
+                    steps: vec![
+                        Step {
+                            place: "Registration".to_owned(),
+                            affordance: "Sign Up".to_owned(),
+                        },
+                        Step {
+                            place: "Support: Help".to_owned(),
+                            affordance: "Try Again".to_owned(),
+                        },
+                        Step {
+                            place: "placeholder".to_owned(),
+                            affordance: "Open".to_owned(),
+                        },
+                    ],
+                },
+                Flow {
+                    name: "Empty".to_owned(),
+                    description: vec!["The next flow.".to_owned()],
+                    steps: vec![],
+                },
+            ]
+        );
+        assert_eq!(breadboard.places.len(), 2);
+        assert_eq!(breadboard.places[0].items.len(), 2);
+
+        let test_cases = vec![
+            ("flow\n", Error::MissingFlowName),
+            (
+                "flow Broken\n  Registration Sign Up",
+                Error::InvalidFlowStep("Registration Sign Up".to_owned()),
+            ),
+            (
+                "flow Broken\n  Registration:\n",
+                Error::MissingStepAffordance("Registration".to_owned()),
+            ),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(parse(input), Err(expected), "{input:?}");
+        }
+    }
+
     #[test]
     fn test_parse_level() {
         let test_cases = vec![
@@ -874,7 +1386,7 @@ mod tests {
             ("(\"quoted string\")", Ok("quoted string".to_owned())),
             (
                 "(\"escaped \\\"quote\\\"\")",
-                Ok("escaped \\\"quote\\\"".to_owned()),
+                Ok("escaped \"quote\"".to_owned()),
             ),
             (
                 "(multi\nline\ndescription)",
@@ -911,28 +1423,182 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_connection_kinds() {
+        let input = indoc! {"
+            place Home
+              Sign Up -> Registration ~> Terms
+                      => (form) Server <- Back
+              Done<-Home
+        "};
+
+        let breadboard = parse(input).unwrap();
+        let connections: Vec<_> = breadboard.places[0]
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Affordance(affordance) => Some(affordance),
+                Item::Reference(_) => None,
+            })
+            .flat_map(|affordance| {
+                affordance.connections.iter().map(|connection| {
+                    (
+                        affordance.name.as_str(),
+                        connection.target_place.as_str(),
+                        connection.kind,
+                    )
+                })
+            })
+            .collect();
+
+        assert_eq!(
+            connections,
+            vec![
+                ("Sign Up", "Registration", ConnectionKind::Navigate),
+                ("Sign Up", "Terms", ConnectionKind::Modal),
+                ("Sign Up", "Server", ConnectionKind::Data),
+                ("Sign Up", "Back", ConnectionKind::Return),
+                ("Done", "Home", ConnectionKind::Return),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        #[rustfmt::skip]
+        let test_cases = vec![
+            ("", Ok(vec![])),
+            ("Username", Ok(vec![])),
+            ("@owner payments\n@ticket ABC-12\nUsername", Ok(vec![("owner", "payments"), ("ticket", "ABC-12")])),
+            ("  @status   in progress  \n", Ok(vec![("status", "in progress")])),
+            ("@draft\n@note \"@ home\"", Ok(vec![("draft", ""), ("note", "@ home")])),
+            ("@ owner", Err(Error::MissingAttributeKey)),
+            ("@owner a\n@owner b", Err(Error::DuplicateAttribute("owner".to_owned()))),
+            ("@note \"a\" b", Err(Error::UnexpectedToken("b".to_owned()))),
+        ];
+
+        for (input, expected) in test_cases {
+            let mut chars = input.chars();
+            let result = parse_attributes(&mut chars);
+            let expected = expected.map(|attributes| {
+                attributes
+                    .into_iter()
+                    .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                    .collect()
+            });
+
+            assert_eq!(result, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_annotation() {
+        #[rustfmt::skip]
+        let test_cases = vec![
+            ("", Ok((None, vec![]))),
+            ("-> Home", Ok((None, vec![]))),
+            ("[button]", Ok((Some("button"), vec![]))),
+            ("[ input required=true ]", Ok((Some("input"), vec![("required", "true")]))),
+            (r#"[input placeholder="Your [name]" max=20]"#, Ok((Some("input"), vec![("placeholder", "Your [name]"), ("max", "20")]))),
+            ("[hint=]", Ok((None, vec![("hint", "")]))),
+            ("[]", Ok((None, vec![]))),
+            ("[input", Err(Error::UnterminatedAnnotation)),
+            ("[input\n]", Err(Error::UnterminatedAnnotation)),
+            ("[input button]", Err(Error::InvalidAnnotation("button".to_owned()))),
+            ("[required=true input]", Err(Error::InvalidAnnotation("input".to_owned()))),
+            ("[=true]", Err(Error::InvalidAnnotation("=".to_owned()))),
+            (r#"["input"]"#, Err(Error::InvalidAnnotation(String::new()))),
+            ("[input max=1 max=2]", Err(Error::DuplicateAttribute("max".to_owned()))),
+        ];
+
+        for (input, expected) in test_cases {
+            let mut chars = input.chars();
+            let result = parse_annotation(&mut chars);
+            let expected = expected.map(|(kind, attributes)| {
+                (
+                    kind.map(ToOwned::to_owned),
+                    attributes
+                        .into_iter()
+                        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                        .collect(),
+                )
+            });
+
+            assert_eq!(result, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_annotated_affordance() {
+        let input = indoc! {r#"
+            place Home
+              Username [input placeholder="Your name"]
+              "Sign [Up]" [button] -> Dashboard
+        "#};
+
+        let breadboard = parse(input).unwrap();
+        let affordances = breadboard.affordances(&breadboard.places[0]);
+
+        assert_eq!(affordances[0].name, "Username");
+        assert_eq!(affordances[0].kind.as_deref(), Some("input"));
+        assert_eq!(
+            affordances[0]
+                .attributes
+                .get("placeholder")
+                .map(String::as_str),
+            Some("Your name")
+        );
+        assert_eq!(affordances[1].name, "Sign [Up]");
+        assert_eq!(affordances[1].kind.as_deref(), Some("button"));
+        assert_eq!(affordances[1].connections[0].target_place, "Dashboard");
+    }
+
+    #[test]
+    fn test_parse_connection_target() {
+        #[rustfmt::skip]
+        let test_cases = vec![
+            ("Home", Ok(("Home", None))),
+            ("Registration.Password", Ok(("Registration", Some("Password")))),
+            ("Registration. Forgot Password -> Home", Ok(("Registration", Some("Forgot Password")))),
+            ("Settings.Tab 2.1", Ok(("Settings", Some("Tab 2.1")))),
+            ("\"v1.0\".\"Sign -> Up\"", Ok(("v1.0", Some("Sign -> Up")))),
+            ("\"v1.0\" -> Home", Ok(("v1.0", None))),
+            ("Home.\n", Err(Error::MissingConnectionAffordance)),
+        ];
+
+        for (input, expected) in test_cases {
+            let mut chars = input.chars();
+            let result = parse_connection_target(&mut chars);
+            let expected = expected
+                .map(|(place, affordance)| (place.to_owned(), affordance.map(ToOwned::to_owned)));
+
+            assert_eq!(result, expected, "{input}");
+        }
+    }
+
     #[test]
     fn test_parse_quoted_string() {
         #[rustfmt::skip]
         let test_cases = vec![
             ("\"simple string\"", Ok("simple string")),
-            ("\"string with \\\"escaped quotes\\\"\"", Ok("string with \\\"escaped quotes\\\"")),
+            ("\"string with \\\"escaped quotes\\\"\"", Ok("string with \"escaped quotes\"")),
             ("\"\"", Ok("")),
             ("\"string with spaces\"", Ok("string with spaces")),
-            ("\"string with newline\\n\"", Ok("string with newline\\n")),
-            ("\"string with tab\\t\"", Ok("string with tab\\t")),
-            ("\"string with various \\\"special\\\" characters!@#\"", Ok("string with various \\\"special\\\" characters!@#")),
+            ("\"string with newline\\n\"", Ok("string with newline\n")),
+            ("\"string with tab\\t\"", Err(Error::InvalidEscape("\\t".to_owned()))),
+            ("\"string with unicode \\u{1F35E}\"", Ok("string with unicode 🍞")),
+            ("\"string with various \\\"special\\\" characters!@#\"", Ok("string with various \"special\" characters!@#")),
             ("\"unterminated string", Err(Error::UnterminatedQuotedString)),
             ("no quotes", Err(Error::ExpectedQuotedString)),
-            ("\"escaped backslash \\\\\"", Ok("escaped backslash \\\\")),
+            ("\"escaped backslash \\\\\"", Ok("escaped backslash \\")),
             ("\"multi\nline\"", Ok("multi\nline")),
-            ("\"string with \\\\\\\"escaped quote\"", Ok("string with \\\\\\\"escaped quote")),
+            ("\"string with \\\\\\\"escaped quote\"", Ok("string with \\\"escaped quote")),
         ];
 
         for (input, expected) in test_cases {
             let mut chars = input.chars();
             let result = parse_quoted_string(&mut chars);
-            assert_eq!(result, expected);
+            assert_eq!(result, expected.map(ToOwned::to_owned));
         }
     }
 
@@ -1120,4 +1786,27 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn test_parse_located() {
+        let test_cases = vec![
+            ("\n\n  place Home\n  bogus", None),
+            (
+                "\n  bogus Home\nplace Home",
+                Some((Error::UnexpectedToken("bogus".to_owned()), 3)),
+            ),
+            (
+                "place Home\n  Go -> \"Away",
+                Some((Error::UnterminatedQuotedString, 24)),
+            ),
+            ("place Home\nplace", Some((Error::MissingPlaceName, 16))),
+        ];
+
+        for (input, expected) in test_cases {
+            let result = parse_located(input)
+                .err()
+                .map(|error| (error.error, error.offset));
+            assert_eq!(result, expected, "{input:?}");
+        }
+    }
 }
@@ -0,0 +1,411 @@
+//! Loading of breadboards that are split across multiple files with `import`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use bnb_ast::{Breadboard, Component as BreadboardComponent, Place};
+use tracing::instrument;
+
+use crate::{parse_located, LocatedError};
+
+/// Access to the files of a breadboard, used by [`load`] to read the imported files.
+pub trait FileSystem {
+    /// Reads the contents of the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist, or cannot be read.
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+}
+
+/// The [`FileSystem`] of the operating system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+}
+
+/// An in-memory [`FileSystem`], mapping paths to the contents of their files.
+impl<S: BuildHasher> FileSystem for HashMap<PathBuf, String, S> {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+/// Loads the breadboard at the given path, along with all the files it (transitively) imports.
+///
+/// Imports are resolved relative to the directory of the importing file. The places and
+/// components (and flows) of all files are merged into a single breadboard, with those of the
+/// importing file before those of its imports. Each [`Place::origin`] is set to the file it is
+/// defined in, and the [`Breadboard::imports`] of the merged breadboard are empty.
+///
+/// A file that is imported more than once, e.g. by two different files, is only loaded once.
+///
+/// # Errors
+///
+/// Returns an error if any of the files cannot be read or parsed, if files import each other in a
+/// cycle, or if a place or component is defined in more than one file.
+///
+/// # Examples
+///
+/// ```
+/// use std::{collections::HashMap, path::PathBuf};
+///
+/// use bnb_parser::load;
+///
+/// let files = HashMap::from([
+///     (PathBuf::from("app/main.bnb"), "import \"auth.bnb\"\nplace Home".to_owned()),
+///     (PathBuf::from("app/auth.bnb"), "place Login\n  Sign In -> Home".to_owned()),
+/// ]);
+///
+/// let breadboard = load("app/main.bnb", &files).unwrap();
+/// let login = breadboard.place("Login").unwrap();
+///
+/// assert_eq!(breadboard.places.len(), 2);
+/// assert_eq!(login.origin, Some(PathBuf::from("app/auth.bnb")));
+/// ```
+pub fn load(path: impl AsRef<Path>, fs: &dyn FileSystem) -> Result<Breadboard, LoadError> {
+    let mut loader = Loader {
+        fs,
+        loaded: HashSet::new(),
+        stack: vec![],
+        breadboard: Breadboard::default(),
+    };
+
+    loader.load(&normalize(path.as_ref()))?;
+
+    Ok(loader.breadboard)
+}
+
+struct Loader<'a> {
+    fs: &'a dyn FileSystem,
+
+    /// The files that have been loaded so far.
+    loaded: HashSet<PathBuf>,
+
+    /// The chain of files currently being loaded, each importing the next.
+    stack: Vec<PathBuf>,
+
+    /// The merged breadboard of all loaded files.
+    breadboard: Breadboard,
+}
+
+impl Loader<'_> {
+    #[instrument(level = "debug", skip(self))]
+    fn load(&mut self, path: &Path) -> Result<(), LoadError> {
+        if let Some(start) = self.stack.iter().position(|file| file == path) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(path.to_owned());
+
+            return Err(LoadError::ImportCycle { cycle });
+        }
+
+        if !self.loaded.insert(path.to_owned()) {
+            return Ok(());
+        }
+
+        let input = self
+            .fs
+            .read_to_string(path)
+            .map_err(|source| LoadError::Read {
+                path: path.to_owned(),
+                source,
+            })?;
+
+        let breadboard = parse_located(&input).map_err(|source| LoadError::Parse {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        for place in breadboard.places {
+            self.add_place(path, place)?;
+        }
+
+        for component in breadboard.components {
+            self.add_component(path, component)?;
+        }
+
+        // Duplicate flows are reported by validation.
+        self.breadboard.flows.extend(breadboard.flows);
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        self.stack.push(path.to_owned());
+        for import in &breadboard.imports {
+            self.load(&normalize(&dir.join(import)))?;
+        }
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    fn add_place(&mut self, path: &Path, mut place: Place) -> Result<(), LoadError> {
+        // Duplicates within a single file are reported by validation instead.
+        if let Some(first) = self
+            .breadboard
+            .place(&place.name)
+            .and_then(other_file(path))
+        {
+            return Err(LoadError::DuplicatePlace {
+                name: place.name,
+                first,
+                second: path.to_owned(),
+            });
+        }
+
+        place.origin = Some(path.to_owned());
+        self.breadboard.places.push(place);
+
+        Ok(())
+    }
+
+    fn add_component(
+        &mut self,
+        path: &Path,
+        mut component: BreadboardComponent,
+    ) -> Result<(), LoadError> {
+        if let Some(first) = self
+            .breadboard
+            .component(&component.name)
+            .and_then(|component| other_file(path)(component))
+        {
+            return Err(LoadError::DuplicateComponent {
+                name: component.name.clone(),
+                first,
+                second: path.to_owned(),
+            });
+        }
+
+        component.origin = Some(path.to_owned());
+        self.breadboard.components.push(component);
+
+        Ok(())
+    }
+}
+
+/// Returns the origin of a place, if it was defined in a file other than `path`.
+fn other_file(path: &Path) -> impl Fn(&Place) -> Option<PathBuf> + '_ {
+    move |place| {
+        place
+            .origin
+            .as_ref()
+            .filter(|origin| origin.as_path() != path)
+            .cloned()
+    }
+}
+
+/// Removes `.` and `..` components from the path, without accessing the file system.
+///
+/// Leading `..` components of relative paths are kept.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// An error that occurred while loading a breadboard with [`load`].
+#[derive(thiserror::Error, Debug)]
+pub enum LoadError {
+    #[error("failed to read {}: {source}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+
+    #[error("failed to parse {}: {source}", path.display())]
+    Parse { path: PathBuf, source: LocatedError },
+
+    #[error("import cycle: {}", display_cycle(cycle))]
+    ImportCycle { cycle: Vec<PathBuf> },
+
+    #[error(
+        "place `{name}` is defined in both {} and {}",
+        first.display(),
+        second.display()
+    )]
+    DuplicatePlace {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+
+    #[error(
+        "component `{name}` is defined in both {} and {}",
+        first.display(),
+        second.display()
+    )]
+    DuplicateComponent {
+        name: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+}
+
+impl LoadError {
+    /// Returns the file in which the error occurred.
+    ///
+    /// For an import cycle, this is the file with the `import` that closes the cycle. For a
+    /// duplicate, it is the file with the second definition.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Read { path, .. } | Self::Parse { path, .. } => path,
+            Self::ImportCycle { cycle } => &cycle[cycle.len().saturating_sub(2)],
+            Self::DuplicatePlace { second, .. } | Self::DuplicateComponent { second, .. } => second,
+        }
+    }
+}
+
+fn display_cycle(cycle: &[PathBuf]) -> String {
+    cycle
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(files: &[(&str, &str)]) -> HashMap<PathBuf, String> {
+        files
+            .iter()
+            .map(|(path, contents)| (PathBuf::from(path), (*contents).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_load() {
+        let files = files(&[
+            (
+                "app/main.bnb",
+                "import \"auth/login.bnb\"\nimport \"./shared.bnb\"\nplace Home",
+            ),
+            (
+                "app/auth/login.bnb",
+                "import \"../shared.bnb\"\nplace Login\n  include Header",
+            ),
+            ("app/shared.bnb", "component Header\n  Logo -> Home"),
+        ]);
+
+        let breadboard = load("app/./main.bnb", &files).unwrap();
+
+        let places: Vec<_> = breadboard
+            .places
+            .iter()
+            .map(|place| (place.name.as_str(), place.origin.as_deref()))
+            .collect();
+
+        assert_eq!(
+            places,
+            vec![
+                ("Home", Some(Path::new("app/main.bnb"))),
+                ("Login", Some(Path::new("app/auth/login.bnb"))),
+            ]
+        );
+        assert_eq!(breadboard.components.len(), 1);
+        assert_eq!(
+            breadboard.components[0].origin.as_deref(),
+            Some(Path::new("app/shared.bnb"))
+        );
+        assert!(breadboard.imports.is_empty());
+        assert_eq!(breadboard.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let files = files(&[
+            ("cycle.bnb", "import \"a.bnb\""),
+            ("a.bnb", "import \"b.bnb\""),
+            ("b.bnb", "import \"a.bnb\""),
+            ("self.bnb", "import \"self.bnb\""),
+            ("missing.bnb", "import \"nope.bnb\""),
+            ("invalid.bnb", "import \"broken.bnb\""),
+            ("broken.bnb", "place Home\n  Go -> \"Away"),
+            ("places.bnb", "import \"home.bnb\"\nplace Home"),
+            ("home.bnb", "place Home"),
+            ("components.bnb", "import \"header.bnb\"\ncomponent Header"),
+            ("header.bnb", "component Header"),
+        ]);
+
+        let error = |path| load(path, &files).unwrap_err();
+
+        assert!(matches!(
+            error("cycle.bnb"),
+            LoadError::ImportCycle { cycle } if cycle == [Path::new("a.bnb"), Path::new("b.bnb"), Path::new("a.bnb")]
+        ));
+        assert_eq!(error("cycle.bnb").path(), Path::new("b.bnb"));
+        assert_eq!(
+            error("cycle.bnb").to_string(),
+            "import cycle: a.bnb -> b.bnb -> a.bnb"
+        );
+        assert_eq!(error("self.bnb").path(), Path::new("self.bnb"));
+
+        assert!(matches!(error("missing.bnb"), LoadError::Read { .. }));
+        assert_eq!(error("missing.bnb").path(), Path::new("nope.bnb"));
+
+        assert!(matches!(error("invalid.bnb"), LoadError::Parse { .. }));
+        assert_eq!(error("invalid.bnb").path(), Path::new("broken.bnb"));
+
+        assert_eq!(
+            error("places.bnb").to_string(),
+            "place `Home` is defined in both places.bnb and home.bnb"
+        );
+        assert_eq!(
+            error("components.bnb").to_string(),
+            "component `Header` is defined in both components.bnb and header.bnb"
+        );
+    }
+
+    #[test]
+    fn test_duplicates_within_file() {
+        let files = files(&[("main.bnb", "place Home\nplace Home")]);
+
+        let breadboard = load("main.bnb", &files).unwrap();
+
+        assert_eq!(breadboard.places.len(), 2);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let test_cases = vec![
+            ("a/b.bnb", "a/b.bnb"),
+            ("./a/./b.bnb", "a/b.bnb"),
+            ("a/../b.bnb", "b.bnb"),
+            ("a/b/../../c.bnb", "c.bnb"),
+            ("../a.bnb", "../a.bnb"),
+            ("../../a/../b.bnb", "../../b.bnb"),
+            ("/a/../b.bnb", "/b.bnb"),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(
+                normalize(Path::new(input)),
+                PathBuf::from(expected),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,526 @@
+//! Printing of a [`Breadboard`] back into its textual DSL representation.
+//!
+//! The printer produces a canonical layout: two spaces of indentation for place items, a blank
+//! line between places, and connections after the first one aligned below the first `->`.
+//!
+//! Regular (`//`) comments are not part of the AST, and are therefore not preserved. Descriptions
+//! (`///`) are.
+
+use bnb_ast::{Affordance, Breadboard, Connection, Coordinate, Flow, Item, Pivot, Place, Sketch};
+
+use crate::{
+    escape::{needs_escape, quote},
+    NameKind, ARROWS,
+};
+
+/// The indentation used for items within a place.
+const INDENT: &str = "  ";
+
+/// Keywords that cannot start an unquoted affordance name.
+const KEYWORDS: &[&str] = &[
+    "place",
+    "component",
+    "import",
+    "flow",
+    "include",
+    "position",
+    "sketch",
+];
+
+/// Prints a [`Breadboard`] as DSL source.
+///
+/// Parsing the output of this function yields the same breadboard again.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::{parse, print};
+///
+/// let breadboard = parse("place Home\n  Sign Up -> Registration").unwrap();
+///
+/// assert_eq!(print(&breadboard), "place Home\n  Sign Up -> Registration\n");
+/// ```
+#[must_use]
+pub fn print(breadboard: &Breadboard) -> String {
+    let places = breadboard.places.iter().map(|place| ("place", place));
+    let components = breadboard
+        .components
+        .iter()
+        .map(|component| ("component", &**component));
+
+    let imports = breadboard
+        .imports
+        .iter()
+        .map(|path| format!("import {}", quote(&path.to_string_lossy())))
+        .fold(String::new(), |imports, import| imports + &import + "\n");
+
+    let flows = breadboard.flows.iter().map(print_flow);
+
+    let body = places
+        .chain(components)
+        .map(|(keyword, place)| print_place(keyword, place))
+        .chain(flows)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if imports.is_empty() || body.is_empty() {
+        imports + &body
+    } else {
+        format!("{imports}\n{body}")
+    }
+}
+
+/// Returns `true` if the source contains any regular (`//`) comments, which [`print`] does not
+/// preserve.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::has_comments;
+///
+/// assert!(has_comments("place Home\n  // TODO\n"));
+/// assert!(!has_comments("/// The start.\nplace Home\n"));
+/// ```
+#[must_use]
+pub fn has_comments(input: &str) -> bool {
+    input.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("//") && !line.starts_with("///")
+    })
+}
+
+fn print_place(keyword: &str, place: &Place) -> String {
+    let mut out = String::new();
+
+    print_description(&mut out, "", &place.description);
+    let kind = match keyword {
+        "component" => NameKind::Component,
+        _ => NameKind::Place,
+    };
+    out.push_str(&format!("{keyword} {}\n", print_name_as(&place.name, kind)));
+
+    for (key, value) in &place.attributes {
+        match print_name_as(value, NameKind::Place) {
+            value if value.is_empty() => out.push_str(&format!("{INDENT}@{key}\n")),
+            value => out.push_str(&format!("{INDENT}@{key} {value}\n")),
+        }
+    }
+
+    for item in &place.items {
+        match item {
+            Item::Affordance(affordance) => print_affordance(&mut out, affordance),
+            Item::Reference(reference) => {
+                out.push_str(&format!(
+                    "{INDENT}{}include {}\n",
+                    level(reference.level),
+                    print_name_as(&reference.name, NameKind::Reference)
+                ));
+            }
+        }
+    }
+
+    if place.position.is_some() || place.sketch.is_some() {
+        if !place.items.is_empty() {
+            out.push('\n');
+        }
+
+        if let Some(position) = &place.position {
+            out.push_str(&format!(
+                "{INDENT}position {}, {}\n",
+                coordinate(&position.x),
+                coordinate(&position.y)
+            ));
+        }
+
+        if let Some(sketch) = &place.sketch {
+            print_sketch(&mut out, sketch);
+        }
+    }
+
+    out
+}
+
+fn print_flow(flow: &Flow) -> String {
+    let mut out = String::new();
+
+    print_description(&mut out, "", &flow.description);
+    out.push_str(&format!(
+        "flow {}\n",
+        print_name_as(&flow.name, NameKind::Flow)
+    ));
+
+    for step in &flow.steps {
+        out.push_str(&format!(
+            "{INDENT}{}: {}\n",
+            print_name_as(&step.place, NameKind::StepPlace),
+            print_name_as(&step.affordance, NameKind::StepAffordance)
+        ));
+    }
+
+    out
+}
+
+fn print_description(out: &mut String, indent: &str, description: &[String]) {
+    for line in description {
+        if line.is_empty() {
+            out.push_str(&format!("{indent}///\n"));
+        } else {
+            out.push_str(&format!("{indent}/// {line}\n"));
+        }
+    }
+}
+
+fn print_affordance(out: &mut String, affordance: &Affordance) {
+    print_description(out, INDENT, &affordance.description);
+
+    let head = format!(
+        "{INDENT}{}{}{}",
+        level(affordance.level),
+        print_name(&affordance.name),
+        print_annotation(affordance)
+    );
+    out.push_str(&head);
+
+    let align = " ".repeat(head.chars().count());
+    for (index, connection) in affordance.connections.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+            out.push_str(&align);
+        }
+
+        out.push(' ');
+        out.push_str(&print_connection(connection));
+    }
+
+    out.push('\n');
+}
+
+/// Prints the `[kind key=value]` annotation of an affordance, including the leading space, or
+/// nothing if the affordance has neither a kind nor attributes.
+fn print_annotation(affordance: &Affordance) -> String {
+    if affordance.kind.is_none() && affordance.attributes.is_empty() {
+        return String::new();
+    }
+
+    let kind = affordance.kind.iter().map(ToOwned::to_owned);
+    let attributes = affordance.attributes.iter().map(|(key, value)| {
+        if value.is_empty()
+            || value.contains(|c: char| c.is_whitespace() || c == ']')
+            || value.starts_with('"')
+            || value.contains(needs_escape)
+        {
+            format!("{key}={}", quote(value))
+        } else {
+            format!("{key}={value}")
+        }
+    });
+
+    format!(
+        " [{}]",
+        kind.chain(attributes).collect::<Vec<_>>().join(" ")
+    )
+}
+
+fn print_connection(connection: &Connection) -> String {
+    let arrow = ARROWS
+        .iter()
+        .find(|(_, kind)| *kind == connection.kind)
+        .map_or("->", |(arrow, _)| arrow);
+    let mut out = format!("{arrow} ");
+
+    if let Some(description) = &connection.description {
+        if description.contains(')')
+            || description.starts_with('"')
+            || description.contains(needs_escape)
+        {
+            out.push_str(&format!("({}) ", quote(description)));
+        } else {
+            out.push_str(&format!("({description}) "));
+        }
+    }
+
+    out.push_str(&print_name_as(&connection.target_place, NameKind::Target));
+    if let Some(affordance) = &connection.target_affordance {
+        out.push('.');
+        out.push_str(&print_name_as(affordance, NameKind::TargetAffordance));
+    }
+
+    out
+}
+
+fn print_sketch(out: &mut String, sketch: &Sketch) {
+    out.push_str(&format!("{INDENT}sketch {}\n", sketch.path.display()));
+
+    for area in &sketch.areas {
+        let (top, left) = area.top_left;
+        out.push_str(&format!(
+            "{INDENT}{INDENT}[{top},{left} {},{}] {}\n",
+            top + area.height,
+            left + area.width,
+            print_name_as(&area.affordance, NameKind::Area)
+        ));
+    }
+}
+
+fn coordinate(coordinate: &Coordinate) -> String {
+    let (place, offset, pivot) = match coordinate {
+        Coordinate::Absolute(value) => return value.to_string(),
+        Coordinate::Relative {
+            place,
+            offset,
+            pivot,
+        } => (place, *offset, *pivot),
+    };
+
+    let pivot = match pivot {
+        Pivot::Center => "",
+        Pivot::Top => "^ ",
+        Pivot::Right => "> ",
+        Pivot::Bottom => "_ ",
+        Pivot::Left => "< ",
+    };
+
+    let place = print_name_as(place, NameKind::Coordinate);
+
+    match offset {
+        0 => format!("{pivot}{place}"),
+        offset if offset < 0 => format!("{pivot}{place} - {}", offset.unsigned_abs()),
+        offset => format!("{pivot}{place} + {offset}"),
+    }
+}
+
+/// Prints an affordance name, quoting (and escaping) it if it would otherwise be parsed
+/// differently.
+///
+/// See [`print_name_as`] for names in other positions, such as connection targets.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::print_name;
+///
+/// assert_eq!(print_name("Home"), "Home");
+/// assert_eq!(print_name("Yes -> No"), "\"Yes -> No\"");
+/// assert_eq!(print_name("Say \"Hi\"\n"), r#""Say \"Hi\"\n""#);
+/// ```
+#[must_use]
+pub fn print_name(name: &str) -> String {
+    let needs_quotes = name.is_empty()
+        || ARROWS.iter().any(|(arrow, _)| name.contains(arrow))
+        || name.contains(needs_escape)
+        || name.contains('[')
+        || name.starts_with(['"', '>', '(', '@'])
+        || name.starts_with("//")
+        || name.trim() != name
+        || KEYWORDS.iter().any(|keyword| name.starts_with(keyword));
+
+    if needs_quotes {
+        quote(name)
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Prints a name as it needs to be written in the position described by `kind`, quoting it if
+/// required.
+///
+/// Place, component and flow names, `include` references, sketch area affordances and flow step
+/// affordances span the rest of their line, and are only quoted if they start with a quote,
+/// contain control characters, or have surrounding whitespace.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_parser::{print_name_as, NameKind};
+///
+/// assert_eq!(print_name_as("Sign -> Up", NameKind::Place), "Sign -> Up");
+/// assert_eq!(print_name_as("Sign\nUp", NameKind::Place), r#""Sign\nUp""#);
+/// assert_eq!(print_name_as("Sign -> Up", NameKind::Target), "\"Sign -> Up\"");
+/// assert_eq!(print_name_as("v1.0", NameKind::Target), "\"v1.0\"");
+/// assert_eq!(print_name_as("Sign-Up", NameKind::Coordinate), "\"Sign-Up\"");
+/// assert_eq!(print_name_as("Step: 1", NameKind::StepPlace), "\"Step: 1\"");
+/// ```
+#[must_use]
+pub fn print_name_as(name: &str, kind: NameKind) -> String {
+    let needs_quotes = match kind {
+        NameKind::Place
+        | NameKind::Component
+        | NameKind::Reference
+        | NameKind::Area
+        | NameKind::Flow
+        | NameKind::StepAffordance => {
+            name.starts_with('"') || name.contains(needs_escape) || name.trim() != name
+        }
+        NameKind::Target if name.contains('.') => true,
+        NameKind::Affordance | NameKind::Target | NameKind::TargetAffordance => {
+            return print_name(name)
+        }
+        // Flow steps end at the next top-level keyword or comment.
+        NameKind::StepPlace => {
+            name.is_empty()
+                || name.contains([':', '"'])
+                || name.contains(needs_escape)
+                || name.starts_with("//")
+                || name.trim() != name
+                || KEYWORDS.iter().any(|keyword| name.starts_with(keyword))
+        }
+        NameKind::Coordinate => {
+            name.is_empty()
+                || name.contains(['+', '-', ',', '"'])
+                || name.contains(needs_escape)
+                || name.starts_with(|c: char| c.is_ascii_digit() || "^>_<".contains(c))
+                || name.trim() != name
+        }
+    };
+
+    if needs_quotes {
+        quote(name)
+    } else {
+        name.to_owned()
+    }
+}
+
+fn level(level: usize) -> String {
+    match level {
+        0 => String::new(),
+        n => format!("{} ", ">".repeat(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::parse;
+
+    use super::*;
+
+    #[test]
+    fn test_print() {
+        let input = indoc! {r#"
+            import "auth.bnb"
+            import "../shared/header.bnb"
+
+            /// The place to register.
+            ///
+            /// Second paragraph.
+            place Registration
+              @owner payments
+              @ticket "ABC-12 "
+              @wip
+              include Header
+              Username [input placeholder="Your name" required=true]
+              > Show Characters [toggle]
+              >> "-> arrow"
+              /// Signs the user up.
+              Sign Up -> (success) Home
+                      -> ("with ) paren") Support
+                      -> Home.Dashboard
+                      -> "v1.0"."-> arrow"
+              Terms [link] ~> Terms
+              "[Beta]" [badge]
+              Submit => (form) "<- Back"
+              Back <- Home
+
+              position < Home - 10, ^ "0,0" + 12
+              sketch sketches/registration.png
+                [50,20 110,40] Sign Up
+
+            place Home
+              "@home"
+              "import"
+
+              position -10, 20
+
+            component Header
+              Logo
+
+            /// Signing up successfully.
+            flow Sign Up
+              Registration: Sign Up
+              "Home: Start": flow
+              "place": Dashboard
+        "#};
+
+        let breadboard = parse(input).unwrap();
+
+        assert_eq!(print(&breadboard), input);
+    }
+
+    #[test]
+    fn test_print_escapes() {
+        let input = indoc! {r#"
+            place "Say \"Hi\"\nNow"
+              include "\u{7}Bell"
+              "Back\\Slash \"->\"" -> ("a \"b\" )") "Say \"Hi\"\nNow"
+              Plain "quote" -> a-b, c+d
+
+              position < "Say \"Hi\"\nNow" - 10, ^ "a, b + c" + 2
+              sketch home.png
+                [0,0 10,10] "\"Quoted\""
+
+            component "\u{7}Bell"
+              Logo
+        "#};
+
+        let breadboard = parse(input).unwrap();
+        let place = &breadboard.places[0];
+
+        assert_eq!(place.name, "Say \"Hi\"\nNow");
+        assert_eq!(breadboard.components[0].name, "\u{7}Bell");
+        assert_eq!(
+            place.sketch.as_ref().unwrap().areas[0].affordance,
+            "\"Quoted\""
+        );
+        assert_eq!(print(&breadboard), input);
+    }
+
+    #[test]
+    fn test_print_attribute_order() {
+        let input = indoc! {r#"
+            place Registration
+              @ticket ABC-12
+              @owner payments
+              Username [input required=true placeholder="Your name"]
+        "#};
+
+        let breadboard = parse(input).unwrap();
+        let place = &breadboard.places[0];
+        let Item::Affordance(affordance) = &place.items[0] else {
+            panic!("expected an affordance");
+        };
+
+        assert!(place.attributes.keys().eq(["ticket", "owner"]));
+        assert!(affordance.attributes.keys().eq(["required", "placeholder"]));
+        assert_eq!(print(&breadboard), input);
+    }
+
+    #[test]
+    fn test_print_roundtrip() {
+        let test_cases = vec![
+            indoc! {r#"
+                place invoice
+                  Turn on autopay -> Set up autopay -> Foo bar -> (test) test 2
+                place two
+                place three and more!
+                  "free -> form!" -> Not -> "(test)"
+                  "placement"
+                  another one!
+                  sketch foo/bar.png
+                    [0,0 10,10] free -> form!
+                    [20,20 30,30] another one!
+                place four!
+            "#},
+            indoc! {"
+                place Home
+                  position ^ Registration - 12
+            "},
+        ];
+
+        for case in test_cases {
+            let breadboard = parse(case).unwrap();
+
+            assert_eq!(parse(&print(&breadboard)), Ok(breadboard));
+        }
+    }
+}
@@ -0,0 +1,734 @@
+//! Refactoring of a [`Breadboard`] AST.
+
+use bnb_ast::{Breadboard, Coordinate, Item, Place};
+
+use crate::{Error, Symbol};
+
+/// The location of a place or component in a [`Breadboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Location {
+    Place(usize),
+    Component(usize),
+}
+
+impl Location {
+    pub(crate) fn get(self, breadboard: &Breadboard) -> &Place {
+        match self {
+            Self::Place(index) => &breadboard.places[index],
+            Self::Component(index) => &breadboard.components[index],
+        }
+    }
+
+    fn get_mut(self, breadboard: &mut Breadboard) -> &mut Place {
+        match self {
+            Self::Place(index) => &mut breadboard.places[index],
+            Self::Component(index) => &mut breadboard.components[index],
+        }
+    }
+}
+
+/// Renames a place, component or affordance, and updates every reference to it.
+///
+/// Renaming a symbol to its current name is a no-op.
+///
+/// # Errors
+///
+/// Returns [`Error::NotFound`] if the symbol does not exist, [`Error::AlreadyExists`] if another
+/// symbol of the same kind already uses the new name, or [`Error::InvalidName`] if the new name is
+/// empty, spans multiple lines, or starts or ends with whitespace.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_refactor::{rename, Symbol};
+///
+/// let mut breadboard = bnb_parser::parse("place Home\n  Go -> Away\n\nplace Away").unwrap();
+/// rename(&mut breadboard, &Symbol::Place("Away".to_owned()), "Elsewhere").unwrap();
+///
+/// assert_eq!(
+///     bnb_parser::print(&breadboard),
+///     "place Home\n  Go -> Elsewhere\n\nplace Elsewhere\n"
+/// );
+/// ```
+pub fn rename(breadboard: &mut Breadboard, symbol: &Symbol, new_name: &str) -> Result<(), Error> {
+    if new_name.is_empty() || new_name.contains(['\n', '\r']) || new_name.trim() != new_name {
+        return Err(Error::InvalidName(new_name.to_owned()));
+    }
+
+    match symbol {
+        Symbol::Place(name) => rename_place(breadboard, name, new_name),
+        Symbol::Component(name) => rename_component(breadboard, name, new_name),
+        Symbol::Affordance { place, affordance } => {
+            rename_affordance(breadboard, place, affordance, new_name)
+        }
+    }
+}
+
+fn rename_place(breadboard: &mut Breadboard, name: &str, new_name: &str) -> Result<(), Error> {
+    if breadboard.place(name).is_none() {
+        return Err(Error::NotFound(Symbol::Place(name.to_owned())));
+    }
+
+    if name == new_name {
+        return Ok(());
+    }
+
+    if breadboard.place(new_name).is_some() {
+        return Err(Error::AlreadyExists(Symbol::Place(new_name.to_owned())));
+    }
+
+    for place in &mut breadboard.places {
+        if place.name == name {
+            new_name.clone_into(&mut place.name);
+        }
+    }
+
+    for location in locations(breadboard) {
+        let place = location.get_mut(breadboard);
+
+        for item in &mut place.items {
+            let Item::Affordance(affordance) = item else {
+                continue;
+            };
+
+            for connection in &mut affordance.connections {
+                if connection.target_place == name {
+                    new_name.clone_into(&mut connection.target_place);
+                }
+            }
+        }
+
+        for coordinate in place
+            .position
+            .iter_mut()
+            .flat_map(|position| [&mut position.x, &mut position.y])
+        {
+            if let Coordinate::Relative { place, .. } = coordinate {
+                if place == name {
+                    new_name.clone_into(place);
+                }
+            }
+        }
+    }
+
+    for step in breadboard.flows.iter_mut().flat_map(|flow| &mut flow.steps) {
+        if step.place == name {
+            new_name.clone_into(&mut step.place);
+        }
+    }
+
+    Ok(())
+}
+
+fn rename_component(breadboard: &mut Breadboard, name: &str, new_name: &str) -> Result<(), Error> {
+    if breadboard.component(name).is_none() {
+        return Err(Error::NotFound(Symbol::Component(name.to_owned())));
+    }
+
+    if name == new_name {
+        return Ok(());
+    }
+
+    if breadboard.component(new_name).is_some() {
+        return Err(Error::AlreadyExists(Symbol::Component(new_name.to_owned())));
+    }
+
+    for component in &mut breadboard.components {
+        if component.name == name {
+            new_name.clone_into(&mut component.name);
+        }
+    }
+
+    for location in locations(breadboard) {
+        for item in &mut location.get_mut(breadboard).items {
+            if let Item::Reference(reference) = item {
+                if reference.name == name {
+                    new_name.clone_into(&mut reference.name);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn rename_affordance(
+    breadboard: &mut Breadboard,
+    place: &str,
+    name: &str,
+    new_name: &str,
+) -> Result<(), Error> {
+    let symbol = |affordance: &str| Symbol::Affordance {
+        place: place.to_owned(),
+        affordance: affordance.to_owned(),
+    };
+
+    let owner = owner(breadboard, place).ok_or_else(|| Error::NotFound(symbol(name)))?;
+    if !has_affordance(owner.get(breadboard), name) {
+        return Err(Error::NotFound(symbol(name)));
+    }
+
+    if name == new_name {
+        return Ok(());
+    }
+
+    if has_affordance(owner.get(breadboard), new_name) {
+        return Err(Error::AlreadyExists(symbol(new_name)));
+    }
+
+    let affected = affected_by_affordance(breadboard, owner);
+    let targets = target_places(breadboard, &affected);
+
+    for item in &mut owner.get_mut(breadboard).items {
+        if let Item::Affordance(affordance) = item {
+            if affordance.name == name {
+                new_name.clone_into(&mut affordance.name);
+            }
+        }
+    }
+
+    for location in affected {
+        let areas = location
+            .get_mut(breadboard)
+            .sketch
+            .iter_mut()
+            .flat_map(|sketch| &mut sketch.areas);
+
+        for area in areas {
+            if area.affordance == name {
+                new_name.clone_into(&mut area.affordance);
+            }
+        }
+    }
+
+    for location in locations(breadboard) {
+        for item in &mut location.get_mut(breadboard).items {
+            let Item::Affordance(affordance) = item else {
+                continue;
+            };
+
+            for connection in &mut affordance.connections {
+                if connection.target_affordance.as_deref() == Some(name)
+                    && targets.contains(&connection.target_place)
+                {
+                    connection.target_affordance = Some(new_name.to_owned());
+                }
+            }
+        }
+    }
+
+    for step in breadboard.flows.iter_mut().flat_map(|flow| &mut flow.steps) {
+        if step.affordance == name && targets.contains(&step.place) {
+            new_name.clone_into(&mut step.affordance);
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves an affordance to another place or component, and updates every reference to it.
+///
+/// The affordance is added to the end of the items of its new place, at the top level.
+/// Connections and flow steps that use the affordance through a place that no longer has it are
+/// updated to use the new place instead, and sketch areas referencing it in such places are
+/// removed.
+///
+/// Moving an affordance to the place it is defined in is a no-op.
+///
+/// # Errors
+///
+/// Returns [`Error::NotFound`] if the affordance or the new place does not exist,
+/// [`Error::AlreadyExists`] if the new place already has an affordance with the same name, or
+/// [`Error::Nested`] if items are nested under the affordance. Moving an affordance to a
+/// component returns [`Error::Unreachable`] if it is used through a place that does not include
+/// the component.
+///
+/// # Examples
+///
+/// ```
+/// use bnb_refactor::move_affordance;
+///
+/// let source = "place Home\n  Help -> Home\n\nplace Away\n  Go -> Home.Help\n";
+/// let mut breadboard = bnb_parser::parse(source).unwrap();
+/// move_affordance(&mut breadboard, "Home", "Help", "Away").unwrap();
+///
+/// assert_eq!(
+///     bnb_parser::print(&breadboard),
+///     "place Home\n\nplace Away\n  Go -> Away.Help\n  Help -> Home\n"
+/// );
+/// ```
+pub fn move_affordance(
+    breadboard: &mut Breadboard,
+    place: &str,
+    name: &str,
+    to: &str,
+) -> Result<(), Error> {
+    let symbol = |place: &str| Symbol::Affordance {
+        place: place.to_owned(),
+        affordance: name.to_owned(),
+    };
+
+    let location = owner(breadboard, place).ok_or_else(|| Error::NotFound(symbol(place)))?;
+    let items = &location.get(breadboard).items;
+    let index = items
+        .iter()
+        .position(|item| matches!(item, Item::Affordance(affordance) if affordance.name == name))
+        .ok_or_else(|| Error::NotFound(symbol(place)))?;
+
+    let destination =
+        owner(breadboard, to).ok_or_else(|| Error::NotFound(Symbol::Place(to.to_owned())))?;
+    if destination == location {
+        return Ok(());
+    }
+
+    if has_affordance(destination.get(breadboard), name) {
+        return Err(Error::AlreadyExists(symbol(to)));
+    }
+
+    if items
+        .get(index + 1)
+        .is_some_and(|next| level(next) > level(&items[index]))
+    {
+        return Err(Error::Nested(symbol(place)));
+    }
+
+    // Work on a copy, so that the breadboard is left untouched if the move fails.
+    let mut moved = breadboard.clone();
+    let Item::Affordance(mut affordance) = location.get_mut(&mut moved).items.remove(index) else {
+        unreachable!("found an affordance at index {index}");
+    };
+    affordance.level = 0;
+    destination
+        .get_mut(&mut moved)
+        .items
+        .push(Item::Affordance(affordance));
+
+    let affected = affected_by_affordance(breadboard, location);
+    let lost = lost_affordance(&moved, &target_places(breadboard, &affected), name);
+
+    // Uses of the affordance through a place that lost it, move along to its new place.
+    let retarget = |place: &mut String| -> Result<(), Error> {
+        if !lost.contains(place) {
+            return Ok(());
+        }
+
+        match destination {
+            Location::Place(_) => {
+                to.clone_into(place);
+                Ok(())
+            }
+            Location::Component(_) => Err(Error::Unreachable {
+                symbol: symbol(place),
+                to: to.to_owned(),
+            }),
+        }
+    };
+
+    for location in locations(&moved) {
+        for item in &mut location.get_mut(&mut moved).items {
+            let Item::Affordance(affordance) = item else {
+                continue;
+            };
+
+            for connection in &mut affordance.connections {
+                if connection.target_affordance.as_deref() == Some(name) {
+                    retarget(&mut connection.target_place)?;
+                }
+            }
+        }
+    }
+
+    // Sketch areas cannot move to the sketch of another place, so they are removed.
+    for location in affected {
+        if has_unique_affordance(&moved, location.get(&moved), name) {
+            continue;
+        }
+
+        if let Some(sketch) = &mut location.get_mut(&mut moved).sketch {
+            sketch.areas.retain(|area| area.affordance != name);
+        }
+    }
+
+    for step in moved.flows.iter_mut().flat_map(|flow| &mut flow.steps) {
+        if step.affordance == name {
+            retarget(&mut step.place)?;
+        }
+    }
+
+    *breadboard = moved;
+
+    Ok(())
+}
+
+/// Returns the places among the given names, which no longer have an affordance with the given
+/// name.
+pub(crate) fn lost_affordance(
+    breadboard: &Breadboard,
+    places: &[String],
+    name: &str,
+) -> Vec<String> {
+    places
+        .iter()
+        .filter(|place| {
+            breadboard
+                .place(place)
+                .is_some_and(|place| !has_unique_affordance(breadboard, place, name))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns `true` if the place has an affordance with the given name, including the affordances
+/// of the components it includes.
+pub(crate) fn has_unique_affordance(breadboard: &Breadboard, place: &Place, name: &str) -> bool {
+    breadboard
+        .unique_affordances(place)
+        .iter()
+        .any(|affordance| affordance.name == name)
+}
+
+fn level(item: &Item) -> usize {
+    match item {
+        Item::Affordance(affordance) => affordance.level,
+        Item::Reference(reference) => reference.level,
+    }
+}
+
+/// Returns the locations of all places and components.
+fn locations(breadboard: &Breadboard) -> Vec<Location> {
+    let places = (0..breadboard.places.len()).map(Location::Place);
+    let components = (0..breadboard.components.len()).map(Location::Component);
+
+    places.chain(components).collect()
+}
+
+/// Returns the place, or else the component, with the given name.
+pub(crate) fn owner(breadboard: &Breadboard, name: &str) -> Option<Location> {
+    let place = breadboard
+        .places
+        .iter()
+        .position(|place| place.name == name);
+    let component = || {
+        breadboard
+            .components
+            .iter()
+            .position(|component| component.name == name)
+    };
+
+    place
+        .map(Location::Place)
+        .or_else(|| component().map(Location::Component))
+}
+
+/// Returns the places and components whose sketch areas can reference the affordances of the
+/// given owner: the owner itself, and, for a component, every place (transitively) including it.
+pub(crate) fn affected_by_affordance(breadboard: &Breadboard, owner: Location) -> Vec<Location> {
+    let Location::Component(_) = owner else {
+        return vec![owner];
+    };
+
+    let name = &owner.get(breadboard).name;
+    locations(breadboard)
+        .into_iter()
+        .filter(|&location| {
+            location == owner || breadboard.includes(location.get(breadboard), name)
+        })
+        .collect()
+}
+
+/// Returns the names of the places among the given locations, which connections can target.
+pub(crate) fn target_places(breadboard: &Breadboard, locations: &[Location]) -> Vec<String> {
+    locations
+        .iter()
+        .filter(|location| matches!(location, Location::Place(_)))
+        .map(|location| location.get(breadboard).name.clone())
+        .collect()
+}
+
+fn has_affordance(place: &Place, name: &str) -> bool {
+    place
+        .items
+        .iter()
+        .any(|item| matches!(item, Item::Affordance(affordance) if affordance.name == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const SOURCE: &str = indoc! {"
+        place Home
+          include Header
+          Sign Up -> Registration
+          position < Registration - 10, 0
+          sketch home.png
+            [0,0 10,10] Logo
+
+        place Registration
+          include Header
+          Back -> Home
+          Menu -> Registration.Item -> Home.Item
+
+        component Header
+          include Menu
+          Logo -> Home
+
+        component Menu
+          Item -> Registration
+          sketch menu.png
+            [0,0 10,10] Item
+    "};
+
+    #[test]
+    fn test_rename() {
+        let test_cases = vec![
+            (
+                Symbol::Place("Registration".to_owned()),
+                "Sign Up",
+                vec![
+                    ("Home", "-> Sign Up"),
+                    ("Home", "position < Sign Up - 10, 0"),
+                    ("Menu", "-> Sign Up"),
+                    ("Sign Up", "place Sign Up"),
+                ],
+            ),
+            (
+                Symbol::Component("Header".to_owned()),
+                "Top",
+                vec![
+                    ("Home", "include Top"),
+                    ("Registration", "include Top"),
+                    ("Top", "include Menu"),
+                ],
+            ),
+            (
+                Symbol::Affordance {
+                    place: "Menu".to_owned(),
+                    affordance: "Item".to_owned(),
+                },
+                "Entry",
+                vec![
+                    ("Menu", "Entry -> Registration"),
+                    ("Menu", "] Entry"),
+                    ("Registration", "-> Registration.Entry"),
+                    ("Registration", "-> Home.Entry"),
+                ],
+            ),
+            (
+                Symbol::Affordance {
+                    place: "Header".to_owned(),
+                    affordance: "Logo".to_owned(),
+                },
+                "Brand",
+                vec![("Header", "Brand -> Home"), ("Home", "] Brand")],
+            ),
+        ];
+
+        for (symbol, new_name, expected) in test_cases {
+            let mut breadboard = bnb_parser::parse(SOURCE).unwrap();
+            rename(&mut breadboard, &symbol, new_name).unwrap();
+
+            let printed = bnb_parser::print(&breadboard);
+            for (place, line) in expected {
+                let place = owner(&breadboard, place).unwrap().get(&breadboard);
+                let printed = bnb_parser::print(&bnb_ast::Breadboard {
+                    imports: vec![],
+                    places: vec![place.clone()],
+                    components: vec![],
+                    flows: vec![],
+                });
+
+                assert!(printed.contains(line), "{symbol}: {line:?} in {printed}");
+            }
+
+            assert!(!printed.contains(match &symbol {
+                Symbol::Place(name) | Symbol::Component(name) => name,
+                Symbol::Affordance { affordance, .. } => affordance,
+            }));
+        }
+    }
+
+    #[test]
+    fn test_rename_errors() {
+        let mut breadboard = bnb_parser::parse(SOURCE).unwrap();
+
+        let test_cases = vec![
+            (
+                Symbol::Place("Nowhere".to_owned()),
+                "Somewhere",
+                Error::NotFound(Symbol::Place("Nowhere".to_owned())),
+            ),
+            (
+                Symbol::Place("Home".to_owned()),
+                "Registration",
+                Error::AlreadyExists(Symbol::Place("Registration".to_owned())),
+            ),
+            (
+                Symbol::Component("Header".to_owned()),
+                "Menu",
+                Error::AlreadyExists(Symbol::Component("Menu".to_owned())),
+            ),
+            (
+                Symbol::Affordance {
+                    place: "Home".to_owned(),
+                    affordance: "Logo".to_owned(),
+                },
+                "Brand",
+                Error::NotFound(Symbol::Affordance {
+                    place: "Home".to_owned(),
+                    affordance: "Logo".to_owned(),
+                }),
+            ),
+            (
+                Symbol::Place("Home".to_owned()),
+                " Home",
+                Error::InvalidName(" Home".to_owned()),
+            ),
+            (
+                Symbol::Place("Home".to_owned()),
+                "Ho\nme",
+                Error::InvalidName("Ho\nme".to_owned()),
+            ),
+        ];
+
+        for (symbol, new_name, expected) in test_cases {
+            assert_eq!(rename(&mut breadboard, &symbol, new_name), Err(expected));
+        }
+
+        assert_eq!(breadboard, bnb_parser::parse(SOURCE).unwrap());
+    }
+
+    #[test]
+    fn test_move_affordance() {
+        let moved = |place, name, to| {
+            let mut breadboard = bnb_parser::parse(SOURCE).unwrap();
+            move_affordance(&mut breadboard, place, name, to).unwrap();
+
+            breadboard
+        };
+
+        let breadboard = moved("Menu", "Item", "Registration");
+        let registration = bnb_parser::print(&bnb_ast::Breadboard {
+            imports: vec![],
+            places: vec![breadboard.place("Registration").unwrap().clone()],
+            components: vec![],
+            flows: vec![],
+        });
+        assert_eq!(registration.matches("-> Registration.Item\n").count(), 2);
+        assert!(registration.ends_with("  Item -> Registration\n"));
+
+        let menu = &breadboard.components[1];
+        assert!(menu.items.is_empty());
+        assert!(menu.sketch.as_ref().unwrap().areas.is_empty());
+
+        let breadboard = moved("Menu", "Item", "Header");
+        let header = &breadboard.components[0];
+        assert!(has_affordance(header, "Item"));
+        assert!(bnb_parser::print(&breadboard).contains("-> Home.Item\n"));
+        assert!(breadboard.components[1]
+            .sketch
+            .as_ref()
+            .unwrap()
+            .areas
+            .is_empty());
+    }
+
+    #[test]
+    fn test_move_affordance_flow() {
+        let source = indoc! {"
+            place Home
+              Go -> Away
+              Help -> Home
+              sketch home.png
+                [0,0 10,10] Help
+
+            place Away
+              Back -> Home.Help
+
+            flow Around
+              Home: Go
+              Home: Help
+        "};
+        let mut breadboard = bnb_parser::parse(source).unwrap();
+        move_affordance(&mut breadboard, "Home", "Help", "Away").unwrap();
+
+        assert_eq!(
+            bnb_parser::print(&breadboard),
+            indoc! {"
+                place Home
+                  Go -> Away
+
+                  sketch home.png
+
+                place Away
+                  Back -> Away.Help
+                  Help -> Home
+
+                flow Around
+                  Home: Go
+                  Away: Help
+            "}
+        );
+    }
+
+    #[test]
+    fn test_move_affordance_errors() {
+        let source = indoc! {"
+            place Home
+              Go -> Away.Help
+              > Nested -> Home
+
+            place Away
+              include Bar
+              Help -> Home
+
+            component Bar
+              Logo -> Home
+
+            component Spare
+              Logo -> Home
+        "};
+        let mut breadboard = bnb_parser::parse(source).unwrap();
+        let affordance = |place: &str, affordance: &str| Symbol::Affordance {
+            place: place.to_owned(),
+            affordance: affordance.to_owned(),
+        };
+
+        let test_cases = vec![
+            (
+                ("Home", "Help", "Away"),
+                Error::NotFound(affordance("Home", "Help")),
+            ),
+            (
+                ("Away", "Help", "Nowhere"),
+                Error::NotFound(Symbol::Place("Nowhere".to_owned())),
+            ),
+            (
+                ("Bar", "Logo", "Spare"),
+                Error::AlreadyExists(affordance("Spare", "Logo")),
+            ),
+            (
+                ("Home", "Go", "Away"),
+                Error::Nested(affordance("Home", "Go")),
+            ),
+            (
+                ("Away", "Help", "Spare"),
+                Error::Unreachable {
+                    symbol: affordance("Away", "Help"),
+                    to: "Spare".to_owned(),
+                },
+            ),
+        ];
+
+        for ((place, name, to), expected) in test_cases {
+            assert_eq!(
+                move_affordance(&mut breadboard, place, name, to),
+                Err(expected)
+            );
+        }
+
+        assert_eq!(breadboard, bnb_parser::parse(source).unwrap());
+    }
+}
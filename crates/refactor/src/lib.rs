@@ -0,0 +1,103 @@
+//! # Bread'n'Butter Refactoring
+//!
+//! **A buttery smooth refactoring experience.**
+//!
+//! The `bnb_refactor` crate renames places, components and affordances, and updates every
+//! reference to them:
+//!
+//! - Renaming a place updates every connection targeting it, and every `position` coordinate
+//!   relative to it.
+//! - Renaming a component updates every `include` of it.
+//! - Renaming an affordance updates the sketch areas referencing it, both in its own place and in
+//!   the places (transitively) including its component.
+//!
+//! Renames can be applied to a [`Breadboard`] through [`rename`], or to DSL source through
+//! [`rename_source`], which produces text [`Edit`]s that keep the formatting and comments of the
+//! source intact.
+//!
+//! Affordances can also be moved to another place or component, through [`move_affordance`] and
+//! [`move_affordance_source`]. Connections and flow steps that use the affordance through
+//! `Place.Affordance` follow it to its new place, and sketch areas that can no longer reference
+//! it are removed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use bnb_refactor::{apply, rename_source, Symbol};
+//!
+//! let source = "place Home\n  // Leave.\n  Go -> Away\n\nplace Away\n";
+//! let edits = rename_source(source, &Symbol::Place("Away".to_owned()), "Far -> Away").unwrap();
+//!
+//! assert_eq!(
+//!     apply(source, &edits),
+//!     "place Home\n  // Leave.\n  Go -> \"Far -> Away\"\n\nplace Far -> Away\n"
+//! );
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+mod ast;
+mod source;
+
+use std::fmt;
+
+pub use ast::{move_affordance, rename};
+pub use source::{apply, move_affordance_source, rename_source, Edit};
+
+/// A place, component or affordance to refactor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Symbol {
+    /// The place with the given name.
+    Place(String),
+
+    /// The component with the given name.
+    Component(String),
+
+    /// An affordance defined directly in a place or component.
+    ///
+    /// If both a place and a component with the given name exist, the place is used.
+    Affordance { place: String, affordance: String },
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Place(place) => write!(f, "place `{place}`"),
+            Self::Component(component) => write!(f, "component `{component}`"),
+            Self::Affordance { place, affordance } => {
+                write!(f, "affordance `{affordance}` in `{place}`")
+            }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The symbol to rename does not exist.
+    #[error("{0} does not exist")]
+    NotFound(Symbol),
+
+    /// A symbol with the new name already exists.
+    #[error("{0} already exists")]
+    AlreadyExists(Symbol),
+
+    /// The new name cannot be written in every position the symbol is referenced from.
+    #[error("invalid name: {0:?}")]
+    InvalidName(String),
+
+    /// The affordance to move has affordances or includes nested under it.
+    #[error("{0} has nested items")]
+    Nested(Symbol),
+
+    /// The affordance to move is used through a place that does not include its new component.
+    #[error("{symbol} is used through a place that does not include `{to}`")]
+    Unreachable { symbol: Symbol, to: String },
+
+    /// The source cannot be edited to match the refactored breadboard, e.g. because of its layout.
+    #[error("{0} cannot be refactored in the source")]
+    Unsupported(Symbol),
+
+    /// The source to refactor does not parse.
+    #[error(transparent)]
+    Parse(#[from] bnb_parser::Error),
+}
@@ -0,0 +1,728 @@
+//! Refactoring of DSL source, through text edits.
+
+use std::ops::Range;
+
+use bnb_ast::Breadboard;
+use bnb_parser::{print_name_as, tokenize, NameKind, Token, TokenKind};
+
+use crate::{
+    ast::{self, Location},
+    move_affordance, rename, Error, Symbol,
+};
+
+/// A replacement of a range of source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// The byte range of the source text to replace.
+    pub range: Range<usize>,
+
+    /// The text to replace the range with.
+    pub new_text: String,
+}
+
+/// Computes the edits needed to rename a place, component or affordance in DSL source, and
+/// update every reference to it.
+///
+/// Only the names themselves are replaced, so formatting and comments are kept intact. New names
+/// are quoted wherever needed for them to parse correctly.
+///
+/// The edits are returned in source order, and do not overlap. Use [`apply`] to apply them.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] if the source does not parse, and any error returned by [`rename`].
+/// If the edited source does not parse into the renamed breadboard, [`Error::InvalidName`] is
+/// returned.
+pub fn rename_source(source: &str, symbol: &Symbol, new_name: &str) -> Result<Vec<Edit>, Error> {
+    let breadboard = bnb_parser::parse(source)?;
+
+    let mut expected = breadboard.clone();
+    rename(&mut expected, symbol, new_name)?;
+
+    // The definitions whose affordances or sketch areas are renamed.
+    let definition = |location| definition(&breadboard, location);
+    let owner = match symbol {
+        Symbol::Affordance { place, .. } => ast::owner(&breadboard, place),
+        Symbol::Place(_) | Symbol::Component(_) => None,
+    };
+    let affected = owner
+        .map(|owner| ast::affected_by_affordance(&breadboard, owner))
+        .unwrap_or_default();
+    let targets = ast::target_places(&breadboard, &affected);
+    let areas: Vec<_> = affected.into_iter().map(definition).collect();
+    let owner = owner.map(definition);
+
+    let mut edits = vec![];
+    let mut current = None;
+    let mut target = None;
+
+    for token in tokenize(source) {
+        let (TokenKind::Name(kind) | TokenKind::QuotedString(kind)) = token.kind else {
+            continue;
+        };
+        let name = token.name(source).unwrap_or_default();
+
+        if matches!(kind, NameKind::Place | NameKind::Component) {
+            current = Some((kind, name.clone()));
+        }
+        if matches!(kind, NameKind::Target | NameKind::StepPlace) {
+            target = Some(name.clone());
+        }
+
+        let definition = current.as_ref().map(|(kind, name)| (*kind, name.as_ref()));
+
+        let renamed = match symbol {
+            Symbol::Place(place) => {
+                matches!(
+                    kind,
+                    NameKind::Place | NameKind::Target | NameKind::Coordinate | NameKind::StepPlace
+                ) && name == *place
+            }
+            Symbol::Component(component) => {
+                matches!(kind, NameKind::Component | NameKind::Reference) && name == *component
+            }
+            Symbol::Affordance { affordance, .. } => {
+                name == *affordance
+                    && match kind {
+                        NameKind::Affordance => definition == owner,
+                        NameKind::Area => {
+                            definition.is_some_and(|definition| areas.contains(&definition))
+                        }
+                        NameKind::TargetAffordance | NameKind::StepAffordance => target
+                            .as_ref()
+                            .is_some_and(|target| targets.iter().any(|place| place == target)),
+                        _ => false,
+                    }
+            }
+        };
+
+        if renamed {
+            edits.push(Edit {
+                range: token.span,
+                new_text: print_name_as(new_name, kind),
+            });
+        }
+    }
+
+    // Make sure the edited source means exactly what was intended.
+    if bnb_parser::parse(&apply(source, &edits)).ok() != Some(expected) {
+        return Err(Error::InvalidName(new_name.to_owned()));
+    }
+
+    Ok(edits)
+}
+
+/// Computes the edits needed to move an affordance to another place or component in DSL source,
+/// and update every reference to it, see [`move_affordance`].
+///
+/// The lines of the affordance, including its description and the lines of its connections, are
+/// moved to the end of the items of the new place, keeping their formatting and comments intact.
+///
+/// The edits are returned in source order, and do not overlap. Use [`apply`] to apply them.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] if the source does not parse, and any error returned by
+/// [`move_affordance`]. If the edited source does not parse into the refactored breadboard,
+/// [`Error::Unsupported`] is returned.
+pub fn move_affordance_source(
+    source: &str,
+    place: &str,
+    affordance: &str,
+    to: &str,
+) -> Result<Vec<Edit>, Error> {
+    let breadboard = bnb_parser::parse(source)?;
+
+    let mut expected = breadboard.clone();
+    move_affordance(&mut expected, place, affordance, to)?;
+    if expected == breadboard {
+        return Ok(vec![]);
+    }
+
+    let unsupported = || {
+        Error::Unsupported(Symbol::Affordance {
+            place: place.to_owned(),
+            affordance: affordance.to_owned(),
+        })
+    };
+
+    // Both exist, or the move would have failed.
+    let owner = ast::owner(&breadboard, place).ok_or_else(unsupported)?;
+    let destination = ast::owner(&breadboard, to).ok_or_else(unsupported)?;
+
+    let affected = ast::affected_by_affordance(&breadboard, owner);
+    let lost = ast::lost_affordance(
+        &expected,
+        &ast::target_places(&breadboard, &affected),
+        affordance,
+    );
+    let areas: Vec<_> = affected
+        .into_iter()
+        .filter(|location| {
+            !ast::has_unique_affordance(&expected, location.get(&expected), affordance)
+        })
+        .map(|location| definition(&breadboard, location))
+        .collect();
+    let owner = definition(&breadboard, owner);
+    let destination = definition(&breadboard, destination);
+
+    let tokens = tokenize(source);
+    let lines = Lines::new(source, &tokens);
+
+    let mut edits = vec![];
+    let mut current = None;
+    let mut target = None;
+    let mut line = None;
+    let mut header = None;
+
+    for token in &tokens {
+        let (TokenKind::Name(kind) | TokenKind::QuotedString(kind)) = token.kind else {
+            continue;
+        };
+        let name = token.name(source).unwrap_or_default();
+
+        if matches!(kind, NameKind::Place | NameKind::Component) {
+            current = Some((kind, name.clone()));
+
+            if (kind, name.as_ref()) == destination && header.is_none() {
+                header = Some(lines.line(token.span.start));
+            }
+        }
+        if matches!(kind, NameKind::Target | NameKind::StepPlace) {
+            target = Some((token.span.clone(), kind, name.clone()));
+        }
+
+        let definition = current.as_ref().map(|(kind, name)| (*kind, name.as_ref()));
+        if name != affordance {
+            continue;
+        }
+
+        match kind {
+            NameKind::Affordance if definition == Some(owner) => {
+                line = Some(lines.line(token.span.start));
+            }
+            NameKind::Area if definition.is_some_and(|definition| areas.contains(&definition)) => {
+                edits.push(Edit {
+                    range: lines.range(lines.line(token.span.start)),
+                    new_text: String::new(),
+                });
+            }
+            NameKind::TargetAffordance | NameKind::StepAffordance => {
+                let Some((range, kind, place)) = &target else {
+                    continue;
+                };
+
+                if lost.iter().any(|lost| lost == place) {
+                    edits.push(Edit {
+                        range: range.clone(),
+                        new_text: print_name_as(to, *kind),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(line), Some(header)) = (line, header) else {
+        return Err(unsupported());
+    };
+
+    let block = lines.block(line);
+    let (mut edits, mut text) = moved_text(source, &lines, line, &block, edits);
+
+    let at = lines.range(lines.end_of_items(header)).end;
+    if !source[..at].ends_with('\n') {
+        text = format!("\n{}", text.trim_end_matches('\n'));
+    }
+
+    edits.push(Edit {
+        range: block,
+        new_text: String::new(),
+    });
+    edits.push(Edit {
+        range: at..at,
+        new_text: text,
+    });
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    // Make sure the edited source means exactly what was intended.
+    if bnb_parser::parse(&apply(source, &edits)).ok() != Some(expected) {
+        return Err(unsupported());
+    }
+
+    Ok(edits)
+}
+
+/// Returns the edits outside of the block of lines of a moved affordance, and the text of the
+/// block with the edits within it applied, moved to the top level.
+fn moved_text(
+    source: &str,
+    lines: &Lines<'_>,
+    line: usize,
+    block: &Range<usize>,
+    edits: Vec<Edit>,
+) -> (Vec<Edit>, String) {
+    // Edits within the moved lines are applied to the moved text.
+    let (inner, edits): (Vec<_>, Vec<_>) = edits
+        .into_iter()
+        .partition(|edit| block.start <= edit.range.start && edit.range.end <= block.end);
+    let inner: Vec<_> = inner
+        .into_iter()
+        .map(|edit| Edit {
+            range: edit.range.start - block.start..edit.range.end - block.start,
+            new_text: edit.new_text,
+        })
+        .collect();
+    let text = apply(&source[block.clone()], &inner);
+
+    // The affordance is moved to the top level.
+    let item = lines.line(block.start).abs_diff(line);
+    let mut text: String = text
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(i, text)| {
+            if i == item {
+                unnest(text)
+            } else {
+                text.to_owned()
+            }
+        })
+        .collect();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    (edits, text)
+}
+
+/// Returns the kind and name of the definition of a place or component.
+fn definition(breadboard: &Breadboard, location: Location) -> (NameKind, &str) {
+    let kind = match location {
+        Location::Place(_) => NameKind::Place,
+        Location::Component(_) => NameKind::Component,
+    };
+
+    (kind, location.get(breadboard).name.as_str())
+}
+
+/// Removes the nesting level markers from the line of an affordance.
+fn unnest(line: &str) -> String {
+    let name = line.trim_start();
+    let indent = &line[..line.len() - name.len()];
+
+    if !name.starts_with('>') {
+        return line.to_owned();
+    }
+
+    format!("{indent}{}", name.trim_start_matches(['>', ' ', '\t']))
+}
+
+/// The lines of a source, classified by their first token.
+struct Lines<'a> {
+    ranges: Vec<Range<usize>>,
+    first: Vec<Option<&'a Token>>,
+    source: &'a str,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str, tokens: &'a [Token]) -> Self {
+        let mut ranges = vec![];
+        let mut start = 0;
+        for (end, _) in source.match_indices('\n') {
+            ranges.push(start..end + 1);
+            start = end + 1;
+        }
+        if start < source.len() {
+            ranges.push(start..source.len());
+        }
+
+        let mut lines = Self {
+            first: vec![None; ranges.len()],
+            ranges,
+            source,
+        };
+        for token in tokens {
+            let line = lines.line(token.span.start);
+            lines.first[line].get_or_insert(token);
+        }
+
+        lines
+    }
+
+    /// Returns the index of the line containing the offset.
+    fn line(&self, offset: usize) -> usize {
+        self.ranges.partition_point(|range| range.end <= offset)
+    }
+
+    /// Returns the byte range of the line, including its newline.
+    fn range(&self, line: usize) -> Range<usize> {
+        self.ranges[line].clone()
+    }
+
+    fn kind(&self, line: usize) -> Option<TokenKind> {
+        self.first
+            .get(line)
+            .copied()
+            .flatten()
+            .map(|token| token.kind)
+    }
+
+    fn is_comment(&self, line: usize) -> bool {
+        matches!(
+            self.kind(line),
+            Some(TokenKind::Description | TokenKind::Comment)
+        )
+    }
+
+    /// Returns the byte range of the lines of the item on the given line: its description and
+    /// comments, and the lines of its connections.
+    fn block(&self, line: usize) -> Range<usize> {
+        let mut start = line;
+        while start > 0 && self.is_comment(start - 1) {
+            start -= 1;
+        }
+
+        let mut end = line;
+        let mut next = line + 1;
+        while next < self.ranges.len() {
+            if self.is_comment(next) {
+                next += 1;
+            } else if self.kind(next) == Some(TokenKind::Arrow) {
+                end = next;
+                next += 1;
+            } else {
+                break;
+            }
+        }
+
+        self.ranges[start].start..self.ranges[end].end
+    }
+
+    /// Returns the last line of the attributes and items of the definition on the given line.
+    fn end_of_items(&self, header: usize) -> usize {
+        let mut last = header;
+
+        for line in header + 1..self.ranges.len() {
+            let Some(token) = self.first[line] else {
+                break;
+            };
+
+            match token.kind {
+                TokenKind::Description | TokenKind::Comment => {}
+                TokenKind::Keyword if token.text(self.source) != "include" => break,
+                TokenKind::Keyword
+                | TokenKind::Level
+                | TokenKind::Arrow
+                | TokenKind::AttributeKey
+                | TokenKind::Name(NameKind::Affordance)
+                | TokenKind::QuotedString(NameKind::Affordance) => last = line,
+                _ => break,
+            }
+        }
+
+        last
+    }
+}
+
+/// Applies non-overlapping edits, in source order, to the source.
+///
+/// # Panics
+///
+/// Panics if the edits overlap, are out of order, or do not fall on character boundaries of the
+/// source.
+#[must_use]
+pub fn apply(source: &str, edits: &[Edit]) -> String {
+    let mut out = String::with_capacity(source.len());
+
+    let mut offset = 0;
+    for edit in edits {
+        out.push_str(&source[offset..edit.range.start]);
+        out.push_str(&edit.new_text);
+        offset = edit.range.end;
+    }
+
+    out.push_str(&source[offset..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const SOURCE: &str = indoc! {r#"
+        /// The start.
+        place Home
+          include Header
+          // Leave.
+          Sign Up -> (go) Registration
+                  -> "Home"
+          position < Registration - 10, 0
+          sketch home.png
+            [0,0 10,10] Logo
+
+        place Registration
+          Logo
+          Back -> Home
+          Top -> Home.Logo
+
+        component Header
+          Logo -> Home
+    "#};
+
+    fn renamed(symbol: &Symbol, new_name: &str) -> String {
+        let edits = rename_source(SOURCE, symbol, new_name).unwrap();
+        apply(SOURCE, &edits)
+    }
+
+    #[test]
+    fn test_rename_source() {
+        assert_eq!(
+            renamed(&Symbol::Place("Registration".to_owned()), "Sign-Up, Now"),
+            indoc! {r#"
+                /// The start.
+                place Home
+                  include Header
+                  // Leave.
+                  Sign Up -> (go) Sign-Up, Now
+                          -> "Home"
+                  position < "Sign-Up, Now" - 10, 0
+                  sketch home.png
+                    [0,0 10,10] Logo
+
+                place Sign-Up, Now
+                  Logo
+                  Back -> Home
+                  Top -> Home.Logo
+
+                component Header
+                  Logo -> Home
+            "#}
+        );
+
+        assert_eq!(
+            renamed(&Symbol::Place("Home".to_owned()), "Start -> Here"),
+            indoc! {r#"
+                /// The start.
+                place Start -> Here
+                  include Header
+                  // Leave.
+                  Sign Up -> (go) Registration
+                          -> "Start -> Here"
+                  position < Registration - 10, 0
+                  sketch home.png
+                    [0,0 10,10] Logo
+
+                place Registration
+                  Logo
+                  Back -> "Start -> Here"
+                  Top -> "Start -> Here".Logo
+
+                component Header
+                  Logo -> "Start -> Here"
+            "#}
+        );
+
+        let header = renamed(
+            &Symbol::Affordance {
+                place: "Header".to_owned(),
+                affordance: "Logo".to_owned(),
+            },
+            "Brand",
+        );
+        assert!(header.contains("[0,0 10,10] Brand\n"));
+        assert!(header.contains("Top -> Home.Brand\n"));
+        assert!(header.contains("Registration\n  Logo\n"));
+        assert!(header.contains("Header\n  Brand -> Home\n"));
+
+        let quoted = renamed(&Symbol::Place("Home".to_owned()), "\"Hi\" -> Now");
+        assert!(quoted.contains("place \"\\\"Hi\\\" -> Now\"\n"));
+        assert!(quoted.contains("Back -> \"\\\"Hi\\\" -> Now\"\n"));
+
+        let component = renamed(&Symbol::Component("Header".to_owned()), "Top");
+        assert!(component.contains("include Top\n"));
+        assert!(component.contains("component Top\n"));
+    }
+
+    #[test]
+    fn test_rename_flow_source() {
+        let source = indoc! {"
+            place Home
+              include Header
+              Go -> Home
+
+            component Header
+              Logo -> Home
+
+            flow Around
+              Home: Go
+              Home: Logo
+        "};
+        let renamed = |symbol: &Symbol, new_name| {
+            apply(source, &rename_source(source, symbol, new_name).unwrap())
+        };
+
+        let place = renamed(&Symbol::Place("Home".to_owned()), "Start: Here");
+        assert!(place.contains("  \"Start: Here\": Go\n  \"Start: Here\": Logo\n"));
+
+        let affordance = renamed(
+            &Symbol::Affordance {
+                place: "Header".to_owned(),
+                affordance: "Logo".to_owned(),
+            },
+            "Brand",
+        );
+        assert!(affordance.contains("  Home: Go\n  Home: Brand\n"));
+    }
+
+    #[test]
+    fn test_rename_source_errors() {
+        let rename = |symbol, new_name| rename_source(SOURCE, &symbol, new_name).unwrap_err();
+
+        assert_eq!(
+            rename(Symbol::Component("Footer".to_owned()), "Bottom"),
+            Error::NotFound(Symbol::Component("Footer".to_owned()))
+        );
+        assert_eq!(
+            rename_source("place", &Symbol::Place("Home".to_owned()), "Start").unwrap_err(),
+            Error::Parse(bnb_parser::Error::MissingPlaceName)
+        );
+    }
+
+    #[test]
+    fn test_move_affordance_source() {
+        let moved = |place, affordance, to| {
+            apply(
+                SOURCE,
+                &move_affordance_source(SOURCE, place, affordance, to).unwrap(),
+            )
+        };
+
+        assert_eq!(
+            moved("Header", "Logo", "Home"),
+            indoc! {r#"
+                /// The start.
+                place Home
+                  include Header
+                  // Leave.
+                  Sign Up -> (go) Registration
+                          -> "Home"
+                  Logo -> Home
+                  position < Registration - 10, 0
+                  sketch home.png
+                    [0,0 10,10] Logo
+
+                place Registration
+                  Logo
+                  Back -> Home
+                  Top -> Home.Logo
+
+                component Header
+            "#}
+        );
+        assert_eq!(
+            moved("Home", "Sign Up", "Registration"),
+            indoc! {r#"
+                /// The start.
+                place Home
+                  include Header
+                  position < Registration - 10, 0
+                  sketch home.png
+                    [0,0 10,10] Logo
+
+                place Registration
+                  Logo
+                  Back -> Home
+                  Top -> Home.Logo
+                  // Leave.
+                  Sign Up -> (go) Registration
+                          -> "Home"
+
+                component Header
+                  Logo -> Home
+            "#}
+        );
+        assert_eq!(
+            apply(
+                "place Home\n  Go -> Away\n\nplace Away",
+                &move_affordance_source(
+                    "place Home\n  Go -> Away\n\nplace Away",
+                    "Home",
+                    "Go",
+                    "Away"
+                )
+                .unwrap()
+            ),
+            "place Home\n\nplace Away\n  Go -> Away"
+        );
+    }
+
+    #[test]
+    fn test_move_affordance_references_source() {
+        let source = indoc! {"
+            place Home
+              Go -> Away
+              > Help -> Home.Help
+              Other -> Home
+              sketch home.png
+                [0,0 10,10] Help
+                [0,0 10,10] Other
+
+            place Away
+              Back -> Home.Help
+
+            flow Around
+              Home: Help
+        "};
+        let edits = move_affordance_source(source, "Home", "Help", "Away").unwrap();
+
+        assert_eq!(
+            apply(source, &edits),
+            indoc! {"
+                place Home
+                  Go -> Away
+                  Other -> Home
+                  sketch home.png
+                    [0,0 10,10] Other
+
+                place Away
+                  Back -> Away.Help
+                  Help -> Away.Help
+
+                flow Around
+                  Away: Help
+            "}
+        );
+    }
+
+    #[test]
+    fn test_move_affordance_source_errors() {
+        let symbol = |place: &str, affordance: &str| Symbol::Affordance {
+            place: place.to_owned(),
+            affordance: affordance.to_owned(),
+        };
+
+        assert_eq!(
+            move_affordance_source(SOURCE, "Header", "Logo", "Registration").unwrap_err(),
+            Error::AlreadyExists(symbol("Registration", "Logo"))
+        );
+        assert_eq!(
+            move_affordance_source(SOURCE, "Home", "Top", "Registration").unwrap_err(),
+            Error::NotFound(symbol("Home", "Top"))
+        );
+        assert_eq!(
+            move_affordance_source(
+                "place Home\n  Go -> Home.Go\n\ncomponent Bar\n",
+                "Home",
+                "Go",
+                "Bar"
+            )
+            .unwrap_err(),
+            Error::Unreachable {
+                symbol: symbol("Home", "Go"),
+                to: "Bar".to_owned(),
+            }
+        );
+    }
+}
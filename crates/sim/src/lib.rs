@@ -0,0 +1,405 @@
+//! # Bread'n'Butter Simulator
+//!
+//! **A buttery smooth rehearsal experience.**
+//!
+//! The `bnb_sim` crate walks through a [`Breadboard`] without a UI, as a state machine with the
+//! places as its states. A [`Simulator`] tracks the current place, lists the affordances available
+//! in it (including those of the components it includes), and triggers them to follow their
+//! connections. Every transition is recorded as a [`Step`] of the trace.
+//!
+//! An affordance with multiple connections leads to one of them, chosen by the description of the
+//! connection (e.g. `success` or `failure`), or by the name of its target place.
+//!
+//! The `bnb-sim` binary is an interactive REPL on top of the simulator, to rehearse flows.
+//!
+//! ## Examples
+//!
+//! Scripted paths can be checked in tests:
+//!
+//! ```
+//! use bnb_sim::Simulator;
+//!
+//! let breadboard = bnb_parser::parse(
+//!     "place Login\n  Sign In -> (success) Home\n          -> (failure) Login\n\nplace Home\n",
+//! )
+//! .unwrap();
+//!
+//! let mut simulator = Simulator::new(&breadboard, "Login").unwrap();
+//! simulator.trigger("Sign In", Some("failure")).unwrap();
+//! simulator.trigger("Sign In", Some("success")).unwrap();
+//!
+//! assert_eq!(simulator.place().name, "Home");
+//! assert_eq!(simulator.trace().len(), 2);
+//! ```
+//!
+//! [`Breadboard`]: bnb_ast::Breadboard
+
+use std::fmt;
+
+use bnb_ast::{Affordance, Breadboard, Connection, Place};
+
+/// Walks through a breadboard, one affordance at a time.
+#[derive(Debug, Clone)]
+pub struct Simulator<'a> {
+    breadboard: &'a Breadboard,
+    entry: &'a Place,
+    place: &'a Place,
+    trace: Vec<Step>,
+}
+
+impl<'a> Simulator<'a> {
+    /// Creates a simulator of the breadboard, starting at the entry place.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownPlace`] if the entry place does not exist.
+    pub fn new(breadboard: &'a Breadboard, entry: &str) -> Result<Self, Error> {
+        let entry = breadboard.place(entry).ok_or_else(|| Error::UnknownPlace {
+            place: entry.to_owned(),
+        })?;
+
+        Ok(Self {
+            breadboard,
+            entry,
+            place: entry,
+            trace: vec![],
+        })
+    }
+
+    /// Returns the current place.
+    #[must_use]
+    pub fn place(&self) -> &'a Place {
+        self.place
+    }
+
+    /// Returns the affordances of the current place, including those of the components it
+    /// includes, in order. Affordances that share a name are combined into one.
+    #[must_use]
+    pub fn affordances(&self) -> Vec<Affordance> {
+        self.breadboard.unique_affordances(self.place)
+    }
+
+    /// Returns the steps taken since the start, or the last [`reset`](Self::reset).
+    #[must_use]
+    pub fn trace(&self) -> &[Step] {
+        &self.trace
+    }
+
+    /// Goes back to the entry place, and clears the trace.
+    pub fn reset(&mut self) {
+        self.place = self.entry;
+        self.trace.clear();
+    }
+
+    /// Triggers the affordance of the current place, and follows its connection.
+    ///
+    /// If the affordance has multiple connections, `choice` selects one of them by its description,
+    /// or by the name of its target place. If it has a single connection, `choice` can be omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current place has no such affordance, if the affordance has no
+    /// connections, if no single connection matches the choice, or if the connection leads to an
+    /// unknown place. The simulator stays in the current place in that case.
+    pub fn trigger(&mut self, affordance: &str, choice: Option<&str>) -> Result<&Step, Error> {
+        let place = &self.place.name;
+        let affordances = self.affordances();
+        let Some(affordance) = affordances.iter().find(|a| a.name == affordance) else {
+            return Err(Error::UnknownAffordance {
+                place: place.clone(),
+                affordance: affordance.to_owned(),
+            });
+        };
+
+        let connection = choose(&affordance.connections, choice).map_err(|reason| {
+            let place = place.clone();
+            let affordance = affordance.name.clone();
+
+            match reason {
+                Reason::None => Error::NoConnections { place, affordance },
+                Reason::Unmatched(choice) => Error::UnknownChoice {
+                    place,
+                    affordance,
+                    choice,
+                },
+                Reason::Ambiguous(choices) => Error::Ambiguous {
+                    place,
+                    affordance,
+                    choices,
+                },
+            }
+        })?;
+
+        let target = self
+            .breadboard
+            .place(&connection.target_place)
+            .ok_or_else(|| Error::UnknownPlace {
+                place: connection.target_place.clone(),
+            })?;
+
+        let index = self.trace.len();
+        self.trace.push(Step {
+            from: place.clone(),
+            affordance: affordance.name.clone(),
+            description: connection.description.clone(),
+            to: target.name.clone(),
+        });
+        self.place = target;
+
+        Ok(&self.trace[index])
+    }
+}
+
+/// Why no single connection could be chosen.
+enum Reason {
+    None,
+    Unmatched(String),
+    Ambiguous(Vec<String>),
+}
+
+/// Chooses one of the connections by its description, or the name of its target place.
+fn choose<'a>(
+    connections: &'a [Connection],
+    choice: Option<&str>,
+) -> Result<&'a Connection, Reason> {
+    if connections.is_empty() {
+        return Err(Reason::None);
+    }
+
+    let matches: Vec<_> = match choice {
+        Some(choice) => {
+            let by_description: Vec<_> = connections
+                .iter()
+                .filter(|c| c.description.as_deref() == Some(choice))
+                .collect();
+
+            if by_description.is_empty() {
+                connections
+                    .iter()
+                    .filter(|c| c.target_place == choice)
+                    .collect()
+            } else {
+                by_description
+            }
+        }
+        None => connections.iter().collect(),
+    };
+
+    match matches.as_slice() {
+        [connection] => Ok(connection),
+        // Without a choice, all connections match.
+        [] => Err(Reason::Unmatched(choice.unwrap_or_default().to_owned())),
+        matches => Err(Reason::Ambiguous(
+            matches.iter().map(|c| choice_of(c)).collect(),
+        )),
+    }
+}
+
+/// Returns the choice that selects the connection: its description, or its target place.
+fn choice_of(connection: &Connection) -> String {
+    connection
+        .description
+        .clone()
+        .unwrap_or_else(|| connection.target_place.clone())
+}
+
+/// A transition from one place to another, recorded by the [`Simulator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The place the affordance was triggered in.
+    pub from: String,
+
+    /// The affordance that was triggered.
+    pub affordance: String,
+
+    /// The description of the connection that was followed.
+    pub description: Option<String>,
+
+    /// The place the connection led to.
+    pub to: String,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> ", self.from, self.affordance)?;
+
+        if let Some(description) = &self.description {
+            write!(f, "({description}) ")?;
+        }
+
+        f.write_str(&self.to)
+    }
+}
+
+/// An error that occurred while simulating a breadboard.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("unknown place `{place}`")]
+    UnknownPlace { place: String },
+
+    #[error("place `{place}` has no affordance `{affordance}`")]
+    UnknownAffordance { place: String, affordance: String },
+
+    #[error("affordance `{affordance}` in `{place}` has no connections")]
+    NoConnections { place: String, affordance: String },
+
+    #[error("affordance `{affordance}` in `{place}` has no connection `{choice}`")]
+    UnknownChoice {
+        place: String,
+        affordance: String,
+        choice: String,
+    },
+
+    #[error(
+        "affordance `{affordance}` in `{place}` has multiple connections, choose one of: {}",
+        choices.join(", ")
+    )]
+    Ambiguous {
+        place: String,
+        affordance: String,
+        choices: Vec<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const INPUT: &str = indoc! {"
+        place Login
+          include Header
+          Sign In -> (success) Home
+                  -> (failure) Login
+          Forgot Password -> Reset
+          Cancel
+
+        place Home
+          include Header
+          Sign Out -> Login
+
+        place Reset
+          Send -> Login
+                -> Home
+
+        component Header
+          Logo -> Home
+    "};
+
+    fn breadboard() -> Breadboard {
+        bnb_parser::parse(INPUT).unwrap()
+    }
+
+    #[test]
+    fn test_scripted_path() {
+        let breadboard = breadboard();
+        let mut simulator = Simulator::new(&breadboard, "Login").unwrap();
+
+        simulator.trigger("Sign In", Some("failure")).unwrap();
+        simulator.trigger("Forgot Password", None).unwrap();
+        simulator.trigger("Send", Some("Home")).unwrap();
+        simulator.trigger("Sign Out", None).unwrap();
+        simulator.trigger("Sign In", Some("success")).unwrap();
+
+        let trace: Vec<_> = simulator.trace().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            trace,
+            [
+                "Login: Sign In -> (failure) Login",
+                "Login: Forgot Password -> Reset",
+                "Reset: Send -> Home",
+                "Home: Sign Out -> Login",
+                "Login: Sign In -> (success) Home",
+            ]
+        );
+        assert_eq!(simulator.place().name, "Home");
+    }
+
+    #[test]
+    fn test_included_affordances() {
+        let breadboard = breadboard();
+        let mut simulator = Simulator::new(&breadboard, "Login").unwrap();
+
+        let names: Vec<_> = simulator
+            .affordances()
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names, ["Logo", "Sign In", "Forgot Password", "Cancel"]);
+
+        let step = simulator.trigger("Logo", None).unwrap();
+        assert_eq!(step.to, "Home");
+    }
+
+    #[test]
+    fn test_repeated_affordance() {
+        let breadboard = bnb_parser::parse(
+            "place Home\n  include Header\n  Logo -> Support\n\nplace Support\n\n\
+             component Header\n  Logo -> Home\n",
+        )
+        .unwrap();
+        let mut simulator = Simulator::new(&breadboard, "Home").unwrap();
+
+        assert_eq!(simulator.affordances().len(), 1);
+
+        let step = simulator.trigger("Logo", Some("Support")).unwrap();
+        assert_eq!(step.to, "Support");
+    }
+
+    #[test]
+    fn test_reset() {
+        let breadboard = breadboard();
+        let mut simulator = Simulator::new(&breadboard, "Login").unwrap();
+
+        simulator.trigger("Logo", None).unwrap();
+        simulator.reset();
+
+        assert_eq!(simulator.place().name, "Login");
+        assert!(simulator.trace().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let breadboard = breadboard();
+        let mut simulator = Simulator::new(&breadboard, "Login").unwrap();
+
+        assert_eq!(
+            Simulator::new(&breadboard, "Nowhere").unwrap_err(),
+            Error::UnknownPlace {
+                place: "Nowhere".to_owned()
+            }
+        );
+        assert_eq!(
+            simulator.trigger("Sign Out", None).unwrap_err(),
+            Error::UnknownAffordance {
+                place: "Login".to_owned(),
+                affordance: "Sign Out".to_owned(),
+            }
+        );
+        assert_eq!(
+            simulator.trigger("Cancel", None).unwrap_err(),
+            Error::NoConnections {
+                place: "Login".to_owned(),
+                affordance: "Cancel".to_owned(),
+            }
+        );
+        assert_eq!(
+            simulator.trigger("Sign In", Some("timeout")).unwrap_err(),
+            Error::UnknownChoice {
+                place: "Login".to_owned(),
+                affordance: "Sign In".to_owned(),
+                choice: "timeout".to_owned(),
+            }
+        );
+        assert_eq!(
+            simulator.trigger("Sign In", None).unwrap_err().to_string(),
+            "affordance `Sign In` in `Login` has multiple connections, choose one of: success, \
+             failure"
+        );
+
+        // Failed triggers do not move the simulator.
+        assert_eq!(simulator.place().name, "Login");
+        assert!(simulator.trace().is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! # Bread'n'Butter Simulator REPL
+//!
+//! **Rehearse a breadboard, one affordance at a time.**
+//!
+//! The `bnb-sim` binary starts at the entry place of a breadboard (the first place by default),
+//! lists the affordances available there, and triggers the one that is entered, by name or by
+//! number:
+//!
+//! ```sh
+//! bnb-sim app.bnb Login
+//! ```
+//!
+//! ```text
+//! Login
+//!   1. Sign In -> (success) Home, (failure) Login
+//!   2. Forgot Password -> Reset
+//! > Sign In (failure)
+//! ```
+//!
+//! If an affordance has multiple connections, the connection to follow is chosen in parentheses,
+//! by its description or by the name of its target place.
+//!
+//! Commands start with a colon: `:trace` prints the steps taken so far, `:reset` goes back to the
+//! entry place, `:help` lists the commands, and `:quit` exits. Since input is read line by line,
+//! scripted paths can be piped in as well.
+
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use bnb_ast::{Affordance, Breadboard};
+use bnb_sim::Simulator;
+
+const HELP: &str = "\
+Enter an affordance by name or number, followed by `(choice)` if it has multiple connections.
+
+Commands:
+  :trace  Print the steps taken so far
+  :reset  Go back to the entry place
+  :help   Print this help
+  :quit   Exit";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1);
+    let (Some(path), entry, None) = (args.next().map(PathBuf::from), args.next(), args.next())
+    else {
+        eprintln!("usage: bnb-sim FILE [ENTRY]");
+        return ExitCode::from(2);
+    };
+
+    let breadboard = match bnb_parser::load(&path, &bnb_parser::OsFileSystem) {
+        Ok(breadboard) => breadboard,
+        Err(error) => {
+            eprintln!("bnb-sim: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let entry = entry
+        .map(|entry| entry.to_string_lossy().into_owned())
+        .or_else(|| breadboard.places.first().map(|place| place.name.clone()));
+    let Some(entry) = entry else {
+        eprintln!("bnb-sim: the breadboard has no places");
+        return ExitCode::FAILURE;
+    };
+
+    match run(&breadboard, &entry) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("bnb-sim: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(breadboard: &Breadboard, entry: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut simulator = Simulator::new(breadboard, entry)?;
+    let mut lines = io::stdin().lock().lines();
+    let mut stdout = io::stdout();
+
+    print_place(&simulator);
+
+    loop {
+        print!("> ");
+        stdout.flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(());
+        };
+
+        match line.trim() {
+            "" => {}
+            ":quit" | ":q" => return Ok(()),
+            ":help" | ":h" => println!("{HELP}"),
+            ":trace" | ":t" => {
+                for (i, step) in simulator.trace().iter().enumerate() {
+                    println!("  {}. {step}", i + 1);
+                }
+            }
+            ":reset" | ":r" => {
+                simulator.reset();
+                print_place(&simulator);
+            }
+            command if command.starts_with(':') => {
+                eprintln!("unknown command `{command}`, see `:help`");
+            }
+            input => {
+                let affordances = simulator.affordances();
+                let (affordance, choice) = match parse(input, &affordances) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        eprintln!("{error}");
+                        continue;
+                    }
+                };
+
+                match simulator.trigger(affordance, choice) {
+                    Ok(step) => {
+                        println!("{step}\n");
+                        print_place(&simulator);
+                    }
+                    Err(error) => eprintln!("{error}"),
+                }
+            }
+        }
+    }
+}
+
+/// Splits the input into the affordance, and the choice in parentheses, if any.
+///
+/// Affordance names can contain parentheses or be numbers, e.g. `Help (FAQ)` or `404`, so the
+/// input is looked up as a name first. Only if no affordance has that name, a trailing `(choice)`
+/// is split off, and a number refers to an affordance by its position in the list.
+fn parse<'a>(
+    input: &'a str,
+    affordances: &'a [Affordance],
+) -> Result<(&'a str, Option<&'a str>), String> {
+    let is_name = |name: &str| affordances.iter().any(|affordance| affordance.name == name);
+    if is_name(input) {
+        return Ok((input, None));
+    }
+
+    let (affordance, choice) = input
+        .strip_suffix(')')
+        .and_then(|input| input.rsplit_once('('))
+        .map_or((input, None), |(affordance, choice)| {
+            (affordance.trim_end(), Some(choice.trim()))
+        });
+
+    match affordance.parse::<usize>() {
+        Ok(i) if !is_name(affordance) => affordances
+            .get(i.wrapping_sub(1))
+            .map(|affordance| (affordance.name.as_str(), choice))
+            .ok_or_else(|| format!("no affordance {i}")),
+        _ => Ok((affordance, choice)),
+    }
+}
+
+/// Prints the current place, and its numbered affordances with their connections.
+fn print_place(simulator: &Simulator<'_>) {
+    println!("{}", simulator.place().name);
+
+    for (i, affordance) in simulator.affordances().iter().enumerate() {
+        let connections: Vec<_> = affordance
+            .connections
+            .iter()
+            .map(|connection| match &connection.description {
+                Some(description) => format!("({description}) {}", connection.target_place),
+                None => connection.target_place.clone(),
+            })
+            .collect();
+
+        if connections.is_empty() {
+            println!("  {}. {}", i + 1, affordance.name);
+        } else {
+            println!(
+                "  {}. {} -> {}",
+                i + 1,
+                affordance.name,
+                connections.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let breadboard = bnb_parser::parse(indoc! {r#"
+            place Home
+              "Help (FAQ)" -> Home
+              404 -> Home
+              Sign In -> (success) Home
+                      -> (failure) Home
+        "#})
+        .unwrap();
+        let affordances = Simulator::new(&breadboard, "Home").unwrap().affordances();
+
+        #[rustfmt::skip]
+        let test_cases = vec![
+            ("Help (FAQ)", Ok(("Help (FAQ)", None))),
+            ("404", Ok(("404", None))),
+            ("Sign In (failure)", Ok(("Sign In", Some("failure")))),
+            ("Sign In ( success )", Ok(("Sign In", Some("success")))),
+            ("404 (success)", Ok(("404", Some("success")))),
+            ("1", Ok(("Help (FAQ)", None))),
+            ("3 (failure)", Ok(("Sign In", Some("failure")))),
+            ("4", Err("no affordance 4".to_owned())),
+            ("0", Err("no affordance 0".to_owned())),
+            ("Sign Out", Ok(("Sign Out", None))),
+        ];
+
+        for (input, expected) in test_cases {
+            assert_eq!(parse(input, &affordances), expected, "{input}");
+        }
+    }
+}
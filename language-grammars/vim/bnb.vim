@@ -22,11 +22,13 @@ syn match bnbSketchPath      ".\+" contained nextgroup=bnbClickable skipwhite sk
 syn match bnbAffordance      "\s*\zs[^->]\+" nextgroup=bnbConnection skipwhite
 syn match bnbConnection      "->" contained nextgroup=bnbAffordance,bnbConnectionLabel skipwhite
 syn match bnbConnectionLabel "(.\{-})" contained
-syn match bnbComment         "#.*"
+syn match bnbComment         "//.*"
+syn match bnbDescription     "///.*"
 syn region bnbClickable      start="\[" end="\]" nextgroup=bnbAffordance skipwhite
 
 " Define Highlight Groups
 hi def link bnbComment         Comment
+hi def link bnbDescription     SpecialComment
 hi def link bnbPlace           Keyword
 hi def link bnbPlaceName       Identifier
 hi def link bnbInclude         Include